@@ -11,6 +11,7 @@ use crate::backend::navigator::{NavigationMethod, RequestOptions};
 use crate::context::UpdateContext;
 use crate::display_object::{DisplayObject, MovieClip, TDisplayObject, TDisplayObjectContainer};
 use crate::ecma_conversions::f64_to_wrapping_u32;
+use crate::loader::MovieLoadOptions;
 use crate::string::AvmString;
 use crate::tag_utils::SwfSlice;
 use crate::vminterface::Instantiator;
@@ -1225,7 +1226,6 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         if target.starts_with("_level") && target.len() > 6 {
             match target[6..].parse::<i32>() {
                 Ok(level_id) => {
-                    let fetch = self.context.navigator.fetch(&url, RequestOptions::get());
                     let level = self.resolve_level(level_id);
 
                     if url.is_empty() {
@@ -1237,10 +1237,12 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
                         let process = self.context.load_manager.load_movie_into_clip(
                             self.context.player.clone().unwrap(),
                             level,
-                            fetch,
                             url,
-                            None,
-                            None,
+                            RequestOptions::get(),
+                            MovieLoadOptions {
+                                loader_url: self.loader_url(),
+                                ..Default::default()
+                            },
                         );
                         self.context.navigator.spawn_future(process);
                     }
@@ -1298,26 +1300,38 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         };
 
         if is_load_vars {
-            if let Some(clip_target) = clip_target {
-                let target_obj = clip_target
-                    .as_movie_clip()
-                    .unwrap()
-                    .object()
-                    .coerce_to_object(self);
-                let (url, opts) = self.locals_into_request_options(
-                    Cow::Borrowed(&url),
-                    NavigationMethod::from_send_vars_method(swf_method),
-                );
-                let fetch = self.context.navigator.fetch(&url, opts);
-                let process = self.context.load_manager.load_form_into_object(
-                    self.context.player.clone().unwrap(),
-                    target_obj,
-                    fetch,
-                );
-
-                self.context.navigator.spawn_future(process);
-            }
+            let (url, opts) = self.locals_into_request_options(
+                Cow::Borrowed(&url),
+                NavigationMethod::from_send_vars_method(swf_method),
+            );
+            let player = self.context.player.clone().unwrap();
+            let base_clip = self.target_clip_or_root()?;
+
+            // Target paths and levels are only resolved once the load
+            // completes, so that they may name clips created in the meantime.
+            let process = match target {
+                Value::Object(target) if target.as_display_object().is_some() => self
+                    .context
+                    .load_manager
+                    .load_form_into_object(player, target, &url, opts),
+                _ if is_target_sprite || window_target.starts_with("_level") => {
+                    self.context.load_manager.load_form_into_target(
+                        player,
+                        base_clip,
+                        window_target.to_string(),
+                        &url,
+                        opts,
+                    )
+                }
+                _ => {
+                    let target_obj = base_clip.object().coerce_to_object(self);
+                    self.context
+                        .load_manager
+                        .load_form_into_object(player, target_obj, &url, opts)
+                }
+            };
 
+            self.context.navigator.spawn_future(process);
             return Ok(FrameControl::Continue);
         } else if is_target_sprite {
             if let Some(clip_target) = clip_target {
@@ -1332,14 +1346,16 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
                         mc.replace_with_movie(self.context.gc_context, None)
                     }
                 } else {
-                    let fetch = self.context.navigator.fetch(&url, opts);
                     let process = self.context.load_manager.load_movie_into_clip(
                         self.context.player.clone().unwrap(),
                         clip_target,
-                        fetch,
                         url.to_string(),
-                        None,
-                        None,
+                        opts,
+                        MovieLoadOptions {
+                            loader_url: self.loader_url(),
+                            requesting_clip: Some(self.base_clip()),
+                            ..Default::default()
+                        },
                     );
                     self.context.navigator.spawn_future(process);
                 }
@@ -1349,16 +1365,17 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
             // target of `_level#` indicates a `loadMovieNum` call.
             match window_target[6..].parse::<i32>() {
                 Ok(level_id) => {
-                    let fetch = self.context.navigator.fetch(&url, RequestOptions::get());
                     let level = self.resolve_level(level_id);
 
                     let process = self.context.load_manager.load_movie_into_clip(
                         self.context.player.clone().unwrap(),
                         level,
-                        fetch,
                         url.to_string(),
-                        None,
-                        None,
+                        RequestOptions::get(),
+                        MovieLoadOptions {
+                            loader_url: self.loader_url(),
+                            ..Default::default()
+                        },
                     );
                     self.context.navigator.spawn_future(process);
                 }
@@ -2957,6 +2974,16 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         self.base_clip
     }
 
+    /// The URL that movies loaded by this code report as their `loaderURL`.
+    ///
+    /// This is the URL of the movie the code belongs to. Movies loaded from
+    /// memory have no URL of their own, so the root movie's URL is used.
+    pub fn loader_url(&self) -> Option<String> {
+        let movie_url =
+            |clip: DisplayObject<'gc>| clip.movie().and_then(|m| m.url().map(str::to_string));
+        movie_url(self.base_clip).or_else(|| movie_url(self.context.stage.root_clip()))
+    }
+
     /// Gets the current target clip of this stack frame.
     /// This is the movie clip to which `GotoFrame` and other actions apply.
     /// Changed via `ActionSetTarget`/`ActionSetTarget2`.
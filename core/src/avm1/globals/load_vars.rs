@@ -1,13 +1,13 @@
 //! AVM1 LoadVars object
-//! TODO: bytesLoaded, bytesTotal, contentType, addRequestHeader
+//! TODO: bytesLoaded, bytesTotal, contentType
 
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::property::Attribute;
 use crate::avm1::property_decl::{define_properties_on, Declaration};
-use crate::avm1::{Object, ScriptObject, TObject, Value};
+use crate::avm1::{ArrayObject, Object, ScriptObject, TObject, Value};
 use crate::avm_warn;
-use crate::backend::navigator::{NavigationMethod, RequestOptions};
+use crate::backend::navigator::{is_forbidden_request_header, NavigationMethod, RequestOptions};
 use crate::string::AvmString;
 use gc_arena::MutationContext;
 use std::borrow::Cow;
@@ -46,15 +46,82 @@ pub fn create_proto<'gc>(
     object.into()
 }
 
+/// Implements `LoadVars.addRequestHeader`
+///
+/// Accepts either a header name and value, or an array of alternating names
+/// and values. Headers are kept in the hidden `_customHeaders` array, in the
+/// order they were added.
 fn add_request_header<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
-    _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    this: Object<'gc>,
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm_warn!(activation, "LoadVars.addRequestHeader: Unimplemented");
+    let new_headers = match args {
+        [Value::Object(array), ..] => (0..array.length(activation)?)
+            .map(|i| array.get_element(activation, i))
+            .collect(),
+        [name, value, ..] => vec![*name, *value],
+        _ => return Ok(Value::Undefined),
+    };
+
+    let headers = match this.get("_customHeaders", activation)? {
+        Value::Object(headers) => headers,
+        _ => {
+            let headers: Object<'gc> = ArrayObject::empty(activation).into();
+            this.define_value(
+                activation.context.gc_context,
+                "_customHeaders",
+                headers.into(),
+                Attribute::DONT_DELETE | Attribute::DONT_ENUM,
+            );
+            headers
+        }
+    };
+
+    let mut length = headers.length(activation)?;
+    for header in new_headers.chunks_exact(2) {
+        headers.set_element(activation, length, header[0])?;
+        headers.set_element(activation, length + 1, header[1])?;
+        length += 2;
+    }
+
     Ok(Value::Undefined)
 }
 
+/// Add the headers from `addRequestHeader` to a request.
+fn add_custom_headers<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    loader_object: Object<'gc>,
+    request_options: &mut RequestOptions,
+) -> Result<(), Error<'gc>> {
+    let headers = match loader_object.get("_customHeaders", activation)? {
+        Value::Object(headers) => headers,
+        _ => return Ok(()),
+    };
+
+    let length = headers.length(activation)?;
+    for i in (0..length - 1).step_by(2) {
+        let name = headers
+            .get_element(activation, i)
+            .coerce_to_string(activation)?;
+        let value = headers
+            .get_element(activation, i + 1)
+            .coerce_to_string(activation)?;
+
+        if is_forbidden_request_header(&name) {
+            avm_warn!(
+                activation,
+                "LoadVars: Request header {} is not allowed and was removed",
+                name
+            );
+        } else {
+            request_options.add_header(name.to_string(), value.to_string());
+        }
+    }
+
+    Ok(())
+}
+
 fn decode<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
@@ -249,19 +316,25 @@ fn spawn_load_var_fetch<'gc>(
     url: &AvmString,
     send_object: Option<(Object<'gc>, NavigationMethod)>,
 ) -> Result<Value<'gc>, Error<'gc>> {
-    let (url, request_options) = if let Some((send_object, method)) = send_object {
+    let (url, mut request_options) = if let Some((send_object, method)) = send_object {
         // Send properties from `send_object`.
         activation.object_into_request_options(send_object, Cow::Borrowed(url), Some(method))
     } else {
         // Not sending any parameters.
         (Cow::Borrowed(url.as_str()), RequestOptions::get())
     };
+    // Headers are taken from the object sending the request.
+    add_custom_headers(
+        activation,
+        send_object.map_or(loader_object, |(send_object, _)| send_object),
+        &mut request_options,
+    )?;
 
-    let fetch = activation.context.navigator.fetch(&url, request_options);
     let process = activation.context.load_manager.load_form_into_load_vars(
         activation.context.player.clone().unwrap(),
         loader_object,
-        fetch,
+        &url,
+        request_options,
     );
 
     // Create hidden properties on object.
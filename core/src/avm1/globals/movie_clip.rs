@@ -13,6 +13,7 @@ use crate::display_object::{
     Bitmap, DisplayObject, EditText, MovieClip, TDisplayObject, TDisplayObjectContainer,
 };
 use crate::ecma_conversions::f64_to_wrapping_i32;
+use crate::loader::MovieLoadOptions;
 use crate::prelude::*;
 use crate::shape_utils::DrawCommand;
 use crate::string::AvmString;
@@ -1336,14 +1337,16 @@ fn load_movie<'gc>(
     let method = args.get(1).cloned().unwrap_or(Value::Undefined);
     let method = NavigationMethod::from_method_str(&method.coerce_to_string(activation)?);
     let (url, opts) = activation.locals_into_request_options(Cow::Borrowed(&url), method);
-    let fetch = activation.context.navigator.fetch(&url, opts);
     let process = activation.context.load_manager.load_movie_into_clip(
         activation.context.player.clone().unwrap(),
         DisplayObject::MovieClip(target),
-        fetch,
         url.to_string(),
-        None,
-        None,
+        opts,
+        MovieLoadOptions {
+            loader_url: activation.loader_url(),
+            requesting_clip: Some(activation.base_clip()),
+            ..Default::default()
+        },
     );
 
     activation.context.navigator.spawn_future(process);
@@ -1361,12 +1364,12 @@ fn load_variables<'gc>(
     let method = args.get(1).cloned().unwrap_or(Value::Undefined);
     let method = NavigationMethod::from_method_str(&method.coerce_to_string(activation)?);
     let (url, opts) = activation.locals_into_request_options(Cow::Borrowed(&url), method);
-    let fetch = activation.context.navigator.fetch(&url, opts);
     let target = target.object().coerce_to_object(activation);
     let process = activation.context.load_manager.load_form_into_object(
         activation.context.player.clone().unwrap(),
         target,
-        fetch,
+        &url,
+        opts,
     );
 
     activation.context.navigator.spawn_future(process);
@@ -10,6 +10,7 @@ use crate::avm1::property_decl::{define_properties_on, Declaration};
 use crate::avm1::{ArrayObject, Object, Value};
 use crate::backend::navigator::RequestOptions;
 use crate::display_object::{DisplayObject, TDisplayObject};
+use crate::loader::MovieLoadOptions;
 use gc_arena::MutationContext;
 
 const PROTO_DECLS: &[Declaration] = declare_properties! {
@@ -51,17 +52,17 @@ pub fn load_clip<'gc>(
             .as_display_object()
             .and_then(|dobj| dobj.as_movie_clip())
         {
-            let fetch = activation
-                .context
-                .navigator
-                .fetch(&url, RequestOptions::get());
             let process = activation.context.load_manager.load_movie_into_clip(
                 activation.context.player.clone().unwrap(),
                 DisplayObject::MovieClip(mc),
-                fetch,
                 url.to_string(),
-                None,
-                Some(this),
+                RequestOptions::get(),
+                MovieLoadOptions {
+                    loader_url: activation.loader_url(),
+                    target_broadcaster: Some(this),
+                    requesting_clip: Some(activation.base_clip()),
+                    ..Default::default()
+                },
             );
 
             activation.context.navigator.spawn_future(process);
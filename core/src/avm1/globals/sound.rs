@@ -1,11 +1,12 @@
 //! AVM1 Sound object
-//! TODO: Sound position, transform, loadSound
+//! TODO: Sound position, transform
 
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::property_decl::{define_properties_on, Declaration};
 use crate::avm1::{Object, ScriptObject, SoundObject, TObject, Value};
 use crate::avm_warn;
+use crate::backend::navigator::RequestOptions;
 use crate::character::Character;
 use crate::display_object::{SoundTransform, TDisplayObject};
 use gc_arena::MutationContext;
@@ -239,12 +240,41 @@ fn id3<'gc>(
 
 fn load_sound<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
-    _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    this: Object<'gc>,
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    if activation.swf_version() >= 6 {
-        avm_warn!(activation, "Sound.loadSound: Unimplemented");
+    if activation.swf_version() < 6 {
+        return Ok(Value::Undefined);
+    }
+
+    if let Some(sound_object) = this.as_sound_object() {
+        let url = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_string(activation)?;
+        let is_streaming = args
+            .get(1)
+            .unwrap_or(&Value::Undefined)
+            .as_bool(activation.swf_version());
+
+        if let Some(sound_instance) = sound_object.sound_instance() {
+            activation.context.stop_sound(sound_instance);
+            sound_object.set_sound_instance(activation.context.gc_context, None);
+        }
+        sound_object.start_loading(activation.context.gc_context);
+
+        let process = activation.context.load_manager.load_sound_avm1(
+            activation.context.player.clone().unwrap(),
+            sound_object,
+            &url,
+            RequestOptions::get(),
+            is_streaming,
+        );
+        activation.context.navigator.spawn_future(process);
+    } else {
+        avm_warn!(activation, "Sound.loadSound: this is not a Sound");
     }
+
     Ok(Value::Undefined)
 }
 
@@ -393,6 +423,9 @@ fn start<'gc>(
                 sound_object
                     .set_sound_instance(activation.context.gc_context, Some(sound_instance));
             }
+        } else if sound_object.is_loading() {
+            // Event sounds can't play until they have fully loaded.
+            sound_object.queue_start(activation.context.gc_context, start_offset, loops.into());
         } else {
             avm_warn!(activation, "Sound.start: No sound is attached");
         }
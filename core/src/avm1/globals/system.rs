@@ -38,6 +38,7 @@ impl fmt::Display for CpuArchitecture {
 }
 
 /// Available type of sandbox for a given SWF
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum SandboxType {
     Remote,
     LocalWithFile,
@@ -45,6 +46,56 @@ pub enum SandboxType {
     LocalTrusted,
 }
 
+impl SandboxType {
+    /// Determine the sandbox of a movie loaded from the given URL by a movie
+    /// in the `parent` sandbox.
+    ///
+    /// Local files share the sandbox of local parents, and `data:` URIs and
+    /// movies without a URL have no origin of their own, so they inherit
+    /// their parent's sandbox.
+    pub fn for_movie_url(url: Option<&str>, parent: SandboxType) -> Self {
+        let scheme = url
+            .and_then(|url| url.split(':').next())
+            .map(|scheme| scheme.to_ascii_lowercase());
+
+        match scheme.as_deref() {
+            None | Some("data") => parent,
+            Some("file") if parent.is_local() => parent,
+            Some("file") => SandboxType::LocalWithFile,
+            Some(_) => SandboxType::Remote,
+        }
+    }
+
+    /// Whether this is one of the local sandboxes.
+    pub fn is_local(self) -> bool {
+        self != SandboxType::Remote
+    }
+
+    /// Check if a child movie loaded from `child_url` shares the sandbox of
+    /// the parent movie at `parent_url`.
+    ///
+    /// Remote movies must also share an origin with their parent.
+    pub fn shares_sandbox(child_url: Option<&str>, parent_url: Option<&str>, parent: Self) -> bool {
+        let child = Self::for_movie_url(child_url, parent);
+        if child != parent {
+            return false;
+        }
+
+        if child.is_local() || child_url.map_or(true, |url| url.starts_with("data:")) {
+            return true;
+        }
+
+        let origin = |url: Option<&str>| {
+            url.and_then(|url| url::Url::parse(url).ok())
+                .map(|url| url.origin())
+        };
+        match (origin(child_url), origin(parent_url)) {
+            (Some(child), Some(parent)) => child == parent,
+            _ => false,
+        }
+    }
+}
+
 impl fmt::Display for SandboxType {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt.write_str(match self {
@@ -528,3 +579,60 @@ pub fn create<'gc>(
     );
     system.into()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::SandboxType;
+
+    #[test]
+    fn sandbox_type_for_movie_url() {
+        use SandboxType::*;
+
+        assert_eq!(
+            SandboxType::for_movie_url(Some("http://example.com/child.swf"), Remote),
+            Remote
+        );
+        assert_eq!(
+            SandboxType::for_movie_url(Some("file:///games/child.swf"), LocalTrusted),
+            LocalTrusted
+        );
+        assert_eq!(
+            SandboxType::for_movie_url(Some("file:///games/child.swf"), Remote),
+            LocalWithFile
+        );
+        assert_eq!(
+            SandboxType::for_movie_url(Some("data:application/x-shockwave-flash,FWS"), Remote),
+            Remote
+        );
+        assert_eq!(
+            SandboxType::for_movie_url(None, LocalWithNetwork),
+            LocalWithNetwork
+        );
+    }
+
+    #[test]
+    fn same_origin_child_shares_sandbox() {
+        let parent = Some("http://example.com/parent.swf");
+
+        assert!(SandboxType::shares_sandbox(
+            Some("http://example.com/games/child.swf"),
+            parent,
+            SandboxType::Remote
+        ));
+        assert!(!SandboxType::shares_sandbox(
+            Some("http://other.com/child.swf"),
+            parent,
+            SandboxType::Remote
+        ));
+        assert!(!SandboxType::shares_sandbox(
+            Some("file:///child.swf"),
+            parent,
+            SandboxType::Remote
+        ));
+        assert!(SandboxType::shares_sandbox(
+            Some("file:///child.swf"),
+            Some("file:///parent.swf"),
+            SandboxType::LocalTrusted
+        ));
+    }
+}
@@ -889,7 +889,6 @@ fn spawn_xml_fetch<'gc>(
 
     this.set("loaded", false.into(), activation)?;
 
-    let fetch = activation.context.navigator.fetch(url, request_options);
     let target_clip = activation.target_clip_or_root()?;
     // given any defined loader object, sends the request. Will load into LoadVars if given.
     let process = if let Some(node) = loader_object.as_xml_node() {
@@ -897,13 +896,15 @@ fn spawn_xml_fetch<'gc>(
             activation.context.player.clone().unwrap(),
             node,
             target_clip,
-            fetch,
+            url,
+            request_options,
         )
     } else {
         activation.context.load_manager.load_form_into_load_vars(
             activation.context.player.clone().unwrap(),
             loader_object,
-            fetch,
+            url,
+            request_options,
         )
     };
 
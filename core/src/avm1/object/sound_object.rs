@@ -37,6 +37,13 @@ pub struct SoundObjectData<'gc> {
 
     /// Duration of the currently attached sound in milliseconds.
     duration: Option<u32>,
+
+    /// Whether a sound is being loaded into this object by `loadSound`.
+    is_loading: bool,
+
+    /// The offset and loop count of a `start` call made while an event sound
+    /// was loading, which plays once the load finishes.
+    queued_start: Option<(f64, f64)>,
 }
 
 impl fmt::Debug for SoundObject<'_> {
@@ -64,6 +71,8 @@ impl<'gc> SoundObject<'gc> {
                 owner: None,
                 position: 0,
                 duration: None,
+                is_loading: false,
+                queued_start: None,
             },
         ))
     }
@@ -108,6 +117,32 @@ impl<'gc> SoundObject<'gc> {
         self.0.write(gc_context).owner = owner;
     }
 
+    pub fn is_loading(self) -> bool {
+        self.0.read().is_loading
+    }
+
+    /// Mark this object as loading a new sound, replacing any attached
+    /// sound.
+    pub fn start_loading(self, gc_context: MutationContext<'gc, '_>) {
+        let mut write = self.0.write(gc_context);
+        write.sound = None;
+        write.duration = None;
+        write.is_loading = true;
+        write.queued_start = None;
+    }
+
+    /// Mark the load into this object as finished, returning the `start`
+    /// call that was queued while it loaded, if any.
+    pub fn finish_loading(self, gc_context: MutationContext<'gc, '_>) -> Option<(f64, f64)> {
+        let mut write = self.0.write(gc_context);
+        write.is_loading = false;
+        write.queued_start.take()
+    }
+
+    pub fn queue_start(self, gc_context: MutationContext<'gc, '_>, start_offset: f64, loops: f64) {
+        self.0.write(gc_context).queued_start = Some((start_offset, loops));
+    }
+
     pub fn position(self) -> u32 {
         self.0.read().position
     }
@@ -123,3 +158,32 @@ impl<'gc> TObject<'gc> for SoundObject<'gc> {
         bare_object(as_sound_object -> SoundObject::empty_sound);
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::SoundObject;
+
+    #[test]
+    fn start_is_queued_while_loading() {
+        gc_arena::rootless_arena(|mc| {
+            let sound = SoundObject::empty_sound(mc, None);
+            assert!(!sound.is_loading());
+
+            sound.start_loading(mc);
+            assert!(sound.is_loading());
+            sound.queue_start(mc, 1.5, 3.0);
+            sound.queue_start(mc, 2.0, 1.0);
+
+            // Only the latest `start` call plays, and only once.
+            assert_eq!(sound.finish_loading(mc), Some((2.0, 1.0)));
+            assert!(!sound.is_loading());
+            assert_eq!(sound.finish_loading(mc), None);
+
+            // A new load drops a `start` queued by the previous one.
+            sound.start_loading(mc);
+            sound.queue_start(mc, 0.0, 1.0);
+            sound.start_loading(mc);
+            assert_eq!(sound.finish_loading(mc), None);
+        })
+    }
+}
@@ -1,6 +1,10 @@
+use crate::avm1::activation::{Activation, ActivationIdentifier};
 use crate::avm1::error::Error;
 use crate::avm1::test_utils::with_avm;
 use crate::avm1::TObject;
+use crate::display_object::{DisplayObject, MovieClip, TDisplayObject};
+use crate::tag_utils::{SwfMovie, SwfSlice};
+use std::sync::Arc;
 
 #[test]
 fn locals_into_form_values() {
@@ -23,3 +27,64 @@ fn locals_into_form_values() {
         Ok(())
     });
 }
+
+/// Build a movie fetched from `url`, if it has one, on behalf of `loader_url`.
+fn loaded_movie(url: Option<&str>, loader_url: Option<String>) -> Arc<SwfMovie> {
+    let mut data = Vec::new();
+    swf::write_swf(&swf::Header::default_with_swf_version(19), &[], &mut data).unwrap();
+    Arc::new(SwfMovie::from_data(&data, url.map(str::to_string), loader_url).unwrap())
+}
+
+#[test]
+fn loads_are_made_on_behalf_of_the_loading_movie() {
+    with_avm(19, |activation, _this| -> Result<(), Error> {
+        let root_url = "http://example.com/root.swf";
+        let root = activation.context.stage.root_clip();
+        root.as_movie_clip().unwrap().replace_with_movie(
+            activation.context.gc_context,
+            Some(loaded_movie(Some(root_url), None)),
+        );
+        assert_eq!(activation.loader_url().as_deref(), Some(root_url));
+
+        // Each movie in a chain of loads is loaded on behalf of the last.
+        let child_url = "http://example.com/a/child.swf";
+        let child: DisplayObject<'_> = MovieClip::new(
+            SwfSlice::empty(loaded_movie(Some(child_url), activation.loader_url())),
+            activation.context.gc_context,
+        )
+        .into();
+        let globals = activation.context.avm1.global_object_cell();
+        let grandchild = {
+            let child_activation = Activation::from_nothing(
+                activation.context.reborrow(),
+                ActivationIdentifier::root("[Child]"),
+                19,
+                globals,
+                child,
+            );
+            loaded_movie(
+                Some("http://example.com/a/b/grandchild.swf"),
+                child_activation.loader_url(),
+            )
+        };
+        assert_eq!(child.movie().unwrap().loader_url(), Some(root_url));
+        assert_eq!(grandchild.loader_url(), Some(child_url));
+
+        // A movie loaded from memory makes its loads on behalf of the root.
+        let in_memory: DisplayObject<'_> = MovieClip::new(
+            SwfSlice::empty(loaded_movie(None, Some(child_url.to_string()))),
+            activation.context.gc_context,
+        )
+        .into();
+        let in_memory_activation = Activation::from_nothing(
+            activation.context.reborrow(),
+            ActivationIdentifier::root("[In memory]"),
+            19,
+            globals,
+            in_memory,
+        );
+        assert_eq!(in_memory_activation.loader_url().as_deref(), Some(root_url));
+
+        Ok(())
+    });
+}
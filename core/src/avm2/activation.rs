@@ -1424,10 +1424,15 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
 
     fn op_in(&mut self) -> Result<FrameControl<'gc>, Error> {
         let obj = self.context.avm2.pop().coerce_to_object(self)?;
-        let name = self.context.avm2.pop().coerce_to_string(self)?;
+        let name = self.context.avm2.pop();
 
-        let qname = QName::new(Namespace::public(), name);
-        let has_prop = obj.has_property(&qname)?;
+        // QName keys carry their own namespace; anything else is looked up
+        // in the public namespace.
+        let multiname = match name.as_qname() {
+            Some(qname) => Multiname::from_qname_key(&qname),
+            None => QName::new(Namespace::public(), name.coerce_to_string(self)?).into(),
+        };
+        let has_prop = obj.resolve_multiname(&multiname)?.is_some();
 
         self.context.avm2.push(has_prop);
 
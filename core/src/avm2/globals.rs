@@ -26,6 +26,7 @@ mod math;
 mod namespace;
 mod number;
 mod object;
+mod q_name;
 mod regexp;
 mod string;
 mod r#uint;
@@ -93,6 +94,7 @@ pub struct SystemPrototypes<'gc> {
     pub int: Object<'gc>,
     pub uint: Object<'gc>,
     pub namespace: Object<'gc>,
+    pub qname: Object<'gc>,
     pub array: Object<'gc>,
     pub movieclip: Object<'gc>,
     pub framelabel: Object<'gc>,
@@ -147,6 +149,7 @@ impl<'gc> SystemPrototypes<'gc> {
             int: empty,
             uint: empty,
             namespace: empty,
+            qname: empty,
             array: empty,
             movieclip: empty,
             framelabel: empty,
@@ -192,6 +195,7 @@ pub struct SystemClasses<'gc> {
     pub int: Object<'gc>,
     pub uint: Object<'gc>,
     pub namespace: Object<'gc>,
+    pub qname: Object<'gc>,
     pub array: Object<'gc>,
     pub movieclip: Object<'gc>,
     pub framelabel: Object<'gc>,
@@ -246,6 +250,7 @@ impl<'gc> SystemClasses<'gc> {
             int: empty,
             uint: empty,
             namespace: empty,
+            qname: empty,
             array: empty,
             movieclip: empty,
             framelabel: empty,
@@ -503,6 +508,7 @@ pub fn load_player_globals<'gc>(
         domain,
         script
     );
+    avm2_system_class!(qname, activation, q_name::create_class(mc), domain, script);
     avm2_system_class!(array, activation, array::create_class(mc), domain, script);
 
     // At this point we have to hide the fact that we had to create the player
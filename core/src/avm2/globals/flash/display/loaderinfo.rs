@@ -1,7 +1,7 @@
 //! `flash.display.LoaderInfo` builtin/prototype
 
+use crate::avm1::globals::system::SandboxType;
 use crate::avm2::activation::Activation;
-use crate::avm2::bytearray::Endian;
 use crate::avm2::class::{Class, ClassAttributes};
 use crate::avm2::method::{Method, NativeMethodImpl};
 use crate::avm2::names::{Namespace, QName};
@@ -9,6 +9,7 @@ use crate::avm2::object::{loaderinfo_allocator, DomainObject, LoaderStream, Obje
 use crate::avm2::value::Value;
 use crate::avm2::{AvmString, Error};
 use crate::display_object::TDisplayObject;
+use crate::tag_utils::SwfMovie;
 use gc_arena::{GcCell, MutationContext};
 use swf::{write_swf, Compression};
 
@@ -216,6 +217,38 @@ pub fn is_url_inaccessible<'gc>(
     Ok(false.into())
 }
 
+/// Implements `childAllowsParent`, `parentAllowsChild` and `sameDomain`.
+///
+/// Without `Security.allowDomain` support, access is only granted when the
+/// loaded movie ends up in the same sandbox as the root movie.
+pub fn same_sandbox<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(loader_stream) = this.as_loader_stream() {
+            match &*loader_stream {
+                LoaderStream::Stage => {
+                    return Err(
+                        "Error: The stage's loader info does not have a security sandbox".into(),
+                    )
+                }
+                LoaderStream::Swf(root, _) => {
+                    return Ok(SandboxType::shares_sandbox(
+                        root.url(),
+                        activation.context.swf.url(),
+                        activation.context.system.sandbox_type,
+                    )
+                    .into());
+                }
+            }
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
 /// `swfVersion` getter
 pub fn swf_version<'gc>(
     _activation: &mut Activation<'_, 'gc, '_>,
@@ -283,6 +316,30 @@ pub fn width<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Rebuild a movie's bytes as an uncompressed SWF.
+///
+/// This is what Flash Player hands back for a loaded movie, rather than the
+/// bytes that came over the wire.
+fn uncompressed_swf_bytes(movie: &SwfMovie) -> Vec<u8> {
+    let mut header = movie.header().swf_header().clone();
+    header.compression = Compression::None;
+
+    let mut bytes = Vec::new();
+    write_swf(&header, &[], &mut bytes).unwrap();
+
+    // `swf` always writes an implicit end tag, let's cut that off before
+    // writing the actual datastream.
+    bytes.truncate(bytes.len() - 2);
+    bytes.extend_from_slice(movie.data());
+
+    // `swf` wrote the wrong length (since we wrote the data ourselves), so
+    // we need to overwrite it ourselves.
+    let length = bytes.len() as u32;
+    bytes[4..8].copy_from_slice(&length.to_le_bytes());
+
+    bytes
+}
+
 /// `bytes` getter
 pub fn bytes<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
@@ -300,32 +357,8 @@ pub fn bytes<'gc>(
 
                     let ba = ba_class.construct(activation, &[])?;
                     let mut ba_write = ba.as_bytearray_mut(activation.context.gc_context).unwrap();
-
-                    // First, write a fake header corresponding to an
-                    // uncompressed SWF
-                    let mut header = root.header().swf_header().clone();
-                    header.compression = Compression::None;
-
-                    write_swf(&header, &[], &mut *ba_write).unwrap();
-
-                    // `swf` always writes an implicit end tag, let's cut that
-                    // off. We scroll back 2 bytes before writing the actual
-                    // datastream as it is guaranteed to at least be as long as
-                    // the implicit end tag we want to get rid of.
-                    let correct_header_length = ba_write.len() - 2;
-                    ba_write.set_position(correct_header_length);
-                    ba_write.write_bytes(root.data())?;
-
-                    // `swf` wrote the wrong length (since we wrote the data
-                    // ourselves), so we need to overwrite it ourselves.
-                    ba_write.set_position(4);
-                    ba_write.set_endian(Endian::Little);
-                    ba_write
-                        .write_unsigned_int((root.data().len() + correct_header_length) as u32)?;
-
-                    // Finally, reset the array to the correct state.
+                    ba_write.write_bytes(&uncompressed_swf_bytes(root))?;
                     ba_write.set_position(0);
-                    ba_write.set_endian(Endian::Big);
 
                     return Ok(ba.into());
                 }
@@ -432,6 +465,7 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         ("applicationDomain", Some(application_domain), None),
         ("bytesLoaded", Some(bytes_total), None),
         ("bytesTotal", Some(bytes_total), None),
+        ("childAllowsParent", Some(same_sandbox), None),
         ("content", Some(content), None),
         ("contentType", Some(content_type), None),
         ("frameRate", Some(frame_rate), None),
@@ -442,9 +476,27 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         ("width", Some(width), None),
         ("bytes", Some(bytes), None),
         ("loaderUrl", Some(loader_url), None),
+        ("parentAllowsChild", Some(same_sandbox), None),
+        ("sameDomain", Some(same_sandbox), None),
         ("parameters", Some(parameters), None),
     ];
     write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
 
     class
 }
+
+#[cfg(test)]
+mod tests {
+    use super::uncompressed_swf_bytes;
+    use crate::tag_utils::SwfMovie;
+
+    #[test]
+    fn uncompressed_movies_keep_their_bytes() {
+        let header = swf::Header::default_with_swf_version(10);
+        let mut data = Vec::new();
+        swf::write_swf(&header, &[swf::Tag::ShowFrame], &mut data).unwrap();
+
+        let movie = SwfMovie::from_data(&data, None, None).unwrap();
+        assert_eq!(uncompressed_swf_bytes(&movie), data);
+    }
+}
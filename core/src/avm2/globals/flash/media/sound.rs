@@ -6,7 +6,7 @@ use crate::avm2::method::{Method, NativeMethodImpl};
 use crate::avm2::names::{Namespace, QName};
 use crate::avm2::object::{sound_allocator, Object, SoundChannelObject, TObject};
 use crate::avm2::value::Value;
-use crate::avm2::Error;
+use crate::avm2::{Avm2, Error, Event};
 use crate::character::Character;
 use crate::display_object::SoundTransform;
 use gc_arena::{GcCell, MutationContext};
@@ -207,13 +207,50 @@ pub fn load<'gc>(
     Err("Sound.load is a stub.".into())
 }
 
-/// Stubs `Sound.loadCompressedDataFromByteArray`
+/// Implements `Sound.loadCompressedDataFromByteArray`
 pub fn load_compressed_data_from_byte_array<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
-    _this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    Err("Sound.loadCompressedDataFromByteArray is a stub.".into())
+    if let Some(this) = this {
+        let bytes = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_object(activation)?;
+        let bytes_length = args
+            .get(1)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_u32(activation)? as usize;
+
+        let data = if let Some(bytearray) = bytes.as_bytearray() {
+            bytearray
+                .read_bytes(bytes_length.min(bytearray.bytes_available()))?
+                .to_vec()
+        } else {
+            return Err("Sound.loadCompressedDataFromByteArray requires a ByteArray".into());
+        };
+
+        let event = match activation.context.audio.register_mp3(&data) {
+            Ok(sound) => {
+                this.set_sound(activation.context.gc_context, sound);
+                Event::new("complete")
+            }
+            Err(e) => {
+                log::warn!(
+                    "Sound.loadCompressedDataFromByteArray: Error #2032: Stream Error. ({})",
+                    e
+                );
+                Event::new("ioError")
+            }
+        };
+
+        Avm2::dispatch_event(&mut activation.context, event, this)?;
+    }
+
+    Ok(Value::Undefined)
 }
 
 /// Stubs `Sound.loadPCMFromByteArray`
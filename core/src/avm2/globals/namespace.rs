@@ -2,20 +2,121 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::class::Class;
-use crate::avm2::method::Method;
+use crate::avm2::method::{Method, NativeMethodImpl};
 use crate::avm2::names::{Namespace, QName};
-use crate::avm2::object::{namespace_allocator, Object};
+use crate::avm2::object::{namespace_allocator, Object, TObject};
 use crate::avm2::value::Value;
 use crate::avm2::Error;
+use crate::string::AvmString;
 use gc_arena::{GcCell, MutationContext};
 
+/// Determine if a string is a valid XML name, and thus usable as a prefix.
+fn is_xml_name(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    match chars.next() {
+        Some(c) if c.is_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+
+    chars.all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == '.')
+}
+
+/// Determine the prefix of a namespace constructed with both a prefix and a
+/// URI.
+///
+/// A prefix of `None` stands for an `undefined` argument. A prefix that is not
+/// a valid XML name leaves the namespace without one. The empty URI can only
+/// have the empty prefix.
+fn explicit_prefix<'gc>(
+    prefix: Option<AvmString<'gc>>,
+    uri: AvmString<'gc>,
+) -> Result<Option<AvmString<'gc>>, Error> {
+    match prefix {
+        None if uri.is_empty() => Ok(Some("".into())),
+        None => Ok(None),
+        Some(prefix) if uri.is_empty() => {
+            if prefix.is_empty() {
+                Ok(Some(prefix))
+            } else {
+                Err(format!(
+                    "TypeError: Error #1098: Illegal prefix {} for no namespace.",
+                    prefix
+                )
+                .into())
+            }
+        }
+        Some(prefix) if is_xml_name(&prefix) => Ok(Some(prefix)),
+        Some(_) => Ok(None),
+    }
+}
+
+/// Coerce a namespace URI argument to a string.
+///
+/// QNames contribute their own URI, unless they match any namespace.
+fn coerce_to_uri<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: &Value<'gc>,
+) -> Result<AvmString<'gc>, Error> {
+    if let Some(qname) = value.as_qname() {
+        if !qname.namespace().is_any() {
+            return Ok(qname.namespace().as_uri());
+        }
+    }
+
+    value.coerce_to_string(activation)
+}
+
 /// Implements `Namespace`'s instance initializer.
 pub fn instance_init<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
-    _this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
-    Err("Namespace constructor is a stub.".into())
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+
+        let copied = match args {
+            [Value::Object(o)] => o
+                .as_namespace_object()
+                .zip(o.as_namespace().map(|ns| ns.as_uri())),
+            _ => None,
+        };
+
+        let (prefix, uri) = if let Some((namespace, uri)) = copied {
+            (namespace.prefix(), uri)
+        } else {
+            match args {
+                [] => (Some("".into()), "".into()),
+                [uri] => {
+                    let uri = coerce_to_uri(activation, uri)?;
+                    let prefix = if uri.is_empty() {
+                        Some("".into())
+                    } else {
+                        None
+                    };
+
+                    (prefix, uri)
+                }
+                [prefix, uri, ..] => {
+                    let uri = coerce_to_uri(activation, uri)?;
+                    let prefix = match prefix {
+                        Value::Undefined => None,
+                        prefix => Some(prefix.coerce_to_string(activation)?),
+                    };
+
+                    (explicit_prefix(prefix, uri)?, uri)
+                }
+            }
+        };
+
+        if let Some(this_namespace) = this.as_namespace_object() {
+            this_namespace.set_namespace(activation.context.gc_context, Namespace::from_uri(uri));
+            this_namespace.set_prefix(activation.context.gc_context, prefix);
+        }
+    }
+
+    Ok(Value::Undefined)
 }
 
 /// Implements `Namespace`'s native instance initializer.
@@ -40,6 +141,45 @@ pub fn class_init<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Implements `Namespace.prefix`'s getter
+pub fn prefix<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(namespace) = this.and_then(|this| this.as_namespace_object()) {
+        if let Some(prefix) = namespace.prefix() {
+            return Ok(prefix.into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Namespace.uri`'s getter
+pub fn uri<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(namespace) = this.as_namespace() {
+            return Ok(namespace.as_uri().into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Namespace.toString`
+pub fn to_string<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    uri(activation, this, args)
+}
+
 /// Construct `Namespace`'s class.
 pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
     let class = Class::new(
@@ -58,5 +198,45 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         mc,
     ));
 
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[("prefix", Some(prefix), None), ("uri", Some(uri), None)];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    const PUBLIC_INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[("toString", to_string)];
+    write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
+
     class
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{explicit_prefix, is_xml_name};
+
+    #[test]
+    fn xml_name_prefixes() {
+        assert!(is_xml_name("pfx"));
+        assert!(is_xml_name("_private"));
+        assert!(is_xml_name("a-b.c1"));
+        assert!(!is_xml_name(""));
+        assert!(!is_xml_name("1abc"));
+        assert!(!is_xml_name("a b"));
+        assert!(!is_xml_name("a:b"));
+    }
+
+    #[test]
+    fn prefix_and_uri() {
+        let pfx = explicit_prefix(Some("pfx".into()), "http://x".into()).unwrap();
+        assert_eq!(pfx.as_deref(), Some("pfx"));
+
+        let invalid = explicit_prefix(Some("1pfx".into()), "http://x".into()).unwrap();
+        assert_eq!(invalid, None);
+        assert_eq!(explicit_prefix(None, "http://x".into()).unwrap(), None);
+
+        let empty = explicit_prefix(None, "".into()).unwrap();
+        assert_eq!(empty.as_deref(), Some(""));
+        assert!(explicit_prefix(Some("pfx".into()), "".into()).is_err());
+    }
+}
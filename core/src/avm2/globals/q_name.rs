@@ -0,0 +1,368 @@
+//! `QName` impl
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{qname_allocator, Object, TObject};
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::string::AvmString;
+use gc_arena::{GcCell, MutationContext};
+
+/// Get the namespace that a QName URI names.
+///
+/// A URI of `*` denotes any namespace.
+fn uri_namespace<'gc>(uri: AvmString<'gc>) -> Namespace<'gc> {
+    if &*uri == "*" {
+        Namespace::Any
+    } else {
+        Namespace::from_uri(uri)
+    }
+}
+
+/// Get the namespace held by a `Namespace` or `QName` object argument.
+fn object_namespace<'gc>(value: &Value<'gc>) -> Option<Namespace<'gc>> {
+    match value {
+        Value::Object(o) => o
+            .as_namespace()
+            .map(|ns| ns.clone())
+            .or_else(|| o.as_qname().map(|qname| qname.namespace().clone())),
+        _ => None,
+    }
+}
+
+/// Split a single-argument QName string into its namespace and local name.
+///
+/// Flash treats a string of the form `uri::localName` as a fully qualified
+/// name, splitting on the *last* `::` separator. A URI of `*` denotes any
+/// namespace, as does the bare local name `*`. An empty URI denotes the
+/// public namespace.
+fn split_qualified_name<'gc>(
+    mc: MutationContext<'gc, '_>,
+    name: AvmString<'gc>,
+) -> (Namespace<'gc>, AvmString<'gc>) {
+    if let Some((uri, local_name)) = name.rsplit_once("::") {
+        (
+            uri_namespace(AvmString::new(mc, uri)),
+            AvmString::new(mc, local_name),
+        )
+    } else if &*name == "*" {
+        (Namespace::Any, name)
+    } else {
+        (Namespace::public(), name)
+    }
+}
+
+/// Get the prefix of a namespace argument, if it's an object that has one.
+fn namespace_prefix<'gc>(value: &Value<'gc>) -> Option<AvmString<'gc>> {
+    match value {
+        Value::Object(o) => o
+            .as_namespace_object()
+            .and_then(|ns| ns.prefix())
+            .or_else(|| o.as_qname_object().and_then(|q| q.prefix())),
+        _ => None,
+    }
+}
+
+/// Implements `QName`'s instance initializer.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+
+        let qname = if args.len() >= 2 {
+            let namespace = match &args[0] {
+                Value::Undefined => Namespace::public(),
+                Value::Null => Namespace::Any,
+                v => match object_namespace(v) {
+                    Some(namespace) => namespace,
+                    None => uri_namespace(v.coerce_to_string(activation)?),
+                },
+            };
+            let local_name = match &args[1] {
+                Value::Undefined => "".into(),
+                v => match v.as_qname() {
+                    Some(qname) => qname.local_name(),
+                    None => v.coerce_to_string(activation)?,
+                },
+            };
+
+            QName::new(namespace, local_name)
+        } else {
+            let arg = args.get(0).unwrap_or(&Value::Undefined);
+
+            if let Some(qname) = arg.as_qname() {
+                qname
+            } else if let Some(namespace) = object_namespace(arg) {
+                QName::new(namespace, "")
+            } else if let Value::Undefined = arg {
+                QName::new(Namespace::public(), "")
+            } else {
+                let name = arg.coerce_to_string(activation)?;
+                let (namespace, local_name) =
+                    split_qualified_name(activation.context.gc_context, name);
+
+                QName::new(namespace, local_name)
+            }
+        };
+
+        let prefix = args.get(0).and_then(namespace_prefix);
+
+        if let Some(mut this_qname) = this.as_qname_mut(activation.context.gc_context) {
+            *this_qname = qname;
+        }
+
+        if let Some(this_qname) = this.as_qname_object() {
+            this_qname.set_prefix(activation.context.gc_context, prefix);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `QName`'s class initializer.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `QName.localName`'s getter
+pub fn local_name<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(qname) = this.as_qname() {
+            return Ok(qname.local_name().into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `QName.uri`'s getter
+///
+/// QNames matching any namespace have a `null` URI, which is distinct from
+/// the empty URI of the public namespace.
+pub fn uri<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(qname) = this.as_qname() {
+            if qname.namespace().is_any() {
+                return Ok(Value::Null);
+            }
+
+            return Ok(qname.namespace().as_uri().into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `QName`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::public(), "QName"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init, "<QName instance initializer>", mc),
+        Method::from_builtin(class_init, "<QName class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+    write.set_instance_allocator(qname_allocator);
+
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[
+        ("localName", Some(local_name), None),
+        ("uri", Some(uri), None),
+    ];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
+    class
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{split_qualified_name, uri};
+    use crate::avm2::activation::Activation;
+    use crate::avm2::names::Namespace;
+    use crate::avm2::object::TObject;
+    use crate::avm2::value::Value;
+    use crate::avm2::Error;
+    use crate::string::AvmString;
+
+    /// Run a test with an activation in a player that has loaded the AVM2
+    /// globals.
+    fn with_avm2<F>(test: F)
+    where
+        F: for<'gc> FnOnce(&mut Activation<'_, 'gc, '_>) -> Result<(), Error>,
+    {
+        use crate::backend::{
+            audio::NullAudioBackend, locale::NullLocaleBackend, log::NullLogBackend,
+            navigator::NullNavigatorBackend, render::NullRenderer, storage::MemoryStorageBackend,
+            ui::NullUiBackend, video::NullVideoBackend,
+        };
+
+        let player = crate::Player::new(
+            Box::new(NullRenderer::new()),
+            Box::new(NullAudioBackend::new()),
+            Box::new(NullNavigatorBackend::new()),
+            Box::new(MemoryStorageBackend::default()),
+            Box::new(NullLocaleBackend::new()),
+            Box::new(NullVideoBackend::new()),
+            Box::new(NullLogBackend::new()),
+            Box::new(NullUiBackend::new()),
+        )
+        .unwrap();
+
+        let result = player.lock().unwrap().update(|context| {
+            let mut activation = Activation::from_nothing(context.reborrow());
+            test(&mut activation).map_err(|e| e.to_string())
+        });
+        result.unwrap();
+    }
+
+    /// Construct a `QName` object and read its `uri` property.
+    fn qname_uri<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+        args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, Error> {
+        let class = activation.avm2().classes().qname;
+        let qname = class.construct(activation, args)?;
+
+        uri(activation, Some(qname), &[])
+    }
+
+    #[test]
+    fn qualified_names_split_on_the_last_separator() {
+        gc_arena::rootless_arena(|mc| {
+            let split = |name: &str| {
+                let (ns, local_name) = split_qualified_name(mc, AvmString::new(mc, name));
+                (ns, local_name.to_string())
+            };
+
+            let (ns, local_name) = split("http::x");
+            assert_eq!(ns, Namespace::Namespace("http".into()));
+            assert_eq!(local_name, "x");
+
+            let (ns, local_name) = split("::local");
+            assert!(ns.is_public());
+            assert_eq!(local_name, "local");
+
+            let (ns, local_name) = split("a::b::c");
+            assert_eq!(ns, Namespace::Namespace("a::b".into()));
+            assert_eq!(local_name, "c");
+
+            let (ns, local_name) = split("*::local");
+            assert!(ns.is_any());
+            assert_eq!(local_name, "local");
+
+            let (ns, local_name) = split("*");
+            assert!(ns.is_any());
+            assert_eq!(local_name, "*");
+
+            let (ns, local_name) = split("plain");
+            assert!(ns.is_public());
+            assert_eq!(local_name, "plain");
+        })
+    }
+
+    #[test]
+    fn null_namespaces_have_null_uris() {
+        with_avm2(|activation| {
+            let uri = qname_uri(activation, &[Value::Null, "x".into()])?;
+            assert_eq!(uri, Value::Null);
+
+            let uri = qname_uri(activation, &["".into(), "x".into()])?;
+            assert_eq!(uri, Value::String("".into()));
+
+            let uri = qname_uri(activation, &[Value::Undefined, "x".into()])?;
+            assert_eq!(uri, Value::String("".into()));
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn star_uris_match_any_namespace() {
+        with_avm2(|activation| {
+            let class = activation.avm2().classes().qname;
+            let qname = class.construct(activation, &["*".into(), "*".into()])?;
+            let qname = qname.as_qname().unwrap().clone();
+            assert!(qname.namespace().is_any());
+            assert_eq!(qname.local_name(), "*".into());
+
+            let uri = qname_uri(activation, &["*".into(), "x".into()])?;
+            assert_eq!(uri, Value::Null);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn namespace_objects_give_their_uri_and_prefix() {
+        with_avm2(|activation| {
+            let namespace_class = activation.avm2().classes().namespace;
+            let qname_class = activation.avm2().classes().qname;
+
+            let namespace = namespace_class.construct(activation, &["http://x".into()])?;
+            let qname = qname_class.construct(activation, &[namespace.into(), "local".into()])?;
+            assert_eq!(
+                uri(activation, Some(qname), &[])?,
+                Value::String("http://x".into())
+            );
+            assert_eq!(qname.as_qname().unwrap().local_name(), "local".into());
+
+            let namespace =
+                namespace_class.construct(activation, &["pfx".into(), "http://x".into()])?;
+            let qname = qname_class.construct(activation, &[namespace.into(), "local".into()])?;
+            let prefix = qname.as_qname_object().unwrap().prefix();
+            assert_eq!(prefix.as_deref(), Some("pfx"));
+
+            let qname = qname_class.construct(activation, &[namespace.into()])?;
+            assert_eq!(
+                uri(activation, Some(qname), &[])?,
+                Value::String("http://x".into())
+            );
+            assert_eq!(qname.as_qname().unwrap().local_name(), "".into());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn constructed_qnames_render_like_flash() {
+        with_avm2(|activation| {
+            let class = activation.avm2().classes().qname;
+            let cases: &[(&[Value<'_>], &str)] = &[
+                (&["".into(), "x".into()], "x"),
+                (&["http://x".into(), "y".into()], "http://x::y"),
+                (&[Value::Null, "x".into()], "*::x"),
+                (&["flash.display::Sprite".into()], "flash.display::Sprite"),
+            ];
+
+            for (args, expected) in cases.iter() {
+                let qname = class.construct(activation, args)?;
+                let string = qname.to_string(activation.context.gc_context)?;
+                assert_eq!(string, Value::String((*expected).into()));
+            }
+
+            Ok(())
+        })
+    }
+}
@@ -71,6 +71,19 @@ impl<'gc> Namespace<'gc> {
         Self::Namespace("http://adobe.com/AS3/2006/builtin".into())
     }
 
+    /// The namespace that script code refers to by URI, as when constructing
+    /// a `Namespace` or `QName`.
+    ///
+    /// The empty URI is the public namespace. Any other URI is a user
+    /// namespace, the same as one declared with `namespace x = "uri"`.
+    pub fn from_uri(uri: AvmString<'gc>) -> Self {
+        if uri.is_empty() {
+            Self::public()
+        } else {
+            Self::Namespace(uri)
+        }
+    }
+
     pub fn package(package_name: impl Into<AvmString<'gc>>) -> Self {
         Self::Package(package_name.into())
     }
@@ -219,6 +232,33 @@ impl<'gc> QName<'gc> {
             .unwrap_or_else(|| format!("{}::{}", uri, name))
     }
 
+    /// Converts this `QName` to the string script code sees from
+    /// `QName.toString`.
+    ///
+    /// Names in the public namespace are just their local name, and names in
+    /// any namespace are prefixed with `*::`. Every other namespace is shown,
+    /// even if its URI is empty.
+    pub fn to_script_string(&self) -> String {
+        if self.ns.is_public() {
+            self.name.to_string()
+        } else if self.ns.is_any() {
+            format!("*::{}", self.name)
+        } else {
+            format!("{}::{}", self.ns.as_uri(), self.name)
+        }
+    }
+
+    /// Check if two `QName`s have the same namespace URI and local name.
+    ///
+    /// This is how script `QName` objects compare, whichever kind of
+    /// namespace they were constructed with. Names in any namespace only
+    /// match each other.
+    pub fn eq_by_uri(&self, other: &Self) -> bool {
+        self.ns.is_any() == other.ns.is_any()
+            && self.ns.as_uri() == other.ns.as_uri()
+            && self.name == other.name
+    }
+
     pub fn local_name(&self) -> AvmString<'gc> {
         self.name
     }
@@ -322,10 +362,14 @@ impl<'gc> Multiname<'gc> {
             }
             AbcMultiname::RTQNameL | AbcMultiname::RTQNameLA => {
                 let ns = activation.avm2().pop().as_namespace()?.clone();
-                let name = activation.avm2().pop().coerce_to_string(activation)?;
+                let name = activation.avm2().pop();
+                if let Some(qname) = name.as_qname() {
+                    return Ok(Self::from_qname_key(&qname));
+                }
+
                 Self {
                     ns: vec![ns],
-                    name: Some(name),
+                    name: Some(name.coerce_to_string(activation)?),
                     params: Vec::new(),
                 }
             }
@@ -347,14 +391,18 @@ impl<'gc> Multiname<'gc> {
             },
             AbcMultiname::MultinameL { namespace_set }
             | AbcMultiname::MultinameLA { namespace_set } => {
-                let name = activation.avm2().pop().coerce_to_string(activation)?;
+                let name = activation.avm2().pop();
+                if let Some(qname) = name.as_qname() {
+                    return Ok(Self::from_qname_key(&qname));
+                }
+
                 Self {
                     ns: Self::abc_namespace_set(
                         translation_unit,
                         namespace_set.clone(),
                         activation.context.gc_context,
                     )?,
-                    name: Some(name),
+                    name: Some(name.coerce_to_string(activation)?),
                     params: Vec::new(),
                 }
             }
@@ -504,6 +552,21 @@ impl<'gc> Multiname<'gc> {
         })
     }
 
+    /// Build the multiname denoted by a `QName` object used as a property
+    /// key.
+    ///
+    /// The QName's namespace replaces any namespace set, and a local name of
+    /// `*` matches every name.
+    pub fn from_qname_key(qname: &QName<'gc>) -> Self {
+        let name = qname.local_name();
+
+        Self {
+            ns: vec![qname.namespace().clone()],
+            name: if &*name == "*" { None } else { Some(name) },
+            params: Vec::new(),
+        }
+    }
+
     /// Indicates the any type (any name in any namespace).
     pub fn any() -> Self {
         Self {
@@ -562,3 +625,104 @@ impl<'gc> From<QName<'gc>> for Multiname<'gc> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Multiname, Namespace, QName};
+
+    #[test]
+    fn qname_key_wildcards() {
+        let names = [
+            QName::new(Namespace::public(), "foo"),
+            QName::new(Namespace::package("flash.display"), "bar"),
+        ];
+
+        let any = Multiname::from_qname_key(&QName::new(Namespace::Any, "*"));
+        assert!(any.is_any());
+        assert!(names.iter().all(|name| any.contains_name(name)));
+
+        let any_public = Multiname::from_qname_key(&QName::new(Namespace::public(), "*"));
+        assert!(any_public.contains_name(&names[0]));
+        assert!(!any_public.contains_name(&names[1]));
+
+        let foo_anywhere = Multiname::from_qname_key(&QName::new(Namespace::Any, "foo"));
+        assert!(foo_anywhere.contains_name(&names[0]));
+        assert!(!foo_anywhere.contains_name(&names[1]));
+    }
+
+    #[test]
+    fn qname_script_strings() {
+        let cases = [
+            (Namespace::public(), "foo"),
+            (Namespace::package("flash.display"), "flash.display::foo"),
+            (
+                Namespace::Namespace("http://example.com".into()),
+                "http://example.com::foo",
+            ),
+            (Namespace::Any, "*::foo"),
+            (Namespace::internal(""), "::foo"),
+            (Namespace::private(""), "::foo"),
+            // Script code can't build an empty-URI namespace that isn't
+            // public, so those names never show an empty prefix.
+            (Namespace::from_uri("".into()), "foo"),
+        ];
+
+        for (ns, expected) in cases.iter() {
+            assert_eq!(QName::new(ns.clone(), "foo").to_script_string(), *expected);
+        }
+    }
+
+    #[test]
+    fn fresh_qnames_compare_by_uri() {
+        let stored = QName::new(Namespace::package("a"), "b");
+        let fresh = QName::new(Namespace::package("a"), "b");
+        assert!(fresh.eq_by_uri(&stored));
+        assert!(Multiname::from_qname_key(&fresh).contains_name(&stored));
+
+        let user_namespace = QName::new(Namespace::Namespace("a".into()), "b");
+        assert!(user_namespace.eq_by_uri(&stored));
+
+        let any_star = QName::new(Namespace::Any, "*");
+        let public_star = QName::new(Namespace::public(), "*");
+        assert!(any_star.eq_by_uri(&QName::new(Namespace::Any, "*")));
+        assert!(!any_star.eq_by_uri(&public_star));
+        assert!(!stored.eq_by_uri(&QName::new(Namespace::package("a"), "c")));
+    }
+
+    #[test]
+    fn script_uris_name_user_namespaces() {
+        assert_eq!(
+            Namespace::from_uri("http://x".into()),
+            Namespace::Namespace("http://x".into())
+        );
+        assert!(Namespace::from_uri("".into()).is_public());
+
+        // A script-constructed name is the same name as one declared in the
+        // namespace with that URI, and finds it when used as a key.
+        let declared = QName::new(Namespace::Namespace("http://x".into()), "p");
+        let constructed = QName::new(Namespace::from_uri("http://x".into()), "p");
+        assert_eq!(constructed, declared);
+        assert!(Multiname::from_qname_key(&constructed).contains_name(&declared));
+
+        // Package namespaces are only named by declarations, never by URI.
+        let package = QName::new(Namespace::package("http://x"), "p");
+        assert_ne!(constructed, package);
+        assert!(!Multiname::from_qname_key(&constructed).contains_name(&package));
+    }
+
+    #[test]
+    fn qname_key_namespaces_are_exact() {
+        let public_foo = QName::new(Namespace::public(), "foo");
+        let private_foo = QName::new(Namespace::private("Test"), "foo");
+        let internal_foo = QName::new(Namespace::internal(""), "foo");
+
+        let key = Multiname::from_qname_key(&public_foo);
+        assert!(key.contains_name(&public_foo));
+        assert!(!key.contains_name(&private_foo));
+        assert!(!key.contains_name(&internal_foo));
+
+        let key = Multiname::from_qname_key(&private_foo);
+        assert!(!key.contains_name(&public_foo));
+        assert!(key.contains_name(&private_foo));
+    }
+}
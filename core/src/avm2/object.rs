@@ -36,6 +36,7 @@ mod function_object;
 mod loaderinfo_object;
 mod namespace_object;
 mod primitive_object;
+mod qname_object;
 mod regexp_object;
 mod script_object;
 mod sound_object;
@@ -57,6 +58,7 @@ pub use crate::avm2::object::loaderinfo_object::{
 };
 pub use crate::avm2::object::namespace_object::{namespace_allocator, NamespaceObject};
 pub use crate::avm2::object::primitive_object::{primitive_allocator, PrimitiveObject};
+pub use crate::avm2::object::qname_object::{qname_allocator, QNameObject};
 pub use crate::avm2::object::regexp_object::{regexp_allocator, RegExpObject};
 pub use crate::avm2::object::script_object::ScriptObject;
 pub use crate::avm2::object::sound_object::{sound_allocator, SoundObject};
@@ -76,6 +78,7 @@ pub use crate::avm2::object::xml_object::{xml_allocator, XmlObject};
         FunctionObject(FunctionObject<'gc>),
         PrimitiveObject(PrimitiveObject<'gc>),
         NamespaceObject(NamespaceObject<'gc>),
+        QNameObject(QNameObject<'gc>),
         ArrayObject(ArrayObject<'gc>),
         StageObject(StageObject<'gc>),
         DomainObject(DomainObject<'gc>),
@@ -233,22 +236,24 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
 
     /// Resolve a multiname into a single QName, if any of the namespaces
     /// match.
+    ///
+    /// A multiname without a local name resolves to the first enumerable
+    /// property it matches.
     fn resolve_multiname(self, multiname: &Multiname<'gc>) -> Result<Option<QName<'gc>>, Error> {
+        let name = match multiname.local_name() {
+            Some(name) => name,
+            None => return Ok(self.matching_enumerants(multiname).into_iter().next()),
+        };
+
         for ns in multiname.namespace_set() {
             if ns.is_any() {
-                if let Some(name) = multiname.local_name() {
-                    let ns = self.resolve_any(name)?;
-                    return Ok(ns.map(|ns| QName::new(ns, name)));
-                } else {
-                    return Ok(None);
-                }
-            } else if let Some(name) = multiname.local_name() {
-                let qname = QName::new(ns.clone(), name);
-                if self.has_property(&qname)? {
-                    return Ok(Some(qname));
-                }
-            } else {
-                return Ok(None);
+                let ns = self.resolve_any(name)?;
+                return Ok(ns.map(|ns| QName::new(ns, name)));
+            }
+
+            let qname = QName::new(ns.clone(), name);
+            if self.has_property(&qname)? {
+                return Ok(Some(qname));
             }
         }
 
@@ -343,6 +348,23 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
     /// mechanism.
     fn get_enumerant_name(&self, index: u32) -> Option<QName<'gc>>;
 
+    /// List the enumerable names of this object that a multiname matches, in
+    /// enumeration order.
+    fn matching_enumerants(&self, multiname: &Multiname<'gc>) -> Vec<QName<'gc>> {
+        let mut names = Vec::new();
+        let mut index = 1;
+
+        while let Some(name) = self.get_enumerant_name(index) {
+            if multiname.contains_name(&name) {
+                names.push(name);
+            }
+
+            index += 1;
+        }
+
+        names
+    }
+
     /// Determine if a property is currently enumerable.
     ///
     /// Properties that do not exist are also not enumerable.
@@ -1118,6 +1140,27 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
         None
     }
 
+    /// Unwrap this object as a namespace object.
+    fn as_namespace_object(&self) -> Option<NamespaceObject<'gc>> {
+        None
+    }
+
+    /// Unwrap this object's `QName`, if the object is a boxed QName.
+    fn as_qname(&self) -> Option<Ref<QName<'gc>>> {
+        None
+    }
+
+    /// Unwrap this object's `QName` for mutation, if the object is a boxed
+    /// QName.
+    fn as_qname_mut(&self, _mc: MutationContext<'gc, '_>) -> Option<RefMut<QName<'gc>>> {
+        None
+    }
+
+    /// Unwrap this object as a QName object.
+    fn as_qname_object(&self) -> Option<QNameObject<'gc>> {
+        None
+    }
+
     /// Unwrap this object as array storage.
     fn as_array_storage(&self) -> Option<Ref<ArrayStorage<'gc>>> {
         None
@@ -1282,3 +1325,54 @@ impl<'gc> Hash for Object<'gc> {
         self.as_ptr().hash(state);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ScriptObject, TObject};
+    use crate::avm2::names::{Multiname, Namespace, QName};
+    use crate::avm2::value::Value;
+
+    #[test]
+    fn wildcard_qname_keys_enumerate_properties() {
+        gc_arena::rootless_arena(|mc| {
+            let mut object = ScriptObject::bare_object(mc);
+            let names = [
+                QName::new(Namespace::public(), "a"),
+                QName::new(Namespace::Namespace("http://x".into()), "b"),
+                QName::new(Namespace::public(), "c"),
+            ];
+            for (i, name) in names.iter().enumerate() {
+                object
+                    .install_dynamic_property(mc, name.clone(), Value::Number(i as f64))
+                    .unwrap();
+                object
+                    .set_local_property_is_enumerable(mc, name, true)
+                    .unwrap();
+            }
+
+            let any = Multiname::from_qname_key(&QName::new(Namespace::Any, "*"));
+            assert_eq!(object.matching_enumerants(&any), names.to_vec());
+            assert_eq!(
+                object.resolve_multiname(&any).unwrap(),
+                Some(names[0].clone())
+            );
+
+            let public = Multiname::from_qname_key(&QName::new(Namespace::public(), "*"));
+            assert_eq!(
+                object.matching_enumerants(&public),
+                vec![names[0].clone(), names[2].clone()]
+            );
+
+            let user =
+                Multiname::from_qname_key(&QName::new(Namespace::from_uri("http://x".into()), "*"));
+            assert_eq!(
+                object.resolve_multiname(&user).unwrap(),
+                Some(names[1].clone())
+            );
+
+            let none = Multiname::from_qname_key(&QName::new(Namespace::private("T"), "*"));
+            assert!(object.matching_enumerants(&none).is_empty());
+            assert_eq!(object.resolve_multiname(&none).unwrap(), None);
+        })
+    }
+}
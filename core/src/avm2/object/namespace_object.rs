@@ -28,6 +28,7 @@ pub fn namespace_allocator<'gc>(
         NamespaceObjectData {
             base,
             namespace: Namespace::public(),
+            prefix: Some("".into()),
         },
     ))
     .into())
@@ -46,6 +47,9 @@ pub struct NamespaceObjectData<'gc> {
 
     /// The namespace name this object is associated with.
     namespace: Namespace<'gc>,
+
+    /// The prefix of this namespace, or `None` if it is undefined.
+    prefix: Option<AvmString<'gc>>,
 }
 
 impl<'gc> NamespaceObject<'gc> {
@@ -58,9 +62,20 @@ impl<'gc> NamespaceObject<'gc> {
         let proto = activation.avm2().prototypes().namespace;
         let base = ScriptObjectData::base_new(Some(proto), Some(class));
 
+        // Only the unnamed namespace has a known prefix.
+        let prefix = if namespace.as_uri().is_empty() {
+            Some("".into())
+        } else {
+            None
+        };
+
         let mut this: Object<'gc> = NamespaceObject(GcCell::allocate(
             activation.context.gc_context,
-            NamespaceObjectData { base, namespace },
+            NamespaceObjectData {
+                base,
+                namespace,
+                prefix,
+            },
         ))
         .into();
         this.install_instance_traits(activation, class)?;
@@ -69,6 +84,21 @@ impl<'gc> NamespaceObject<'gc> {
 
         Ok(this)
     }
+
+    /// Change the namespace name this object is associated with.
+    pub fn set_namespace(&self, mc: MutationContext<'gc, '_>, namespace: Namespace<'gc>) {
+        self.0.write(mc).namespace = namespace;
+    }
+
+    /// Get the prefix of this namespace, if it has one.
+    pub fn prefix(&self) -> Option<AvmString<'gc>> {
+        self.0.read().prefix
+    }
+
+    /// Change the prefix of this namespace.
+    pub fn set_prefix(&self, mc: MutationContext<'gc, '_>, prefix: Option<AvmString<'gc>>) {
+        self.0.write(mc).prefix = prefix;
+    }
 }
 
 impl<'gc> TObject<'gc> for NamespaceObject<'gc> {
@@ -88,6 +118,10 @@ impl<'gc> TObject<'gc> for NamespaceObject<'gc> {
         Some(Ref::map(self.0.read(), |s| &s.namespace))
     }
 
+    fn as_namespace_object(&self) -> Option<NamespaceObject<'gc>> {
+        Some(*self)
+    }
+
     fn derive(&self, activation: &mut Activation<'_, 'gc, '_>) -> Result<Object<'gc>, Error> {
         let this: Object<'gc> = Object::NamespaceObject(*self);
         let base = ScriptObjectData::base_new(Some(this), None);
@@ -97,6 +131,7 @@ impl<'gc> TObject<'gc> for NamespaceObject<'gc> {
             NamespaceObjectData {
                 base,
                 namespace: Namespace::public(),
+                prefix: Some("".into()),
             },
         ))
         .into())
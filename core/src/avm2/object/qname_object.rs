@@ -0,0 +1,106 @@
+//! Boxed QNames
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::script_object::ScriptObjectData;
+use crate::avm2::object::{Object, ObjectPtr, TObject};
+use crate::avm2::scope::Scope;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::string::AvmString;
+use crate::{
+    impl_avm2_custom_object, impl_avm2_custom_object_instance, impl_avm2_custom_object_properties,
+};
+use gc_arena::{Collect, GcCell, MutationContext};
+use std::cell::{Ref, RefMut};
+
+/// A class instance allocator that allocates QName objects.
+pub fn qname_allocator<'gc>(
+    class: Object<'gc>,
+    proto: Object<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Object<'gc>, Error> {
+    let base = ScriptObjectData::base_new(Some(proto), Some(class));
+
+    Ok(QNameObject(GcCell::allocate(
+        activation.context.gc_context,
+        QNameObjectData {
+            base,
+            qname: QName::new(Namespace::public(), ""),
+            prefix: None,
+        },
+    ))
+    .into())
+}
+
+/// An Object which represents a boxed QName.
+#[derive(Collect, Debug, Clone, Copy)]
+#[collect(no_drop)]
+pub struct QNameObject<'gc>(GcCell<'gc, QNameObjectData<'gc>>);
+
+#[derive(Collect, Debug, Clone)]
+#[collect(no_drop)]
+pub struct QNameObjectData<'gc> {
+    /// All normal script data.
+    base: ScriptObjectData<'gc>,
+
+    /// The QName this object is associated with.
+    qname: QName<'gc>,
+
+    /// The prefix of the namespace this QName was constructed from, if any.
+    prefix: Option<AvmString<'gc>>,
+}
+
+impl<'gc> QNameObject<'gc> {
+    /// Get the namespace prefix of this QName, if it has one.
+    pub fn prefix(&self) -> Option<AvmString<'gc>> {
+        self.0.read().prefix
+    }
+
+    /// Set the namespace prefix of this QName.
+    pub fn set_prefix(&self, mc: MutationContext<'gc, '_>, prefix: Option<AvmString<'gc>>) {
+        self.0.write(mc).prefix = prefix;
+    }
+}
+
+impl<'gc> TObject<'gc> for QNameObject<'gc> {
+    impl_avm2_custom_object!(base);
+    impl_avm2_custom_object_properties!(base);
+    impl_avm2_custom_object_instance!(base);
+
+    fn to_string(&self, mc: MutationContext<'gc, '_>) -> Result<Value<'gc>, Error> {
+        Ok(AvmString::new(mc, self.0.read().qname.to_script_string()).into())
+    }
+
+    fn value_of(&self, _mc: MutationContext<'gc, '_>) -> Result<Value<'gc>, Error> {
+        Ok(Object::from(*self).into())
+    }
+
+    fn as_qname(&self) -> Option<Ref<QName<'gc>>> {
+        Some(Ref::map(self.0.read(), |s| &s.qname))
+    }
+
+    fn as_qname_mut(&self, mc: MutationContext<'gc, '_>) -> Option<RefMut<QName<'gc>>> {
+        Some(RefMut::map(self.0.write(mc), |s| &mut s.qname))
+    }
+
+    fn as_qname_object(&self) -> Option<QNameObject<'gc>> {
+        Some(*self)
+    }
+
+    fn derive(&self, activation: &mut Activation<'_, 'gc, '_>) -> Result<Object<'gc>, Error> {
+        let this: Object<'gc> = Object::QNameObject(*self);
+        let base = ScriptObjectData::base_new(Some(this), None);
+
+        Ok(QNameObject(GcCell::allocate(
+            activation.context.gc_context,
+            QNameObjectData {
+                base,
+                qname: QName::new(Namespace::public(), ""),
+                prefix: None,
+            },
+        ))
+        .into())
+    }
+}
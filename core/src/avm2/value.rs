@@ -225,6 +225,14 @@ impl<'gc> Value<'gc> {
         }
     }
 
+    /// Get the QName held by this value, if it's a `QName` object.
+    pub fn as_qname(&self) -> Option<QName<'gc>> {
+        match self {
+            Value::Object(o) => o.as_qname().map(|qname| qname.clone()),
+            _ => None,
+        }
+    }
+
     /// Get the numerical portion of the value, if it exists.
     ///
     /// This function performs no numerical coercion, nor are user-defined
@@ -732,7 +740,14 @@ impl<'gc> Value<'gc> {
             }
             (Value::String(a), Value::String(b)) => Ok(a == b),
             (Value::Bool(a), Value::Bool(b)) => Ok(a == b),
-            (Value::Object(a), Value::Object(b)) => Ok(Object::ptr_eq(*a, *b)),
+            (Value::Object(a), Value::Object(b)) => {
+                // QNames compare by value, not by identity.
+                if let (Some(a), Some(b)) = (a.as_qname(), b.as_qname()) {
+                    return Ok(a.eq_by_uri(&b));
+                }
+
+                Ok(Object::ptr_eq(*a, *b))
+            }
             (Value::Undefined, Value::Null) => Ok(true),
             (Value::Null, Value::Undefined) => Ok(true),
             (Value::Number(_) | Value::Unsigned(_) | Value::Integer(_), Value::String(_)) => {
@@ -30,6 +30,26 @@ pub trait AudioBackend: Downcast {
     fn pause(&mut self);
     fn register_sound(&mut self, swf_sound: &swf::Sound) -> Result<SoundHandle, Error>;
 
+    /// Registers a standalone MP3 file, such as one provided by script code.
+    ///
+    /// The sound format and length are read from the MP3 frame headers.
+    fn register_mp3(&mut self, data: &[u8]) -> Result<SoundHandle, Error> {
+        let (format, num_samples) = read_mp3_format(data)?;
+
+        // MP3 data in SWFs starts with a latency seek, which standalone
+        // MP3 files lack.
+        let mut swf_data = Vec::with_capacity(data.len() + 2);
+        swf_data.extend_from_slice(&[0, 0]);
+        swf_data.extend_from_slice(data);
+
+        self.register_sound(&swf::Sound {
+            id: 0,
+            format,
+            num_samples,
+            data: &swf_data,
+        })
+    }
+
     /// Used by the web backend to pre-decode sound streams.
     /// Returns the sound handle to be used to add data to the stream.
     /// Other backends return `None`.
@@ -122,6 +142,123 @@ pub trait AudioBackend: Downcast {
 
 impl_downcast!(AudioBackend);
 
+/// The reason an MP3 file could not be registered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum DecodeError {
+    #[error("MP3 data is empty")]
+    Empty,
+
+    #[error("ID3 tag is longer than the MP3 data")]
+    TruncatedId3Tag,
+
+    #[error("free-format MP3 bitrates are not supported")]
+    FreeFormatBitrate,
+
+    #[error("no MPEG Layer III frames found")]
+    NoFrames,
+}
+
+/// Read the sound format and total number of samples of an MP3 file.
+fn read_mp3_format(data: &[u8]) -> Result<(swf::SoundFormat, u32), DecodeError> {
+    const MPEG1_BITRATES: [u32; 15] = [
+        0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+    ];
+    const MPEG2_BITRATES: [u32; 15] =
+        [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+    const SAMPLE_RATES: [u32; 3] = [44100, 48000, 32000];
+
+    if data.is_empty() {
+        return Err(DecodeError::Empty);
+    }
+
+    let mut pos = 0;
+
+    // Skip an ID3v2 tag, whose size is stored as a 28-bit syncsafe integer.
+    if data.len() >= 10 && &data[0..3] == b"ID3" {
+        let size = data[6..10]
+            .iter()
+            .fold(0, |size, byte| (size << 7) | usize::from(byte & 0x7F));
+        pos = 10 + size;
+
+        if pos >= data.len() {
+            return Err(DecodeError::TruncatedId3Tag);
+        }
+    }
+
+    let mut format = None;
+    let mut num_samples = 0;
+    let mut saw_free_format = false;
+
+    while let Some(header) = data.get(pos..pos + 4) {
+        // 0 = MPEG 2.5, 1 = reserved, 2 = MPEG 2, 3 = MPEG 1
+        let version = (header[1] >> 3) & 0b11;
+        // 1 = Layer III
+        let layer = (header[1] >> 1) & 0b11;
+        let bitrate_index = usize::from(header[2] >> 4);
+        let sample_rate_index = usize::from((header[2] >> 2) & 0b11);
+
+        let is_valid = header[0] == 0xFF
+            && header[1] & 0xE0 == 0xE0
+            && version != 1
+            && layer == 1
+            && bitrate_index != 0
+            && bitrate_index != 15
+            && sample_rate_index != 3;
+
+        if !is_valid {
+            if header[0] == 0xFF && header[1] & 0xE0 == 0xE0 && layer == 1 && bitrate_index == 0 {
+                saw_free_format = true;
+            }
+
+            if format.is_some() {
+                // Trailing data, such as an ID3v1 tag.
+                break;
+            }
+
+            // Search for the first frame.
+            pos += 1;
+            continue;
+        }
+
+        let (bitrate, sample_rate, samples_per_frame) = match version {
+            3 => (
+                MPEG1_BITRATES[bitrate_index],
+                SAMPLE_RATES[sample_rate_index],
+                1152,
+            ),
+            2 => (
+                MPEG2_BITRATES[bitrate_index],
+                SAMPLE_RATES[sample_rate_index] / 2,
+                576,
+            ),
+            _ => (
+                MPEG2_BITRATES[bitrate_index],
+                SAMPLE_RATES[sample_rate_index] / 4,
+                576,
+            ),
+        };
+        let padding = u32::from((header[2] >> 1) & 1);
+        let is_stereo = header[3] >> 6 != 0b11;
+
+        format.get_or_insert(swf::SoundFormat {
+            compression: swf::AudioCompression::Mp3,
+            sample_rate: sample_rate as u16,
+            is_stereo,
+            is_16_bit: true,
+        });
+        num_samples += samples_per_frame;
+
+        let frame_len = samples_per_frame / 8 * bitrate * 1000 / sample_rate + padding;
+        pos += frame_len as usize;
+    }
+
+    match format {
+        Some(format) => Ok((format, num_samples)),
+        None if saw_free_format => Err(DecodeError::FreeFormatBitrate),
+        None => Err(DecodeError::NoFrames),
+    }
+}
+
 /// Information about a sound provided to `NullAudioBackend`.
 struct NullSound {
     /// The duration of the sound in milliseconds.
@@ -598,3 +735,56 @@ impl Default for SoundTransform {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn register_mp3_reads_frame_headers() {
+        // Three MPEG-1 Layer III frames: 128kbps, 44.1kHz, joint stereo.
+        let mut data = b"junk".to_vec();
+        for _ in 0..3 {
+            let mut frame = vec![0; 417];
+            frame[0..4].copy_from_slice(&[0xFF, 0xFB, 0x90, 0x44]);
+            data.extend_from_slice(&frame);
+        }
+
+        let (format, num_samples) = read_mp3_format(&data).unwrap();
+        assert_eq!(format.sample_rate, 44100);
+        assert!(format.is_stereo);
+        assert_eq!(num_samples, 3 * 1152);
+
+        let mut audio = NullAudioBackend::new();
+        let sound = audio.register_mp3(&data).unwrap();
+        let duration = audio.get_sound_duration(sound).unwrap();
+        assert!((duration - 3.0 * 1152.0 * 1000.0 / 44100.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn register_mp3_rejects_corrupt_data() {
+        let mut audio = NullAudioBackend::new();
+        let error = audio.register_mp3(b"not an mp3 file").unwrap_err();
+        assert!(error.to_string().contains("no MPEG Layer III frames"));
+        assert!(audio.register_mp3(&[]).is_err());
+    }
+
+    #[test]
+    fn read_mp3_format_reports_decode_reason() {
+        assert_eq!(read_mp3_format(&[]), Err(DecodeError::Empty));
+        assert_eq!(
+            read_mp3_format(b"ID3\x04\x00\x00\x00\x00\x7F\x7F"),
+            Err(DecodeError::TruncatedId3Tag)
+        );
+
+        // A 44.1kHz MPEG-1 Layer III header with a free-format bitrate.
+        let mut data = vec![0; 64];
+        data[0..4].copy_from_slice(&[0xFF, 0xFB, 0x00, 0x44]);
+        assert_eq!(read_mp3_format(&data), Err(DecodeError::FreeFormatBitrate));
+
+        assert_eq!(
+            read_mp3_format(b"RIFF\x24\x00\x00\x00WAVEfmt "),
+            Err(DecodeError::NoFrames)
+        );
+    }
+}
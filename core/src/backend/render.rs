@@ -549,22 +549,37 @@ pub fn decode_png(data: &[u8]) -> Result<Bitmap, Error> {
     use png::{ColorType, Transformations};
 
     let mut decoder = png::Decoder::new(data);
-    // EXPAND expands palettized types to RGB.
-    decoder.set_transformations(Transformations::EXPAND);
+    // EXPAND expands palettized types to RGB, or RGBA if they have transparency,
+    // and low bit depths to 8 bits. STRIP_16 reduces high bit depths to 8 bits.
+    decoder.set_transformations(Transformations::EXPAND | Transformations::STRIP_16);
     let mut reader = decoder.read_info()?;
 
     let mut data = vec![0; reader.output_buffer_size()];
     let info = reader.next_frame(&mut data)?;
 
+    let data = match info.color_type {
+        ColorType::Rgba => BitmapFormat::Rgba(data),
+        ColorType::GrayscaleAlpha => {
+            let mut rgba = Vec::with_capacity(data.len() * 2);
+            for pixel in data.chunks_exact(2) {
+                rgba.extend_from_slice(&[pixel[0], pixel[0], pixel[0], pixel[1]]);
+            }
+            BitmapFormat::Rgba(rgba)
+        }
+        ColorType::Grayscale => {
+            let mut rgb = Vec::with_capacity(data.len() * 3);
+            for &gray in &data {
+                rgb.extend_from_slice(&[gray, gray, gray]);
+            }
+            BitmapFormat::Rgb(rgb)
+        }
+        _ => BitmapFormat::Rgb(data),
+    };
+
     Ok(Bitmap {
         width: info.width,
         height: info.height,
-        data: if info.color_type == ColorType::Rgba {
-            BitmapFormat::Rgba(data)
-        } else {
-            // EXPAND expands other types to RGB.
-            BitmapFormat::Rgb(data)
-        },
+        data,
     })
 }
 
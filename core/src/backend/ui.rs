@@ -29,11 +29,28 @@ pub trait UiBackend: Downcast {
     /// by providing a direct .swf link instead.
     fn display_root_movie_download_failed_message(&self);
 
+    /// Displays a message about a root movie that was downloaded, but can't
+    /// be played.
+    fn display_root_movie_invalid_message(&self, reason: InvalidMovieReason);
+
     // Unused, but kept in case we need it later
     fn message(&self, message: &str);
 }
 impl_downcast!(UiBackend);
 
+/// Why a downloaded root movie can't be played.
+/// Communicated from the core to the UI backend via
+/// `UiBackend::display_root_movie_invalid_message`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvalidMovieReason {
+    /// The file isn't a SWF movie.
+    NotSwf,
+
+    /// The file claims to be a SWF movie of the given version, which is
+    /// newer than any Flash Player, and couldn't be read.
+    UnsupportedVersion(u8),
+}
+
 /// A mouse cursor icon displayed by the Flash Player.
 /// Communicated from the core to the UI backend via `UiBackend::set_mouse_cursor`.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -95,6 +112,8 @@ impl UiBackend for NullUiBackend {
 
     fn display_root_movie_download_failed_message(&self) {}
 
+    fn display_root_movie_invalid_message(&self, _reason: InvalidMovieReason) {}
+
     fn message(&self, _message: &str) {}
 }
 
@@ -0,0 +1,335 @@
+//! HTTP cookies shared between loads
+
+use chrono::{DateTime, Duration, Utc};
+use url::Url;
+
+/// Common second-level domains under which anyone can register a name.
+///
+/// Cookies may not be set for these, as they would be shared between
+/// unrelated sites. This is a small subset of the Public Suffix List, which
+/// covers the country-code registries most often seen.
+const PUBLIC_SUFFIXES: &[&str] = &[
+    "ac.jp", "ac.uk", "co.in", "co.jp", "co.kr", "co.nz", "co.uk", "co.za", "com.ar", "com.au",
+    "com.br", "com.cn", "com.hk", "com.mx", "com.sg", "com.tr", "com.tw", "gov.uk", "ne.jp",
+    "net.au", "or.jp", "org.au", "org.uk",
+];
+
+/// A cookie received in a `Set-Cookie` header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Cookie {
+    name: String,
+    value: String,
+
+    /// The domain the cookie is sent to.
+    domain: String,
+
+    /// Whether the cookie is only sent to `domain` itself, and not to its
+    /// subdomains.
+    host_only: bool,
+
+    /// The path the cookie is sent to, along with everything below it.
+    path: String,
+
+    /// Whether the cookie is only sent over HTTPS.
+    secure: bool,
+
+    /// When the cookie expires, or `None` if it lasts for the session.
+    expires: Option<DateTime<Utc>>,
+}
+
+impl Cookie {
+    /// Parse a `Set-Cookie` header sent in response to a request to `url`.
+    ///
+    /// Returns `None` if the cookie is malformed, or if the server isn't
+    /// allowed to set it.
+    fn parse(url: &Url, set_cookie: &str, now: DateTime<Utc>) -> Option<Self> {
+        let host = url.host_str()?.to_ascii_lowercase();
+        let mut attributes = set_cookie.split(';');
+        let (name, value) = attributes.next()?.split_once('=')?;
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+
+        let mut cookie = Self {
+            name: name.to_string(),
+            value: value.trim().to_string(),
+            domain: host.clone(),
+            host_only: true,
+            path: default_path(url),
+            secure: false,
+            expires: None,
+        };
+        let mut max_age = None;
+
+        for attribute in attributes {
+            let (key, value) = attribute.split_once('=').unwrap_or((attribute, ""));
+            let value = value.trim();
+            match key.trim().to_ascii_lowercase().as_str() {
+                "domain" if !value.is_empty() => {
+                    let domain = value.trim_start_matches('.').to_ascii_lowercase();
+                    if !domain_matches(&host, &domain) {
+                        return None;
+                    }
+                    if is_public_suffix(&domain) {
+                        // A host may still set a cookie for itself, but it
+                        // isn't shared with any other host.
+                        if domain != host {
+                            return None;
+                        }
+                        continue;
+                    }
+                    cookie.domain = domain;
+                    cookie.host_only = false;
+                }
+                "path" if value.starts_with('/') => cookie.path = value.to_string(),
+                "secure" => cookie.secure = true,
+                "max-age" => max_age = value.parse::<i64>().ok(),
+                "expires" => {
+                    if let Ok(expires) = DateTime::parse_from_rfc2822(value) {
+                        cookie.expires = Some(expires.with_timezone(&Utc));
+                    }
+                }
+                // `HttpOnly` cookies must never be shown to content, which is
+                // already the case for every cookie, as there's no script API
+                // for them.
+                _ => {}
+            }
+        }
+
+        // `Max-Age` takes precedence over `Expires`.
+        if let Some(max_age) = max_age {
+            cookie.expires = Some(now + Duration::seconds(max_age.max(0)));
+        }
+
+        // Insecure origins may not set secure cookies.
+        if cookie.secure && url.scheme() != "https" {
+            return None;
+        }
+
+        Some(cookie)
+    }
+
+    /// Check if this cookie has expired.
+    fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        matches!(self.expires, Some(expires) if expires <= now)
+    }
+
+    /// Check if this cookie should be sent with a request to `url`.
+    fn matches(&self, url: &Url, now: DateTime<Utc>) -> bool {
+        let host = match url.host_str() {
+            Some(host) => host.to_ascii_lowercase(),
+            None => return false,
+        };
+        let domain_matches = if self.host_only {
+            host == self.domain
+        } else {
+            domain_matches(&host, &self.domain)
+        };
+
+        domain_matches
+            && path_matches(url.path(), &self.path)
+            && (!self.secure || url.scheme() == "https")
+            && !self.is_expired(now)
+    }
+}
+
+/// Check if a domain is shared by unrelated sites, such as `com` or `co.uk`.
+///
+/// Every single-label domain is treated as a top-level domain.
+fn is_public_suffix(domain: &str) -> bool {
+    !domain.contains('.') || PUBLIC_SUFFIXES.contains(&domain)
+}
+
+/// Check if a host is the given domain or one of its subdomains.
+fn domain_matches(host: &str, domain: &str) -> bool {
+    host == domain || host.ends_with(&format!(".{}", domain))
+}
+
+/// Check if a request path is the given cookie path or below it.
+fn path_matches(request_path: &str, cookie_path: &str) -> bool {
+    request_path == cookie_path
+        || (request_path.starts_with(cookie_path)
+            && (cookie_path.ends_with('/') || request_path[cookie_path.len()..].starts_with('/')))
+}
+
+/// The path a cookie applies to if it doesn't specify one: the directory of
+/// the URL that set it.
+fn default_path(url: &Url) -> String {
+    match url.path().rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(end) => url.path()[..end].to_string(),
+    }
+}
+
+/// The cookies set by the responses to the loaders' fetches.
+///
+/// Cookies are kept in memory for as long as the player exists, and are sent
+/// back with later fetches in the same way as a browser would.
+#[derive(Debug, Clone, Default)]
+pub struct CookieJar {
+    cookies: Vec<Cookie>,
+}
+
+impl CookieJar {
+    /// Construct an empty cookie jar.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store the cookie set by a `Set-Cookie` header in a response to `url`.
+    ///
+    /// A cookie that has already expired removes any cookie it replaces.
+    pub fn store(&mut self, url: &Url, set_cookie: &str, now: DateTime<Utc>) {
+        let cookie = match Cookie::parse(url, set_cookie, now) {
+            Some(cookie) => cookie,
+            None => return,
+        };
+
+        self.cookies.retain(|existing| {
+            !(existing.name == cookie.name
+                && existing.domain == cookie.domain
+                && existing.path == cookie.path)
+                && !existing.is_expired(now)
+        });
+
+        if !cookie.is_expired(now) {
+            self.cookies.push(cookie);
+        }
+    }
+
+    /// Build the `Cookie` header to send with a request to `url`, if any
+    /// cookies apply to it.
+    ///
+    /// Cookies with longer paths are listed first.
+    pub fn cookie_header(&self, url: &Url, now: DateTime<Utc>) -> Option<String> {
+        let mut cookies: Vec<&Cookie> = self
+            .cookies
+            .iter()
+            .filter(|cookie| cookie.matches(url, now))
+            .collect();
+        if cookies.is_empty() {
+            return None;
+        }
+
+        cookies.sort_by(|a, b| b.path.len().cmp(&a.path.len()));
+        let pairs: Vec<String> = cookies
+            .iter()
+            .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+            .collect();
+        Some(pairs.join("; "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CookieJar;
+    use chrono::{Duration, TimeZone, Utc};
+    use url::Url;
+
+    fn url(url: &str) -> Url {
+        Url::parse(url).unwrap()
+    }
+
+    #[test]
+    fn cookies_are_sent_back_to_their_origin() {
+        let now = Utc.ymd(2021, 6, 1).and_hms(12, 0, 0);
+        let mut jar = CookieJar::new();
+        jar.store(
+            &url("http://example.com/game/login.php"),
+            "session=abc123; HttpOnly",
+            now,
+        );
+        jar.store(
+            &url("http://example.com/game/login.php"),
+            "theme=dark; Path=/; Domain=.example.com",
+            now,
+        );
+
+        assert_eq!(
+            jar.cookie_header(&url("http://example.com/game/level1.swf"), now),
+            Some("session=abc123; theme=dark".to_string())
+        );
+
+        // The session cookie is limited to its host and path.
+        assert_eq!(
+            jar.cookie_header(&url("http://cdn.example.com/game/level1.swf"), now),
+            Some("theme=dark".to_string())
+        );
+        assert_eq!(
+            jar.cookie_header(&url("http://example.com/gameover.swf"), now),
+            Some("theme=dark".to_string())
+        );
+        assert_eq!(jar.cookie_header(&url("http://evil.com/"), now), None);
+    }
+
+    #[test]
+    fn secure_cookies_need_https() {
+        let now = Utc.ymd(2021, 6, 1).and_hms(12, 0, 0);
+        let mut jar = CookieJar::new();
+        jar.store(&url("http://example.com/"), "insecure=1; Secure", now);
+        jar.store(&url("https://example.com/"), "token=xyz; Secure", now);
+
+        assert_eq!(
+            jar.cookie_header(&url("https://example.com/data.xml"), now),
+            Some("token=xyz".to_string())
+        );
+        assert_eq!(
+            jar.cookie_header(&url("http://example.com/data.xml"), now),
+            None
+        );
+    }
+
+    #[test]
+    fn cookies_expire_and_are_replaced() {
+        let now = Utc.ymd(2021, 6, 1).and_hms(12, 0, 0);
+        let page = url("http://example.com/");
+        let mut jar = CookieJar::new();
+        jar.store(&page, "a=1; Max-Age=60", now);
+        jar.store(&page, "b=1; Expires=Tue, 01 Jun 2021 13:00:00 GMT", now);
+        jar.store(&page, "c=1", now);
+        jar.store(&page, "c=2", now);
+        jar.store(&url("http://other.com/"), "d=1; Domain=example.com", now);
+
+        assert_eq!(
+            jar.cookie_header(&page, now),
+            Some("a=1; b=1; c=2".to_string())
+        );
+        assert_eq!(
+            jar.cookie_header(&page, now + Duration::minutes(30)),
+            Some("b=1; c=2".to_string())
+        );
+
+        jar.store(&page, "c=; Max-Age=0", now);
+        assert_eq!(jar.cookie_header(&page, now), Some("a=1; b=1".to_string()));
+    }
+
+    #[test]
+    fn cookies_cannot_be_set_for_public_suffixes() {
+        let now = Utc.ymd(2021, 6, 1).and_hms(12, 0, 0);
+        let mut jar = CookieJar::new();
+        jar.store(&url("http://shop.example.co.uk/"), "a=1; Domain=co.uk", now);
+        jar.store(&url("http://shop.example.co.uk/"), "b=1; Domain=.uk", now);
+        jar.store(&url("http://example.com/"), "c=1; Domain=com", now);
+        jar.store(
+            &url("http://shop.example.co.uk/"),
+            "d=1; Domain=example.co.uk",
+            now,
+        );
+
+        assert_eq!(jar.cookie_header(&url("http://other.co.uk/"), now), None);
+        assert_eq!(jar.cookie_header(&url("http://other.com/"), now), None);
+        assert_eq!(
+            jar.cookie_header(&url("http://www.example.co.uk/"), now),
+            Some("d=1".to_string())
+        );
+
+        // A host that is itself a public suffix only gets a host-only cookie.
+        jar.store(&url("http://localhost/"), "e=1; Domain=localhost", now);
+        assert_eq!(
+            jar.cookie_header(&url("http://localhost/"), now),
+            Some("e=1".to_string())
+        );
+        assert_eq!(jar.cookie_header(&url("http://www.localhost/"), now), None);
+    }
+}
@@ -37,7 +37,7 @@ mod video;
 use crate::avm1::activation::Activation;
 use crate::backend::ui::MouseCursor;
 pub use crate::display_object::container::{
-    DisplayObjectContainer, Lists, TDisplayObjectContainer,
+    dispatch_removed_event, DisplayObjectContainer, Lists, TDisplayObjectContainer,
 };
 use crate::events::{ClipEvent, ClipEventResult};
 pub use avm1_button::{Avm1Button, ButtonState, ButtonTracking};
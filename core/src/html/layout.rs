@@ -395,13 +395,12 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
         span: &TextSpan,
         is_device_font: bool,
     ) -> Option<Font<'gc>> {
-        let library = context.library.library_for_movie_mut(self.movie.clone());
-
         // If this text field is set to use device fonts, fallback to using our embedded Noto Sans.
         // Note that the SWF can still contain a DefineFont tag with no glyphs/layout info in this case (see #451).
         // In an ideal world, device fonts would search for a matching font on the system and render it in some way.
-        if let Some(font) = library
-            .get_font_by_name(&span.font, span.bold, span.italic)
+        if let Some(font) = context
+            .library
+            .font_by_name_or_shared(self.movie.clone(), &span.font, span.bold, span.italic)
             .filter(|f| !is_device_font && f.has_glyphs())
             .or_else(|| context.library.device_font())
         {
@@ -456,10 +455,9 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
     /// should be appended after line fixup has completed, but before the text
     /// cursor is moved down.
     fn append_bullet(&mut self, context: &mut UpdateContext<'_, 'gc, '_>, span: &TextSpan) {
-        let library = context.library.library_for_movie_mut(self.movie.clone());
-
-        if let Some(bullet_font) = library
-            .get_font_by_name(&span.font, span.bold, span.italic)
+        if let Some(bullet_font) = context
+            .library
+            .font_by_name_or_shared(self.movie.clone(), &span.font, span.bold, span.italic)
             .filter(|f| f.has_glyphs())
             .or_else(|| context.library.device_font())
             .or(self.font)
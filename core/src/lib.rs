@@ -31,6 +31,7 @@ mod character;
 pub mod color_transform;
 pub mod context;
 pub mod context_menu;
+mod cookie_jar;
 mod drawing;
 mod ecma_conversions;
 pub mod events;
@@ -41,6 +42,7 @@ mod library;
 pub mod loader;
 pub mod matrix;
 mod player;
+mod policy_file;
 mod prelude;
 pub mod shape_utils;
 pub mod string;
@@ -61,3 +63,4 @@ pub use indexmap;
 pub use player::Player;
 pub use swf;
 pub use swf::Color;
+pub use url;
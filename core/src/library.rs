@@ -209,6 +209,17 @@ impl<'gc> MovieLibrary<'gc> {
         self.avm1_constructor_registry
     }
 
+    /// Returns every font that this movie exports.
+    pub fn exported_fonts(&self) -> Vec<Font<'gc>> {
+        self.export_characters
+            .iter()
+            .filter_map(|(_, character)| match character {
+                Character::Font(font) => Some(*font),
+                _ => None,
+            })
+            .collect()
+    }
+
     /// Instantiates the library item with the given character ID into a display object.
     /// The object must then be post-instantiated before being used.
     pub fn instantiate_by_id(
@@ -398,6 +409,9 @@ pub struct Library<'gc> {
     /// The embedded device font.
     device_font: Option<Font<'gc>>,
 
+    /// Fonts that loaded movies have shared with every other movie.
+    shared_fonts: HashMap<FontDescriptor, Font<'gc>>,
+
     constructor_registry_case_insensitive: Gc<'gc, Avm1ConstructorRegistry<'gc>>,
     constructor_registry_case_sensitive: Gc<'gc, Avm1ConstructorRegistry<'gc>>,
 
@@ -413,6 +427,7 @@ unsafe impl<'gc> gc_arena::Collect for Library<'gc> {
             val.trace(cc);
         }
         self.device_font.trace(cc);
+        self.shared_fonts.trace(cc);
         self.constructor_registry_case_insensitive.trace(cc);
         self.constructor_registry_case_sensitive.trace(cc);
         self.avm2_class_registry.trace(cc);
@@ -424,6 +439,7 @@ impl<'gc> Library<'gc> {
         Self {
             movie_libraries: PtrWeakKeyHashMap::new(),
             device_font: None,
+            shared_fonts: HashMap::new(),
             constructor_registry_case_insensitive: Gc::allocate(
                 gc_context,
                 Avm1ConstructorRegistry::new(false, gc_context),
@@ -465,6 +481,39 @@ impl<'gc> Library<'gc> {
         self.device_font = font;
     }
 
+    /// Share a font with every movie.
+    ///
+    /// A font replaces any shared font with the same name and style.
+    pub fn register_shared_font(&mut self, font: Font<'gc>) {
+        self.shared_fonts.insert(font.descriptor().clone(), font);
+    }
+
+    /// Find a shared font by its name and style.
+    pub fn shared_font_by_name(
+        &self,
+        name: &str,
+        is_bold: bool,
+        is_italic: bool,
+    ) -> Option<Font<'gc>> {
+        let descriptor = FontDescriptor::from_parts(name, is_bold, is_italic);
+
+        self.shared_fonts.get(&descriptor).copied()
+    }
+
+    /// Find a font by its name and style in a movie's library, falling back to
+    /// the shared fonts.
+    pub fn font_by_name_or_shared(
+        &self,
+        movie: Arc<SwfMovie>,
+        name: &str,
+        is_bold: bool,
+        is_italic: bool,
+    ) -> Option<Font<'gc>> {
+        self.library_for_movie(movie)
+            .and_then(|library| library.get_font_by_name(name, is_bold, is_italic))
+            .or_else(|| self.shared_font_by_name(name, is_bold, is_italic))
+    }
+
     /// Gets the constructor registry to use for the given SWF version.
     /// Because SWFs v6 and v7+ use different case-sensitivity rules, Flash
     /// keeps two separate registries, one case-sensitive, the other not.
@@ -489,3 +538,83 @@ impl<'gc> Library<'gc> {
         &mut self.avm2_class_registry
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::render::NullRenderer;
+    use gc_arena::rootless_arena;
+
+    fn font<'gc>(mc: MutationContext<'gc, '_>, glyphs: Vec<swf::Glyph>) -> Font<'gc> {
+        let tag = swf::Font {
+            version: 3,
+            id: 1,
+            name: swf::SwfStr::from_utf8_str("Shared"),
+            language: swf::Language::Unknown,
+            layout: None,
+            glyphs,
+            is_small_text: false,
+            is_shift_jis: false,
+            is_ansi: false,
+            is_bold: false,
+            is_italic: false,
+        };
+
+        Font::from_swf_tag(mc, &mut NullRenderer::new(), &tag, swf::UTF_8).unwrap()
+    }
+
+    #[test]
+    fn last_shared_font_wins() {
+        rootless_arena(|mc| {
+            let glyph = swf::Glyph {
+                shape_records: vec![],
+                code: u16::from(b'a'),
+                advance: Some(0),
+                bounds: None,
+            };
+            let mut library = Library::empty(mc);
+            library.register_shared_font(font(mc, vec![]));
+            library.register_shared_font(font(mc, vec![glyph]));
+
+            let shared = library.shared_font_by_name("Shared", false, false);
+            assert!(shared.unwrap().has_glyphs());
+            assert!(library.shared_font_by_name("Shared", true, false).is_none());
+        })
+    }
+
+    #[test]
+    fn movie_fonts_come_before_shared_fonts() {
+        rootless_arena(|mc| {
+            let glyph = swf::Glyph {
+                shape_records: vec![],
+                code: u16::from(b'a'),
+                advance: Some(0),
+                bounds: None,
+            };
+            let movie = Arc::new(SwfMovie::empty(8));
+            let other_movie = Arc::new(SwfMovie::empty(8));
+            let mut library = Library::empty(mc);
+            library
+                .library_for_movie_mut(movie.clone())
+                .register_character(1, Character::Font(font(mc, vec![])));
+            library.register_shared_font(font(mc, vec![glyph]));
+
+            let own = library.font_by_name_or_shared(movie, "Shared", false, false);
+            assert!(!own.unwrap().has_glyphs());
+            let shared = library.font_by_name_or_shared(other_movie, "Shared", false, false);
+            assert!(shared.unwrap().has_glyphs());
+        })
+    }
+
+    #[test]
+    fn only_exported_fonts_are_listed() {
+        rootless_arena(|mc| {
+            let mut library = MovieLibrary::new(AvmType::Avm1);
+            library.register_character(1, Character::Font(font(mc, vec![])));
+            assert!(library.exported_fonts().is_empty());
+
+            library.register_export(1, "SharedFont");
+            assert_eq!(library.exported_fonts().len(), 1);
+        })
+    }
+}
@@ -1,23 +1,47 @@
 //! Management of async loaders
 
 use crate::avm1::activation::{Activation, ActivationIdentifier};
-use crate::avm1::{Avm1, Object, TObject, Value};
-use crate::avm2::{Activation as Avm2Activation, Domain as Avm2Domain};
-use crate::backend::navigator::OwnedFuture;
-use crate::context::{ActionQueue, ActionType};
-use crate::display_object::{DisplayObject, MorphShape, TDisplayObject};
+use crate::avm1::property::Attribute;
+use crate::avm1::{Avm1, Object, SoundObject, TObject, Value};
+use crate::avm2::{
+    Activation as Avm2Activation, ArrayObject as Avm2ArrayObject, ArrayStorage as Avm2ArrayStorage,
+    Avm2, Domain as Avm2Domain, Error as Avm2Error, Event as Avm2Event, Namespace as Avm2Namespace,
+    Object as Avm2Object, QName as Avm2QName, TObject as Avm2TObject, Value as Avm2Value,
+};
+use crate::backend::navigator::{
+    redact_url_credentials, DigestAlgorithm, NavigationMethod, OwnedFuture, RedirectPolicy,
+    RequestOptions, Response,
+};
+use crate::backend::render::{decode_gif, decode_jpeg, decode_png, Bitmap, BitmapFormat};
+use crate::backend::ui::InvalidMovieReason;
+use crate::context::{ActionQueue, ActionType, UpdateContext};
+use crate::cookie_jar::CookieJar;
+use crate::display_object::{
+    dispatch_removed_event, DisplayObject, MorphShape, TDisplayObject, TDisplayObjectContainer,
+};
 use crate::player::{Player, NEWEST_PLAYER_VERSION};
+use crate::policy_file::PolicyFile;
 use crate::string::AvmString;
 use crate::tag_utils::SwfMovie;
-use crate::vminterface::Instantiator;
+use crate::vminterface::{AvmType, Instantiator};
 use crate::xml::XmlNode;
-use encoding_rs::UTF_8;
+use chrono::Utc;
+use encoding_rs::{Encoding, UTF_8};
 use gc_arena::{Collect, CollectionContext};
 use generational_arena::{Arena, Index};
+use indexmap::{IndexMap, IndexSet};
+use json::JsonValue;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::{self, Rc};
 use std::string::FromUtf8Error;
 use std::sync::{Arc, Mutex, Weak};
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
 use thiserror::Error;
-use url::form_urlencoded;
+use url::{form_urlencoded, Url};
 
 pub type Handle = Index;
 
@@ -41,25 +65,135 @@ pub enum Error {
     #[error("Non-XML loader spawned as XML loader")]
     NotXmlLoader,
 
+    #[error("Non-JSON loader spawned as JSON loader")]
+    NotJsonLoader,
+
+    #[error("Non-sound loader spawned as sound loader")]
+    NotSoundLoader,
+
     #[error("Could not fetch movie {0}")]
     FetchError(String),
 
+    /// The server responded, but with an unsuccessful HTTP status.
+    #[error("HTTP status is not ok, got {status}")]
+    HttpNotOk { status: u16, body: Vec<u8> },
+
+    /// The connection was lost after part of the response body was received.
+    ///
+    /// `body` holds the bytes received so far, which may be resumed with a
+    /// `Range` request.
+    #[error("Fetch interrupted: {message}")]
+    FetchInterrupted {
+        status: u16,
+        body: Vec<u8>,
+        message: String,
+    },
+
     #[error("Invalid SWF")]
     InvalidSwf(#[from] crate::tag_utils::Error),
 
+    #[error("Unrecognized content type loaded from {0}")]
+    UnknownContentType(String),
+
+    #[error("Could not load {0}: SVG images are not supported by Flash Player")]
+    UnsupportedSvg(String),
+
+    #[error("Invalid BMP image: {0}")]
+    InvalidBmp(&'static str),
+
+    #[error("Movie {0} uses a different ActionScript version than the movie it was loaded into")]
+    AvmVersionMismatch(String),
+
+    #[error("Movie {url} has SWF version {version}, but at most {max} is allowed")]
+    SwfVersionTooNew { url: String, version: u8, max: u8 },
+
+    #[error("Cannot load a movie into {0}: {1}")]
+    InvalidLoadTarget(String, &'static str),
+
+    #[error("Loaded movie has no frame labelled {0}")]
+    FrameLabelNotFound(String),
+
+    #[error("Could not decode {url}: {message}")]
+    ContentDecodeFailed { url: String, message: String },
+
     #[error("Invalid XML encoding")]
     InvalidXmlEncoding(#[from] FromUtf8Error),
 
     #[error("Network error")]
     NetworkError(#[from] std::io::Error),
 
+    #[error("Could not decode response body: {0}")]
+    ContentEncodingError(String),
+
     #[error("Network unavailable.")]
     NetworkUnavailable,
 
-    // TODO: We can't support lifetimes on this error object yet (or we'll need some backends inside
-    // the GC arena). We're losing info here. How do we fix that?
+    #[error("Security error: {0}")]
+    SecurityError(String),
+
+    #[error("Load of {0} was blocked")]
+    Blocked(String),
+
+    #[error("Response is larger than the limit of {limit} bytes")]
+    ResponseTooLarge { limit: usize },
+
+    #[error("Load did not complete within the maximum execution duration")]
+    LoadTimedOut,
+
+    #[error("Response does not match its expected {0:?} digest")]
+    DigestMismatch(DigestAlgorithm),
+
+    #[error("Movie {0} has no digest to verify it against")]
+    UnverifiedMovie(String),
+
+    #[error("Invalid digest {0}")]
+    InvalidDigest(String),
+
+    // We can't support lifetimes on this error object yet (or we'll need some backends inside
+    // the GC arena), so script errors are detached into a `ScriptError` first.
     #[error("Error running avm1 script: {0}")]
-    Avm1Error(String),
+    Avm1Error(ScriptError),
+}
+
+/// The broad category of a script error raised during a load.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ScriptErrorKind {
+    /// A script ran for too long and was terminated.
+    ExecutionTimeout,
+
+    /// A prototype chain or call stack grew too deep.
+    RecursionLimit,
+
+    /// The movie being executed could not be parsed.
+    InvalidSwf,
+
+    /// Script code touched a display object in an invalid state.
+    InvalidDisplayObjectHierarchy,
+
+    /// Script code threw a value of its own.
+    Thrown,
+}
+
+/// A script error that occurred during a load, detached from the GC arena.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ScriptError {
+    /// What kind of error this was.
+    pub kind: ScriptErrorKind,
+
+    /// The numeric code associated with this error, if the VM provided one.
+    pub code: Option<u32>,
+
+    /// A human-readable description of the error.
+    pub message: String,
+}
+
+impl std::fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.code {
+            Some(code) => write!(f, "Error #{}: {}", code, self.message),
+            None => f.write_str(&self.message),
+        }
+    }
 }
 
 pub type FormLoadHandler<'gc> =
@@ -69,794 +203,6197 @@ pub type FormErrorHandler<'gc> = fn(&mut Activation<'_, 'gc, '_>, Object<'gc>) -
 
 impl From<crate::avm1::error::Error<'_>> for Error {
     fn from(error: crate::avm1::error::Error<'_>) -> Self {
-        Error::Avm1Error(error.to_string())
+        use crate::avm1::error::Error as Avm1Error;
+
+        let kind = match &error {
+            Avm1Error::ExecutionTimeout => ScriptErrorKind::ExecutionTimeout,
+            Avm1Error::PrototypeRecursionLimit
+            | Avm1Error::FunctionRecursionLimit(_)
+            | Avm1Error::SpecialRecursionLimit => ScriptErrorKind::RecursionLimit,
+            Avm1Error::InvalidSwf(_) => ScriptErrorKind::InvalidSwf,
+            Avm1Error::InvalidDisplayObjectHierarchy => {
+                ScriptErrorKind::InvalidDisplayObjectHierarchy
+            }
+            Avm1Error::ThrownValue(_) => ScriptErrorKind::Thrown,
+        };
+
+        // AVM1 has no numbered runtime errors.
+        Error::Avm1Error(ScriptError {
+            kind,
+            code: None,
+            message: error.to_string(),
+        })
     }
 }
 
-/// Holds all in-progress loads for the player.
-pub struct LoadManager<'gc>(Arena<Loader<'gc>>);
+/// Optional settings for a movie load started by `load_movie_into_clip`.
+#[derive(Default)]
+pub struct MovieLoadOptions<'gc> {
+    /// Parameters for the loaded movie, which take precedence over those in
+    /// the query string of its URL.
+    pub parameters: Vec<(String, String)>,
 
-unsafe impl<'gc> Collect for LoadManager<'gc> {
-    fn trace(&self, cc: CollectionContext) {
-        for (_, loader) in self.0.iter() {
-            loader.trace(cc)
+    /// The URL of the movie that started the load, which the loaded movie
+    /// reports as its `loaderURL`.
+    pub loader_url: Option<String>,
+
+    /// The AVM1 `MovieClipLoader` that the load's events are broadcast to.
+    pub target_broadcaster: Option<Object<'gc>>,
+
+    /// The clip whose code started the load. Loads into its ancestors are
+    /// refused, unless they replace a level.
+    pub requesting_clip: Option<DisplayObject<'gc>>,
+
+    /// The type of the loaded content, which is otherwise sniffed from its
+    /// data.
+    pub force_content_type: Option<ContentType>,
+
+    /// The frame the loaded movie starts playing from, instead of its first
+    /// frame. Loads that replace the root movie always start from its first
+    /// frame.
+    pub start_frame: Option<FrameLabelOrNumber>,
+
+    /// Called with the header of a loaded movie once it has been parsed.
+    pub on_metadata: Option<Box<dyn FnOnce(&swf::HeaderExt)>>,
+
+    /// Called once the loaded movie has run its first frame, after that
+    /// frame's scripts. Images have no frames, so this is called as soon as
+    /// a loaded image has been placed in the clip.
+    pub on_init: Option<Box<dyn FnOnce()>>,
+}
+
+/// Where a movie loader gets the movie it loads from.
+pub enum MovieSource {
+    /// The response to a request, unless the movie is cached.
+    Fetch(OwnedFuture<Response, Error>),
+
+    /// A movie that has already been parsed.
+    Parsed(Arc<SwfMovie>),
+
+    /// An already decoded image, which is loaded like an image file.
+    Bitmap(Bitmap),
+}
+
+/// A frame of a loaded movie to start playing from.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum FrameLabelOrNumber {
+    /// A 1-based frame number.
+    Number(u16),
+
+    /// A frame label, which is matched case-insensitively.
+    Label(String),
+}
+
+/// The kind of data a movie loader received, as determined by its contents.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ContentType {
+    Swf,
+
+    Jpeg,
+
+    Png,
+
+    /// GIF images, of which only the first frame is shown.
+    Gif,
+
+    /// SVG images, which Flash Player never supported loading.
+    Svg,
+
+    /// Uncompressed Windows bitmaps.
+    Bmp,
+
+    Unknown,
+}
+
+impl ContentType {
+    /// Determine the type of some loaded data from its signature.
+    ///
+    /// Only the signature is inspected, so a truncated SWF is still reported
+    /// as a SWF and left for the parser to reject.
+    pub fn sniff(data: &[u8]) -> Self {
+        match data.get(0..3) {
+            Some(b"FWS") | Some(b"CWS") | Some(b"ZWS") => ContentType::Swf,
+            Some([0xff, 0xd8, 0xff]) => ContentType::Jpeg,
+            _ if data.starts_with(b"\x89PNG\r\n\x1a\n") => ContentType::Png,
+            _ if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") => ContentType::Gif,
+            _ if is_svg(data) => ContentType::Svg,
+            _ if is_bmp(data) => ContentType::Bmp,
+            _ => ContentType::Unknown,
         }
     }
 }
 
-impl<'gc> LoadManager<'gc> {
-    /// Construct a new `LoadManager`.
-    pub fn new() -> Self {
-        Self(Arena::new())
+/// Combine the query string parameters of a movie URL with parameters
+/// supplied by the loader, with the latter taking precedence.
+fn merge_parameters(url: &str, explicit: Vec<(String, String)>) -> Vec<(String, String)> {
+    let mut parameters: Vec<(String, String)> = match Url::parse(url) {
+        Ok(url) => url.query_pairs().into_owned().collect(),
+        Err(_) => Vec::new(),
+    };
+
+    for (key, value) in explicit {
+        parameters.retain(|(existing_key, _)| *existing_key != key);
+        parameters.push((key, value));
     }
 
-    /// Add a new loader to the `LoadManager`.
-    ///
-    /// This function returns the loader handle for later inspection. A loader
-    /// handle is valid for as long as the load operation. Once the load
-    /// finishes, the handle will be invalidated (and the underlying loader
-    /// deleted).
-    pub fn add_loader(&mut self, loader: Loader<'gc>) -> Handle {
-        let handle = self.0.insert(loader);
-        self.0
-            .get_mut(handle)
-            .unwrap()
-            .introduce_loader_handle(handle);
+    parameters
+}
 
-        handle
+/// Give a load its own copy of a movie, if it needs one.
+///
+/// Libraries are keyed by movie, so a movie that is also held by the cache is
+/// copied. Otherwise every clip loaded from the same URL would share one
+/// library, including its exports and AVM2 domain. Parameters are part of the
+/// movie, so loads with parameters are always given a copy as well.
+fn movie_for_load(
+    movie: Arc<SwfMovie>,
+    parameters: Vec<(String, String)>,
+    is_cached: bool,
+) -> Arc<SwfMovie> {
+    if parameters.is_empty() && !is_cached {
+        movie
+    } else {
+        let mut movie = (*movie).clone();
+        movie.append_parameters(parameters);
+        Arc::new(movie)
     }
+}
 
-    /// Retrieve a loader by handle.
-    pub fn get_loader(&self, handle: Handle) -> Option<&Loader<'gc>> {
-        self.0.get(handle)
+/// Multiply the color channels of straight RGBA pixels by their alpha.
+fn premultiply_alpha(rgba: &mut [u8]) {
+    for pixel in rgba.chunks_exact_mut(4) {
+        let alpha = u16::from(pixel[3]);
+        for channel in &mut pixel[..3] {
+            *channel = (u16::from(*channel) * alpha / 255) as u8;
+        }
     }
+}
 
-    /// Retrieve a loader by handle for mutation.
-    pub fn get_loader_mut(&mut self, handle: Handle) -> Option<&mut Loader<'gc>> {
-        self.0.get_mut(handle)
+/// Convert a bitmap to RGBA pixels, which is all that clips display.
+fn into_rgba(bitmap: Bitmap) -> Bitmap {
+    match bitmap.data {
+        BitmapFormat::Rgba(_) => bitmap,
+        BitmapFormat::Rgb(rgb) => {
+            let mut rgba = Vec::with_capacity(rgb.len() / 3 * 4);
+            for pixel in rgb.chunks_exact(3) {
+                rgba.extend_from_slice(pixel);
+                rgba.push(255);
+            }
+            Bitmap {
+                data: BitmapFormat::Rgba(rgba),
+                ..bitmap
+            }
+        }
     }
+}
 
-    /// Kick off the root movie load.
-    ///
-    /// The root movie is special because it determines a few bits of player
-    /// state, such as the size of the stage and the current frame rate. Ergo,
-    /// this method should only be called once, by the player that is trying to
-    /// kick off its root movie load.
-    pub fn load_root_movie(
-        &mut self,
-        player: Weak<Mutex<Player>>,
-        fetch: OwnedFuture<Vec<u8>, Error>,
-        url: String,
-        parameters: Vec<(String, String)>,
-        on_metadata: Box<dyn FnOnce(&swf::HeaderExt)>,
-    ) -> OwnedFuture<(), Error> {
-        let loader = Loader::RootMovie { self_handle: None };
-        let handle = self.add_loader(loader);
+/// Find the EXIF orientation of a JPEG, from 1 to 8.
+///
+/// Only the segments before the image data are read, so JPEGs without an
+/// `Exif` segment are cheap to check. Returns 1, the upright orientation, if
+/// there is no orientation tag.
+fn jpeg_orientation(data: &[u8]) -> u16 {
+    let mut data = match data.strip_prefix(b"\xff\xd8") {
+        Some(data) => data,
+        None => return 1,
+    };
 
-        let loader = self.get_loader_mut(handle).unwrap();
-        loader.introduce_loader_handle(handle);
+    // Each segment starts with a marker and a big-endian length, which counts
+    // itself but not the marker.
+    while let [0xff, marker, len_high, len_low, rest @ ..] = data {
+        let len = usize::from(u16::from_be_bytes([*len_high, *len_low]));
+        if *marker == 0xda || len < 2 || rest.len() < len - 2 {
+            break;
+        }
 
-        loader.root_movie_loader(player, fetch, url, parameters, on_metadata)
+        let (segment, next) = rest.split_at(len - 2);
+        if *marker == 0xe1 {
+            if let Some(tiff) = segment.strip_prefix(b"Exif\0\0") {
+                return tiff_orientation(tiff).unwrap_or(1);
+            }
+        }
+        data = next;
     }
 
-    /// Kick off a movie clip load.
-    ///
-    /// Returns the loader's async process, which you will need to spawn.
-    pub fn load_movie_into_clip(
-        &mut self,
-        player: Weak<Mutex<Player>>,
-        target_clip: DisplayObject<'gc>,
-        fetch: OwnedFuture<Vec<u8>, Error>,
-        url: String,
-        loader_url: Option<String>,
-        target_broadcaster: Option<Object<'gc>>,
-    ) -> OwnedFuture<(), Error> {
-        let loader = Loader::Movie {
-            self_handle: None,
-            target_clip,
-            target_broadcaster,
-            loader_status: LoaderStatus::Pending,
-        };
-        let handle = self.add_loader(loader);
+    1
+}
 
-        let loader = self.get_loader_mut(handle).unwrap();
-        loader.introduce_loader_handle(handle);
+/// Read the orientation tag from the first IFD of the TIFF data in an `Exif`
+/// segment.
+fn tiff_orientation(tiff: &[u8]) -> Option<u16> {
+    let big_endian = match tiff.get(..2)? {
+        b"MM" => true,
+        b"II" => false,
+        _ => return None,
+    };
+    let read = |offset: usize, len: usize| -> Option<u32> {
+        let bytes = tiff.get(offset..offset.checked_add(len)?)?;
+        let fold = |value: u32, byte: &u8| (value << 8) | u32::from(*byte);
+        if big_endian {
+            Some(bytes.iter().fold(0, fold))
+        } else {
+            Some(bytes.iter().rev().fold(0, fold))
+        }
+    };
 
-        loader.movie_loader(player, fetch, url, loader_url)
+    let ifd = read(4, 4)? as usize;
+    let num_entries = read(ifd, 2)? as usize;
+    for i in 0..num_entries {
+        let entry = ifd + 2 + i * 12;
+        if read(entry, 2)? == 0x0112 {
+            return Some(read(entry + 8, 2)? as u16);
+        }
     }
+    None
+}
 
-    /// Indicates that a movie clip has initialized (ran its first frame).
-    ///
-    /// Interested loaders will be invoked from here.
-    pub fn movie_clip_on_load(
-        &mut self,
-        loaded_clip: DisplayObject<'gc>,
-        clip_object: Option<Object<'gc>>,
-        queue: &mut ActionQueue<'gc>,
-    ) {
-        let mut invalidated_loaders = vec![];
+/// Rotate and flip RGBA pixels upright, according to their EXIF orientation.
+///
+/// Orientations 5 to 8 swap the width and height of the image.
+fn apply_orientation(bitmap: Bitmap, orientation: u16) -> Bitmap {
+    if let (BitmapFormat::Rgba(rgba), 2..=8) = (&bitmap.data, orientation) {
+        let (width, height) = (bitmap.width as usize, bitmap.height as usize);
+        let (new_width, new_height) = if orientation >= 5 {
+            (height, width)
+        } else {
+            (width, height)
+        };
 
-        for (index, loader) in self.0.iter_mut() {
-            if loader.movie_clip_loaded(loaded_clip, clip_object, queue) {
-                invalidated_loaders.push(index);
+        let mut oriented = Vec::with_capacity(rgba.len());
+        for y in 0..new_height {
+            for x in 0..new_width {
+                let (src_x, src_y) = match orientation {
+                    2 => (width - 1 - x, y),
+                    3 => (width - 1 - x, height - 1 - y),
+                    4 => (x, height - 1 - y),
+                    5 => (y, x),
+                    6 => (y, height - 1 - x),
+                    7 => (width - 1 - y, height - 1 - x),
+                    _ => (width - 1 - y, x),
+                };
+                let i = (src_y * width + src_x) * 4;
+                oriented.extend_from_slice(&rgba[i..i + 4]);
             }
         }
 
-        for index in invalidated_loaders {
-            self.0.remove(index);
-        }
+        return Bitmap {
+            width: new_width as u32,
+            height: new_height as u32,
+            data: BitmapFormat::Rgba(oriented),
+        };
     }
+    bitmap
+}
 
-    /// Kick off a form data load into an AVM1 object.
-    ///
-    /// Returns the loader's async process, which you will need to spawn.
-    pub fn load_form_into_object(
-        &mut self,
-        player: Weak<Mutex<Player>>,
-        target_object: Object<'gc>,
-        fetch: OwnedFuture<Vec<u8>, Error>,
-    ) -> OwnedFuture<(), Error> {
-        let loader = Loader::Form {
-            self_handle: None,
-            target_object,
-        };
-        let handle = self.add_loader(loader);
+/// Decode a loaded JPEG, PNG or GIF image into premultiplied RGBA pixels.
+///
+/// Loaded files never carry separate alpha data, unlike `DefineBitsJPEG3`
+/// tags, so only PNGs and GIFs can be transparent. JPEGs are turned upright
+/// according to their EXIF orientation. Color profiles are ignored.
+fn decode_image(response: &Response, content_type: ContentType) -> Result<Bitmap, Error> {
+    let bitmap = match content_type {
+        ContentType::Jpeg => decode_jpeg(&response.body, None),
+        ContentType::Png => decode_png(&response.body),
+        _ => decode_gif(&response.body),
+    }
+    .map_err(|e| Error::ContentDecodeFailed {
+        url: response.url.clone(),
+        message: e.to_string(),
+    })?;
 
-        let loader = self.get_loader_mut(handle).unwrap();
-        loader.introduce_loader_handle(handle);
+    let mut bitmap = into_rgba(bitmap);
+    if content_type == ContentType::Jpeg {
+        bitmap = apply_orientation(bitmap, jpeg_orientation(&response.body));
+    }
+    if let BitmapFormat::Rgba(rgba) = &mut bitmap.data {
+        premultiply_alpha(rgba);
+    }
+    Ok(bitmap)
+}
 
-        loader.form_loader(player, fetch)
+/// Check if some data looks like an SVG document.
+///
+/// Only the start of the data is searched for an `svg` root element, which
+/// may follow an XML declaration, doctype or comments.
+fn is_svg(data: &[u8]) -> bool {
+    let head = &data[..data.len().min(1024)];
+    let head = head.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(head);
+    let head = match head.iter().position(|b| !b.is_ascii_whitespace()) {
+        Some(start) => &head[start..],
+        None => return false,
+    };
+
+    let is_svg_tag = |tag: &[u8]| {
+        tag.starts_with(b"<svg")
+            && matches!(tag.get(4), Some(b) if b.is_ascii_whitespace() || *b == b'>' || *b == b'/')
+    };
+
+    if head.starts_with(b"<?xml") || head.starts_with(b"<!") {
+        // The first element after the prolog must be the SVG root.
+        head.iter()
+            .enumerate()
+            .filter(|(_, b)| **b == b'<')
+            .map(|(i, _)| &head[i..])
+            .find(|tag| !tag.starts_with(b"<?") && !tag.starts_with(b"<!"))
+            .map_or(false, is_svg_tag)
+    } else {
+        is_svg_tag(head)
     }
+}
 
-    /// Kick off a form data load into an AVM1 object.
-    ///
-    /// Returns the loader's async process, which you will need to spawn.
-    pub fn load_form_into_load_vars(
-        &mut self,
-        player: Weak<Mutex<Player>>,
-        target_object: Object<'gc>,
-        fetch: OwnedFuture<Vec<u8>, Error>,
-    ) -> OwnedFuture<(), Error> {
-        let loader = Loader::LoadVars {
-            self_handle: None,
-            target_object,
-        };
-        let handle = self.add_loader(loader);
+/// The length of the file header and `BITMAPINFOHEADER` of a BMP image.
+const BMP_HEADER_LEN: usize = 54;
 
-        let loader = self.get_loader_mut(handle).unwrap();
-        loader.introduce_loader_handle(handle);
+/// Check if some data looks like a BMP image.
+///
+/// Text can start with `BM` too, so the DIB header must also be at least a
+/// `BITMAPINFOHEADER` and describe a single color plane.
+fn is_bmp(data: &[u8]) -> bool {
+    data.starts_with(b"BM")
+        && data.len() >= BMP_HEADER_LEN
+        && u32::from_le_bytes([data[14], data[15], data[16], data[17]]) >= 40
+        && u16::from_le_bytes([data[26], data[27]]) == 1
+}
 
-        loader.load_vars_loader(player, fetch)
+/// Decode an uncompressed BMP image into premultiplied RGBA pixels.
+///
+/// 8-bit palettized, 24-bit and 32-bit images are supported, whether their
+/// rows are stored bottom-up, as is usual, or top-down.
+fn decode_bmp(data: &[u8]) -> Result<Bitmap, Error> {
+    let u16_at = |offset: usize| {
+        data.get(offset..offset + 2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]))
+            .ok_or(Error::InvalidBmp("truncated header"))
+    };
+    let u32_at = |offset: usize| {
+        data.get(offset..offset + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .ok_or(Error::InvalidBmp("truncated header"))
+    };
+
+    if !data.starts_with(b"BM") {
+        return Err(Error::InvalidBmp("missing signature"));
+    }
+    let pixels_offset = u32_at(10)? as usize;
+    let info_len = u32_at(14)? as usize;
+    if info_len < 40 || u16_at(26)? != 1 {
+        return Err(Error::InvalidBmp("unsupported header"));
     }
+    let width = u32_at(18)? as i32;
+    let height = u32_at(22)? as i32;
+    let bits_per_pixel = u16_at(28)?;
+    let compression = u32_at(30)?;
+    let colors_used = u32_at(46)? as usize;
 
-    /// Kick off an XML data load into an XML node.
-    ///
-    /// Returns the loader's async process, which you will need to spawn.
-    pub fn load_xml_into_node(
-        &mut self,
-        player: Weak<Mutex<Player>>,
-        target_node: XmlNode<'gc>,
-        active_clip: DisplayObject<'gc>,
-        fetch: OwnedFuture<Vec<u8>, Error>,
-    ) -> OwnedFuture<(), Error> {
-        let loader = Loader::Xml {
-            self_handle: None,
-            active_clip,
-            target_node,
+    // 32-bit images with bit fields use the usual BGRA layout in practice.
+    match (bits_per_pixel, compression) {
+        (8, 0) | (24, 0) | (32, 0) | (32, 3) => {}
+        _ => return Err(Error::InvalidBmp("unsupported pixel format")),
+    }
+
+    // A negative height marks an image stored top-down.
+    let top_down = height < 0;
+    let height = height.checked_abs().unwrap_or(0);
+    if width <= 0 || width > 0xffff || height == 0 || height > 0xffff {
+        return Err(Error::InvalidBmp("invalid dimensions"));
+    }
+    let (width, height) = (width as usize, height as usize);
+
+    let palette: Vec<[u8; 4]> = if bits_per_pixel == 8 {
+        let colors = if colors_used == 0 {
+            256
+        } else {
+            colors_used.min(256)
         };
-        let handle = self.add_loader(loader);
+        info_len
+            .checked_add(14)
+            .and_then(|start| data.get(start..start.checked_add(colors * 4)?))
+            .ok_or(Error::InvalidBmp("truncated palette"))?
+            .chunks_exact(4)
+            .map(|bgr| [bgr[2], bgr[1], bgr[0], 255])
+            .collect()
+    } else {
+        Vec::new()
+    };
 
-        let loader = self.get_loader_mut(handle).unwrap();
-        loader.introduce_loader_handle(handle);
+    // The header can claim any size, so check that all of the pixel data is
+    // present before allocating room for it.
+    let stride = (width * usize::from(bits_per_pixel) + 31) / 32 * 4;
+    let pixels_end = stride
+        .checked_mul(height)
+        .and_then(|len| len.checked_add(pixels_offset));
+    if !matches!(pixels_end, Some(end) if end <= data.len()) {
+        return Err(Error::InvalidBmp("truncated pixel data"));
+    }
+    let rgba_len = (width * 4)
+        .checked_mul(height)
+        .ok_or(Error::InvalidBmp("invalid dimensions"))?;
+    let mut rgba = Vec::with_capacity(rgba_len);
+    for y in 0..height {
+        let row = if top_down { y } else { height - 1 - y };
+        let start = pixels_offset + row * stride;
+        let row = data
+            .get(start..start + stride)
+            .ok_or(Error::InvalidBmp("truncated pixel data"))?;
 
-        loader.xml_loader(player, fetch)
+        for x in 0..width {
+            let color = match bits_per_pixel {
+                8 => palette
+                    .get(usize::from(row[x]))
+                    .copied()
+                    .unwrap_or([0, 0, 0, 255]),
+                24 => [row[x * 3 + 2], row[x * 3 + 1], row[x * 3], 255],
+                _ => [row[x * 4 + 2], row[x * 4 + 1], row[x * 4], row[x * 4 + 3]],
+            };
+            rgba.extend_from_slice(&color);
+        }
+    }
+
+    if bits_per_pixel == 32 {
+        // Most 32-bit images leave the alpha channel unused, as zero.
+        if rgba.chunks_exact(4).all(|pixel| pixel[3] == 0) {
+            for pixel in rgba.chunks_exact_mut(4) {
+                pixel[3] = 255;
+            }
+        } else {
+            premultiply_alpha(&mut rgba);
+        }
     }
+
+    Ok(Bitmap {
+        width: width as u32,
+        height: height as u32,
+        data: BitmapFormat::Rgba(rgba),
+    })
 }
 
-impl<'gc> Default for LoadManager<'gc> {
-    fn default() -> Self {
-        Self::new()
+/// Parse the response to a movie load into a movie.
+///
+/// The type of the response is sniffed from its data, unless a type is
+/// forced. Returns the length of the loaded data along with the movie, and
+/// the decoded image if the response is one.
+fn parse_movie_response(
+    response: &Response,
+    force_content_type: Option<ContentType>,
+    loader_url: Option<String>,
+) -> Result<(usize, Arc<SwfMovie>, Option<Bitmap>), Error> {
+    let content_type = force_content_type.unwrap_or_else(|| ContentType::sniff(&response.body));
+    match content_type {
+        ContentType::Swf => {
+            // Relative loads made by the movie resolve against where it
+            // ended up after any redirects.
+            let movie =
+                SwfMovie::from_data(&response.body, Some(response.url.clone()), loader_url)?;
+            Ok((response.body.len(), Arc::new(movie), None))
+        }
+        ContentType::Bmp => {
+            let bitmap = decode_bmp(&response.body)?;
+            let movie = SwfMovie::empty(NEWEST_PLAYER_VERSION);
+            Ok((response.body.len(), Arc::new(movie), Some(bitmap)))
+        }
+        ContentType::Jpeg | ContentType::Png | ContentType::Gif => {
+            let bitmap = decode_image(response, content_type)?;
+            let movie = SwfMovie::empty(NEWEST_PLAYER_VERSION);
+            Ok((response.body.len(), Arc::new(movie), Some(bitmap)))
+        }
+        ContentType::Svg => Err(Error::UnsupportedSvg(response.url.clone())),
+        ContentType::Unknown => Err(Error::UnknownContentType(response.url.clone())),
     }
 }
 
-/// The completion status of a `Loader` loading a movie.
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Collect)]
-#[collect(require_static)]
-pub enum LoaderStatus {
-    /// The movie hasn't been loaded yet.
-    Pending,
-    /// The movie loaded successfully.
-    Succeeded,
-    /// An error occurred while loading the movie.
-    Failed,
+/// Check that a movie loaded into a clip runs on the player's AVM.
+///
+/// AVM1 and AVM2 content can't share a display list, so this only allows
+/// mixing AVMs when the loaded movie replaces the root movie entirely.
+fn check_avm_type(movie: &SwfMovie, player_avm: AvmType, url: &str) -> Result<(), Error> {
+    if movie.avm_type() == player_avm {
+        Ok(())
+    } else {
+        Err(Error::AvmVersionMismatch(url.to_string()))
+    }
 }
 
-/// A struct that holds garbage-collected pointers for asynchronous code.
-#[derive(Collect)]
-#[collect(no_drop)]
-pub enum Loader<'gc> {
-    /// Loader that is loading the root movie of a player.
-    RootMovie {
-        /// The handle to refer to this loader instance.
-        #[collect(require_static)]
-        self_handle: Option<Handle>,
-    },
+/// Check that a loaded movie isn't newer than the embedder allows, if it
+/// limits the SWF version.
+fn check_swf_version(
+    movie: &SwfMovie,
+    max_swf_version: Option<u8>,
+    url: &str,
+) -> Result<(), Error> {
+    match max_swf_version {
+        Some(max) if movie.version() > max => Err(Error::SwfVersionTooNew {
+            url: url.to_string(),
+            version: movie.version(),
+            max,
+        }),
+        _ => Ok(()),
+    }
+}
 
-    /// Loader that is loading a new movie into a MovieClip.
-    Movie {
-        /// The handle to refer to this loader instance.
+/// List the ancestors of a display object, nearest first.
+///
+/// Returns `None` if its chain of parents loops back on itself.
+fn ancestors(object: DisplayObject<'_>) -> Option<Vec<DisplayObject<'_>>> {
+    let mut ancestors = vec![];
+    let mut ancestor = object.parent();
+    while let Some(parent) = ancestor {
+        if DisplayObject::ptr_eq(parent, object)
+            || ancestors
+                .iter()
+                .any(|visited| DisplayObject::ptr_eq(*visited, parent))
+        {
+            return None;
+        }
+        ancestors.push(parent);
+        ancestor = parent.parent();
+    }
+    Some(ancestors)
+}
+
+/// Check that a movie can be loaded into the given clip on behalf of the
+/// clip whose code started the load, if any.
+///
+/// Only movie clips can hold a loaded movie. Loading into an ancestor of the
+/// requesting clip would unload the requesting clip while it is still
+/// running, and a clip that is one of its own ancestors can't be unloaded
+/// without walking the cycle forever. Both are refused before anything is
+/// fetched. Levels, such as the root clip, sit directly on the stage and can
+/// always be replaced.
+fn check_load_target<'gc>(
+    target_clip: DisplayObject<'gc>,
+    requesting_clip: Option<DisplayObject<'gc>>,
+) -> Result<(), Error> {
+    let invalid = |reason| Error::InvalidLoadTarget(target_clip.name().to_string(), reason);
+    if target_clip.as_movie_clip().is_none() {
+        return Err(invalid("it is not a movie clip"));
+    }
+
+    let target_ancestors =
+        ancestors(target_clip).ok_or_else(|| invalid("it is contained within itself"))?;
+    let is_level = target_ancestors
+        .first()
+        .map_or(true, |parent| parent.as_stage().is_some());
+    if let Some(requesting_clip) = requesting_clip {
+        let contains_requesting_clip = ancestors(requesting_clip)
+            .unwrap_or_default()
+            .iter()
+            .any(|ancestor| DisplayObject::ptr_eq(*ancestor, target_clip));
+        if contains_requesting_clip && !is_level {
+            return Err(invalid("it contains the clip loading into it"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Turn a response with an HTTP error status into an error.
+///
+/// Navigators that don't already fail such fetches hand back the server's
+/// error page as an ordinary response.
+///
+/// A `304 Not Modified` response is also an error. There's no HTTP cache to
+/// fill in the body it refers to, so it leaves the load without any content.
+fn reject_http_error(response: Response) -> Result<Response, Error> {
+    if response.status >= 400 || response.status == 304 {
+        Err(Error::HttpNotOk {
+            status: response.status,
+            body: response.body,
+        })
+    } else {
+        Ok(response)
+    }
+}
+
+/// Decode a text response body.
+///
+/// A byte order mark takes precedence over the `charset` of the
+/// `Content-Type` header. Text is assumed to be UTF-8 if neither is present,
+/// or if the declared charset isn't recognized.
+fn decode_text(body: &[u8], content_type: Option<&str>) -> String {
+    let charset = content_type.and_then(|content_type| {
+        content_type.split(';').skip(1).find_map(|parameter| {
+            let (name, value) = parameter.split_once('=')?;
+            if name.trim().eq_ignore_ascii_case("charset") {
+                Some(value.trim().trim_matches('"'))
+            } else {
+                None
+            }
+        })
+    });
+
+    let encoding = match charset {
+        Some(charset) => Encoding::for_label(charset.as_bytes()).unwrap_or_else(|| {
+            log::warn!("Unknown charset {}, decoding as UTF-8", charset);
+            UTF_8
+        }),
+        None => UTF_8,
+    };
+
+    // `decode` sniffs for a BOM, which overrides the given encoding.
+    encoding.decode(body).0.into_owned()
+}
+
+/// Parse a JSON response body.
+///
+/// A byte order mark and any whitespace around the document are ignored.
+fn parse_json(body: &[u8], content_type: Option<&str>) -> Result<JsonValue, json::Error> {
+    json::parse(decode_text(body, content_type).trim())
+}
+
+/// Parse the JSON document in a response.
+///
+/// A `204 No Content` response successfully loads nothing, so it is `null`
+/// rather than an empty document that fails to parse.
+fn parse_json_response(response: &Response) -> Result<JsonValue, json::Error> {
+    match response.status {
+        204 => Ok(JsonValue::Null),
+        _ => parse_json(&response.body, response.header("Content-Type")),
+    }
+}
+
+/// Convert a parsed JSON document into AVM2 values, as `JSON.parse` would.
+fn json_to_avm2<'gc>(
+    activation: &mut Avm2Activation<'_, 'gc, '_>,
+    json: &JsonValue,
+) -> Result<Avm2Value<'gc>, Avm2Error> {
+    let mc = activation.context.gc_context;
+
+    Ok(match json {
+        JsonValue::Null => Avm2Value::Null,
+        JsonValue::Short(s) => AvmString::new(mc, s.as_str()).into(),
+        JsonValue::String(s) => AvmString::new(mc, s.as_str()).into(),
+        JsonValue::Number(n) => f64::from(*n).into(),
+        JsonValue::Boolean(b) => (*b).into(),
+        JsonValue::Object(entries) => {
+            let object_class = activation.avm2().classes().object;
+            let mut object = object_class.construct(activation, &[])?;
+            for (key, value) in entries.iter() {
+                let value = json_to_avm2(activation, value)?;
+                let name = Avm2QName::new(Avm2Namespace::public(), AvmString::new(mc, key));
+                object.set_property(object, &name, value, activation)?;
+            }
+
+            object.into()
+        }
+        JsonValue::Array(items) => {
+            let values = items
+                .iter()
+                .map(|item| json_to_avm2(activation, item))
+                .collect::<Result<Vec<_>, _>>()?;
+            Avm2ArrayObject::from_storage(activation, Avm2ArrayStorage::from_args(&values))?.into()
+        }
+    })
+}
+
+/// Determine the HTTP status and body to report to content for a fetch.
+///
+/// Error responses still deliver their body if they have one. Fetches that
+/// never got a response from the server report a status of `0`.
+fn http_status_and_body(result: &Result<Response, Error>) -> (u16, Option<&[u8]>) {
+    match result {
+        Ok(response) => (response.status, Some(&response.body)),
+        Err(Error::HttpNotOk { status, body }) if !body.is_empty() => (*status, Some(body)),
+        Err(Error::HttpNotOk { status, .. }) => (*status, None),
+        Err(_) => (0, None),
+    }
+}
+
+/// Combine the bytes received before an interrupted fetch with the response
+/// to the `Range` request that resumed it.
+///
+/// Servers that ignore the range reply with the full body, which replaces
+/// whatever was received before.
+fn resume_response(received: Vec<u8>, mut response: Response) -> Result<Response, Error> {
+    if received.is_empty() || response.status != 206 {
+        return Ok(response);
+    }
+
+    let start = response
+        .header("Content-Range")
+        .and_then(|range| range.trim().strip_prefix("bytes "))
+        .and_then(|range| range.split('-').next())
+        .and_then(|start| start.trim().parse::<usize>().ok());
+    if start != Some(received.len()) {
+        return Err(Error::FetchError(format!(
+            "Resumed response for {} does not start at byte {}",
+            response.url,
+            received.len()
+        )));
+    }
+
+    let mut body = received;
+    body.append(&mut response.body);
+    response.body = body;
+    response.status = 200;
+    response
+        .headers
+        .retain(|(name, _)| !name.eq_ignore_ascii_case("Content-Range"));
+
+    Ok(response)
+}
+
+/// Undo any `Content-Encoding` the server applied to a response body.
+///
+/// Backends that decompress responses themselves are expected to drop the
+/// header, so anything still listed here has yet to be decoded. Encodings are
+/// applied in the order listed, so they are removed in reverse.
+fn decode_content_encoding(mut response: Response) -> Result<Response, Error> {
+    use flate2::read::{DeflateDecoder, GzDecoder, ZlibDecoder};
+    use std::io::Read;
+
+    let encodings: Vec<String> = match response.header("Content-Encoding") {
+        Some(encodings) => encodings
+            .split(',')
+            .map(|encoding| encoding.trim().to_ascii_lowercase())
+            .filter(|encoding| !encoding.is_empty() && encoding != "identity")
+            .collect(),
+        None => return Ok(response),
+    };
+
+    for encoding in encodings.iter().rev() {
+        let mut body = Vec::new();
+        let result = match encoding.as_str() {
+            "gzip" | "x-gzip" => GzDecoder::new(&response.body[..]).read_to_end(&mut body),
+            // Servers disagree on whether `deflate` is zlib-wrapped, so fall
+            // back to a raw stream if the zlib header is missing.
+            "deflate" => ZlibDecoder::new(&response.body[..])
+                .read_to_end(&mut body)
+                .or_else(|_| {
+                    body.clear();
+                    DeflateDecoder::new(&response.body[..]).read_to_end(&mut body)
+                }),
+            _ => {
+                return Err(Error::ContentEncodingError(format!(
+                    "unsupported encoding {}",
+                    encoding
+                )))
+            }
+        };
+
+        result.map_err(|e| Error::ContentEncodingError(format!("{}: {}", encoding, e)))?;
+        response.body = body;
+    }
+
+    response
+        .headers
+        .retain(|(name, _)| !name.eq_ignore_ascii_case("Content-Encoding"));
+
+    Ok(response)
+}
+
+/// Check that a movie may load data from a URL.
+///
+/// Loads from the movie's own origin are always permitted. Other origins must
+/// list the movie's host in their `crossdomain.xml`.
+async fn check_policy_file(
+    player: &Arc<Mutex<Player>>,
+    movie_url: &Url,
+    url: &Url,
+    policy_options: RequestOptions,
+) -> Result<(), Error> {
+    if movie_url.origin() == url.origin() {
+        return Ok(());
+    }
+
+    let denied = || {
+        Error::SecurityError(format!(
+            "{} is not permitted to load data from {}",
+            movie_url, url
+        ))
+    };
+    let host = movie_url.host_str().ok_or_else(denied)?;
+    let policy_url = url.join("/crossdomain.xml").map_err(|_| denied())?;
+
+    let policy_fetch = player
+        .lock()
+        .expect("Could not lock player!!")
+        .navigator()
+        .fetch(policy_url.as_str(), policy_options);
+    let policy = policy_fetch
+        .await
+        .and_then(decode_content_encoding)
+        .ok()
+        .filter(|response| (200..300).contains(&response.status))
+        .and_then(|response| PolicyFile::parse(&response.body));
+
+    match policy {
+        Some(policy) if policy.allows(host) => Ok(()),
+        Some(_) => Err(denied()),
+        None => {
+            log::warn!(
+                "No valid policy file found at {}",
+                redact_url_credentials(policy_url.as_str())
+            );
+            Err(denied())
+        }
+    }
+}
+
+/// A request header that is sent with every fetch made by the loaders.
+///
+/// Some content only serves its assets to requests that look like they came
+/// from the Flash Player, such as those with an `x-flash-version` header.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SpoofedHeader {
+    pub name: String,
+    pub value: String,
+
+    /// Whether this header replaces one already set on the request.
+    pub force: bool,
+}
+
+/// Add spoofed headers to a request.
+fn apply_spoofed_headers(options: &mut RequestOptions, spoofed_headers: &[SpoofedHeader]) {
+    for spoofed in spoofed_headers {
+        if spoofed.force || options.header(&spoofed.name).is_none() {
+            options.set_header(spoofed.name.clone(), spoofed.value.clone());
+        }
+    }
+}
+
+/// Describes how failed fetches are retried before a load is given up on.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct RetryPolicy {
+    /// The maximum number of times a request is attempted, including the
+    /// initial attempt.
+    pub max_attempts: u32,
+
+    /// How long to wait before the first retry.
+    ///
+    /// Each subsequent retry waits twice as long as the one before it.
+    pub base_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// How long to wait before the given retry, counting from 1.
+    pub fn delay_before_retry(&self, retry: u32) -> Duration {
+        self.base_delay * 2u32.saturating_pow(retry.saturating_sub(1))
+    }
+}
+
+/// A least-recently-used cache of parsed movies, keyed by their resolved URL.
+///
+/// Content that wants a fresh copy of a movie can bypass the cache the same
+/// way it would in Flash: by adding a unique query string to the URL.
+pub struct MovieCache {
+    /// The cached movies, ordered from least to most recently used.
+    movies: IndexMap<String, Arc<SwfMovie>>,
+
+    /// The maximum number of movies to keep.
+    ///
+    /// A capacity of zero disables the cache entirely.
+    capacity: usize,
+}
+
+impl MovieCache {
+    /// Construct an empty cache holding at most `capacity` movies.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            movies: IndexMap::new(),
+            capacity,
+        }
+    }
+
+    /// Change the maximum number of movies to keep, evicting the least
+    /// recently used movies if necessary.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.evict();
+    }
+
+    /// Look up a movie by URL, marking it as recently used.
+    pub fn get(&mut self, url: &str) -> Option<Arc<SwfMovie>> {
+        let movie = self.movies.shift_remove(url)?;
+        self.movies.insert(url.to_string(), movie.clone());
+
+        Some(movie)
+    }
+
+    /// Store a movie under the given URL.
+    pub fn insert(&mut self, url: String, movie: Arc<SwfMovie>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        self.movies.shift_remove(&url);
+        self.movies.insert(url, movie);
+        self.evict();
+    }
+
+    /// Remove all movies from the cache.
+    pub fn clear(&mut self) {
+        self.movies.clear();
+    }
+
+    fn evict(&mut self) {
+        while self.movies.len() > self.capacity {
+            self.movies.shift_remove_index(0);
+        }
+    }
+}
+
+/// Identifies fetches that identical data loads may share.
+///
+/// The response size limit and expected digest are part of the key, as the
+/// fetch checks the response against them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct FetchKey {
+    url: String,
+    body: Option<(Vec<u8>, String)>,
+    headers: Vec<(String, String)>,
+    max_response_size: Option<usize>,
+    expected_digest: Option<(DigestAlgorithm, Vec<u8>)>,
+}
+
+impl FetchKey {
+    /// Build the key of a fetch, or `None` if it must not be shared.
+    ///
+    /// Only GET requests are shared, as a POST may have side effects on the
+    /// server that content expects to happen once per load.
+    fn new(url: &str, options: &RequestOptions) -> Option<Self> {
+        match options.method() {
+            NavigationMethod::Get => Some(Self {
+                url: url.to_string(),
+                body: options.body().clone(),
+                headers: options.headers().to_vec(),
+                max_response_size: options.max_response_size(),
+                expected_digest: options.expected_digest().cloned(),
+            }),
+            NavigationMethod::Post => None,
+        }
+    }
+}
+
+/// A fetch whose result is shared by every identical data load started
+/// while it was in flight.
+struct SharedFetch {
+    /// The fetch, until it completes.
+    fetch: Option<OwnedFuture<Response, Error>>,
+
+    /// The result of the fetch, once it completes.
+    result: Option<Result<Response, Error>>,
+
+    /// The tasks of the loads waiting for the fetch.
+    wakers: Vec<Waker>,
+}
+
+impl SharedFetch {
+    fn new(fetch: OwnedFuture<Response, Error>) -> Self {
+        Self {
+            fetch: Some(fetch),
+            result: None,
+            wakers: Vec::new(),
+        }
+    }
+
+    /// Check if the fetch has yet to complete.
+    fn is_in_flight(&self) -> bool {
+        self.result.is_none()
+    }
+}
+
+/// One load's view of a `SharedFetch`.
+///
+/// Whichever load is polled drives the fetch, and every load receives its
+/// own copy of the result.
+struct SharedFetchFuture(Rc<RefCell<SharedFetch>>);
+
+impl Future for SharedFetchFuture {
+    type Output = Result<Response, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut shared = self.0.borrow_mut();
+
+        if let Some(fetch) = shared.fetch.as_mut() {
+            match fetch.as_mut().poll(cx) {
+                Poll::Ready(result) => {
+                    shared.fetch = None;
+                    shared.result = Some(result);
+                    for waker in shared.wakers.drain(..) {
+                        waker.wake();
+                    }
+                }
+                Poll::Pending => {
+                    // The fetch only wakes the last load to poll it, so the
+                    // others are woken once it completes.
+                    if !shared.wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                        shared.wakers.push(cx.waker().clone());
+                    }
+
+                    return Poll::Pending;
+                }
+            }
+        }
+
+        Poll::Ready(match shared.result.as_ref() {
+            Some(Ok(response)) => Ok(response.clone()),
+            Some(Err(error)) => Err(clone_fetch_error(error)),
+            None => Err(Error::Cancelled),
+        })
+    }
+}
+
+/// Copy the error of a shared fetch for each load waiting on it.
+///
+/// Errors that carry data content can observe keep it; anything else is
+/// reported as a plain fetch error with the same message.
+fn clone_fetch_error(error: &Error) -> Error {
+    match error {
+        Error::Cancelled => Error::Cancelled,
+        Error::HttpNotOk { status, body } => Error::HttpNotOk {
+            status: *status,
+            body: body.clone(),
+        },
+        Error::NetworkUnavailable => Error::NetworkUnavailable,
+        Error::SecurityError(message) => Error::SecurityError(message.clone()),
+        Error::Blocked(url) => Error::Blocked(url.clone()),
+        Error::ResponseTooLarge { limit } => Error::ResponseTooLarge { limit: *limit },
+        Error::DigestMismatch(algorithm) => Error::DigestMismatch(*algorithm),
+        error => Error::FetchError(error.to_string()),
+    }
+}
+
+/// What an embedder's content decoder produced from loaded data.
+pub enum DecodedContent {
+    /// A movie, which is loaded like any other SWF.
+    Movie(Arc<SwfMovie>),
+
+    /// An image, which is placed into the target clip.
+    Bitmap(Bitmap),
+}
+
+/// An embedder's decoder for a custom content format.
+struct ContentDecoder {
+    /// Whether the decoder handles the given data.
+    matches: Box<dyn Fn(&[u8]) -> bool>,
+
+    /// Decode the data, or describe why it couldn't be decoded.
+    decode: Box<dyn Fn(&[u8]) -> Result<DecodedContent, String>>,
+}
+
+/// What to do with a URL an embedder's URL filter was asked about.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum UrlDecision {
+    /// Fetch the URL as-is.
+    Allow,
+
+    /// Fetch a different URL instead.
+    ///
+    /// Content often needs its query string, so a rewrite that keeps the
+    /// path but has no query of its own keeps the original query. Any other
+    /// rewrite is used as-is.
+    RewriteTo(String),
+
+    /// Fail the fetch.
+    Deny,
+}
+
+/// Carry the query of a URL over to the URL it was rewritten to, if the
+/// rewrite only moved it to another origin.
+fn keep_query(url: &str, new_url: String) -> String {
+    let (url, mut rewritten) = match (Url::parse(url), Url::parse(&new_url)) {
+        (Ok(url), Ok(rewritten)) => (url, rewritten),
+        _ => return new_url,
+    };
+
+    if rewritten.query().is_none() && rewritten.path() == url.path() {
+        rewritten.set_query(url.query());
+        return rewritten.into();
+    }
+
+    new_url
+}
+
+/// Holds all in-progress loads for the player.
+pub struct LoadManager<'gc> {
+    loaders: Arena<Loader<'gc>>,
+
+    /// The handles of all loaders, in the order they were started.
+    ///
+    /// The arena reuses the slots of removed loaders, so its own order
+    /// doesn't reflect when loads began.
+    start_order: IndexSet<Handle>,
+
+    /// How failed fetches should be retried, if at all.
+    retry_policy: Option<RetryPolicy>,
+
+    /// Movies that were previously loaded into clips.
+    movie_cache: MovieCache,
+
+    /// Whether cross-origin data and movie loads must be permitted by the
+    /// target server's policy file.
+    check_policy_files: bool,
+
+    /// Headers added to every fetch.
+    spoofed_headers: Vec<SpoofedHeader>,
+
+    /// Which redirects fetches may follow.
+    redirect_policy: RedirectPolicy,
+
+    /// The largest response body fetches may receive, if limited.
+    max_response_size: Option<usize>,
+
+    /// The `User-Agent` header sent with every fetch, if overridden.
+    user_agent: Option<String>,
+
+    /// Cookies set by responses, which are sent back with later fetches.
+    cookie_jar: CookieJar,
+
+    /// Embedder callback that may rewrite or block the URL of any fetch.
+    url_filter: Option<Box<dyn Fn(&str) -> UrlDecision>>,
+
+    /// Init callbacks of movie loads that are ready to run once the current
+    /// frame's scripts have.
+    pending_inits: Vec<Box<dyn FnOnce()>>,
+
+    /// Data fetches that are in flight, which identical loads share.
+    in_flight_fetches: HashMap<FetchKey, rc::Weak<RefCell<SharedFetch>>>,
+
+    /// Embedder callback told about every change of a movie loader's status.
+    status_observer: Option<Box<dyn Fn(Handle, LoaderStatus)>>,
+
+    /// The newest SWF version that movie loads accept, if limited.
+    max_swf_version: Option<u8>,
+
+    /// Embedder decoders for movie loads that aren't SWFs or known images.
+    content_decoders: Vec<ContentDecoder>,
+
+    /// Whether the fonts exported by movies loaded into clips are shared
+    /// with every other movie.
+    share_loaded_fonts: bool,
+
+    /// Whether movies loaded into clips must be verified against a digest.
+    require_verified_movies: bool,
+}
+
+unsafe impl<'gc> Collect for LoadManager<'gc> {
+    fn trace(&self, cc: CollectionContext) {
+        for (_, loader) in self.loaders.iter() {
+            loader.trace(cc)
+        }
+    }
+}
+
+impl<'gc> LoadManager<'gc> {
+    /// Construct a new `LoadManager`.
+    pub fn new() -> Self {
+        Self {
+            loaders: Arena::new(),
+            start_order: IndexSet::new(),
+            retry_policy: None,
+            movie_cache: MovieCache::new(0),
+            check_policy_files: false,
+            spoofed_headers: Vec::new(),
+            redirect_policy: RedirectPolicy::FollowAll,
+            max_response_size: None,
+            user_agent: None,
+            cookie_jar: CookieJar::new(),
+            url_filter: None,
+            pending_inits: Vec::new(),
+            in_flight_fetches: HashMap::new(),
+            status_observer: None,
+            max_swf_version: None,
+            content_decoders: Vec::new(),
+            share_loaded_fonts: false,
+            require_verified_movies: false,
+        }
+    }
+
+    /// Set how failed fetches should be retried.
+    ///
+    /// Only GET requests that fail without reaching the server are retried.
+    /// Responses with an HTTP error status are final. `None` disables
+    /// retries, which is the default.
+    pub fn set_retry_policy(&mut self, retry_policy: Option<RetryPolicy>) {
+        self.retry_policy = retry_policy;
+    }
+
+    /// Set a callback that decides whether each fetch may proceed.
+    ///
+    /// The callback receives the absolute URL about to be fetched. Blocked
+    /// fetches fail with `Error::Blocked`, which loaders report the same way
+    /// as a network failure.
+    pub fn set_url_filter(&mut self, url_filter: Option<Box<dyn Fn(&str) -> UrlDecision>>) {
+        self.url_filter = url_filter;
+    }
+
+    /// Set a callback that is told whenever a movie loader's status changes.
+    ///
+    /// This is meant for debugging the order in which loads finish.
+    pub fn set_status_observer(
+        &mut self,
+        status_observer: Option<Box<dyn Fn(Handle, LoaderStatus)>>,
+    ) {
+        self.status_observer = status_observer;
+    }
+
+    /// Set the newest SWF version that movie loads accept.
+    ///
+    /// This lets embedders emulate an older Flash Player, which refuses to
+    /// run movies made for a newer one. `None` accepts any version, which is
+    /// the default.
+    pub fn set_max_swf_version(&mut self, max_swf_version: Option<u8>) {
+        self.max_swf_version = max_swf_version;
+    }
+
+    /// Set whether the fonts exported by movies loaded into clips are
+    /// shared with every other movie.
+    ///
+    /// This lets a movie load a font library and use its fonts by name. A
+    /// shared font replaces any earlier shared font with the same name and
+    /// style, but a movie's own fonts take precedence over shared ones.
+    pub fn set_share_loaded_fonts(&mut self, share_loaded_fonts: bool) {
+        self.share_loaded_fonts = share_loaded_fonts;
+    }
+
+    /// Set whether movies loaded into clips must be verified.
+    ///
+    /// When enabled, a movie load fails unless its request options carry an
+    /// expected digest, and a movie that doesn't match its digest fails as
+    /// usual. This secures libraries that are only trusted in a known form.
+    pub fn set_require_verified_movies(&mut self, require_verified_movies: bool) {
+        self.require_verified_movies = require_verified_movies;
+    }
+
+    /// Check that a movie load can be verified, if verification is required.
+    fn check_movie_verifiable(&self, url: &str, options: &RequestOptions) -> Result<(), Error> {
+        if self.require_verified_movies && options.expected_digest().is_none() {
+            Err(Error::UnverifiedMovie(url.to_string()))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Register a decoder for a custom content format.
+    ///
+    /// Movie loads whose data isn't recognized as a SWF or a supported image
+    /// are offered to each decoder in the order they were registered. The
+    /// first decoder that `matches` the data decodes it. A decoding error
+    /// fails the load in the same way as an invalid SWF.
+    pub fn register_content_decoder(
+        &mut self,
+        matches: Box<dyn Fn(&[u8]) -> bool>,
+        decode: Box<dyn Fn(&[u8]) -> Result<DecodedContent, String>>,
+    ) {
+        self.content_decoders
+            .push(ContentDecoder { matches, decode });
+    }
+
+    /// Decode the response to a movie load with the first content decoder
+    /// that handles it.
+    ///
+    /// The result is the same as that of `parse_movie_response`.
+    fn decode_custom_content(
+        &self,
+        response: &Response,
+    ) -> Result<(usize, Arc<SwfMovie>, Option<Bitmap>), Error> {
+        let decoder = self
+            .content_decoders
+            .iter()
+            .find(|decoder| (decoder.matches)(&response.body))
+            .ok_or_else(|| Error::UnknownContentType(response.url.clone()))?;
+
+        let length = response.body.len();
+        match (decoder.decode)(&response.body) {
+            Ok(DecodedContent::Movie(movie)) => Ok((length, movie, None)),
+            Ok(DecodedContent::Bitmap(bitmap)) => {
+                let movie = Arc::new(SwfMovie::empty(NEWEST_PLAYER_VERSION));
+                Ok((length, movie, Some(into_rgba(bitmap))))
+            }
+            Err(message) => Err(Error::ContentDecodeFailed {
+                url: response.url.clone(),
+                message,
+            }),
+        }
+    }
+
+    /// Change the status of a movie loader, telling the status observer if
+    /// it changed.
+    fn set_loader_status(&mut self, handle: Handle, status: LoaderStatus) {
+        let changed = match self.loaders.get_mut(handle) {
+            Some(Loader::Movie { loader_status, .. }) if *loader_status != status => {
+                *loader_status = status;
+                true
+            }
+            _ => false,
+        };
+
+        if changed {
+            if let Some(status_observer) = &self.status_observer {
+                status_observer(handle, status);
+            }
+        }
+    }
+
+    /// Apply the URL filter, if any, to a URL about to be fetched.
+    fn filter_url(&self, url: String) -> Result<String, Error> {
+        let url_filter = match &self.url_filter {
+            Some(url_filter) => url_filter,
+            None => return Ok(url),
+        };
+
+        match url_filter(&url) {
+            UrlDecision::Allow => Ok(url),
+            UrlDecision::RewriteTo(new_url) => {
+                let new_url = keep_query(&url, new_url);
+                log::info!(
+                    "Load of {} rewritten to {}",
+                    redact_url_credentials(&url),
+                    redact_url_credentials(&new_url)
+                );
+                Ok(new_url)
+            }
+            UrlDecision::Deny => Err(Error::Blocked(url)),
+        }
+    }
+
+    /// Retrieve the compressed bytes loaded and total bytes of a movie load,
+    /// as of its last progress event.
+    ///
+    /// For batch loads, this is the combined size of every fetch that has
+    /// finished so far.
+    ///
+    /// Loads that have not reported any progress yet return `(0, 0)`. Loads
+    /// that are not loading movies into clips or batches, or that have
+    /// already been removed, return `None`.
+    pub fn loader_progress(&self, handle: Handle) -> Option<(usize, usize)> {
+        match self.get_loader(handle)? {
+            Loader::Movie {
+                bytes_loaded,
+                bytes_total,
+                ..
+            } => Some((*bytes_loaded, *bytes_total)),
+            Loader::Batch { state, .. } => Some(state.progress()),
+            _ => None,
+        }
+    }
+
+    /// List every loader that is still in flight.
+    ///
+    /// This is intended for debugging tools, and returns a snapshot that
+    /// does not borrow from the garbage-collected loaders.
+    pub fn active_loaders(&self) -> Vec<LoaderInfoSnapshot> {
+        self.start_order
+            .iter()
+            .filter_map(|handle| Some(self.loaders.get(*handle)?.snapshot(*handle)))
+            .collect()
+    }
+
+    /// Set whether cross-origin loads must be permitted by a policy file.
+    pub fn set_check_policy_files(&mut self, check_policy_files: bool) {
+        self.check_policy_files = check_policy_files;
+    }
+
+    /// Set the headers to add to every fetch.
+    pub fn set_spoofed_headers(&mut self, spoofed_headers: Vec<SpoofedHeader>) {
+        self.spoofed_headers = spoofed_headers;
+    }
+
+    /// Set which redirects fetches may follow.
+    pub fn set_redirect_policy(&mut self, redirect_policy: RedirectPolicy) {
+        self.redirect_policy = redirect_policy;
+    }
+
+    /// Set the `User-Agent` header to send with every fetch.
+    ///
+    /// `None` leaves it up to the navigator, which is the default.
+    pub fn set_user_agent(&mut self, user_agent: Option<String>) {
+        self.user_agent = user_agent;
+    }
+
+    /// Set the largest response body fetches may receive.
+    ///
+    /// Requests that set their own limit keep it. `None` removes the limit,
+    /// which is the default.
+    pub fn set_max_response_size(&mut self, max_response_size: Option<usize>) {
+        self.max_response_size = max_response_size;
+    }
+
+    /// Retrieve the cache of movies loaded into clips.
+    ///
+    /// The cache starts out disabled; give it a capacity to enable it.
+    pub fn movie_cache_mut(&mut self) -> &mut MovieCache {
+        &mut self.movie_cache
+    }
+
+    /// Construct a future that fetches a URL through the player's navigator.
+    ///
+    /// Fetch errors on GET requests are retried according to the current
+    /// retry policy. Other requests are never retried, as they may not be
+    /// idempotent.
+    fn fetch(
+        &self,
+        player: Weak<Mutex<Player>>,
+        url: &str,
+        mut options: RequestOptions,
+    ) -> OwnedFuture<Response, Error> {
+        if let Some(user_agent) = &self.user_agent {
+            options.set_header("User-Agent", user_agent.clone());
+        }
+        apply_spoofed_headers(&mut options, &self.spoofed_headers);
+        options.set_redirect_policy(self.redirect_policy);
+        if options.max_response_size().is_none() {
+            options.set_max_response_size(self.max_response_size);
+        }
+
+        let url = url.to_string();
+        let retry_policy = match options.method() {
+            NavigationMethod::Get => self.retry_policy,
+            NavigationMethod::Post => None,
+        };
+        let max_attempts = retry_policy.map(|p| p.max_attempts).unwrap_or(1);
+
+        Box::pin(async move {
+            let player = player.upgrade().ok_or(Error::Cancelled)?;
+            let url = player.lock().expect("Could not lock player!!").update(
+                |uc| -> Result<String, Error> {
+                    let url = uc.navigator.resolve_relative_url(&url).into_owned();
+                    uc.load_manager.filter_url(url)
+                },
+            )?;
+            let mut attempt = 1;
+            let mut received = Vec::new();
+
+            loop {
+                let mut attempt_options = options.clone();
+                if !received.is_empty() {
+                    attempt_options.set_header("Range", format!("bytes={}-", received.len()));
+                }
+
+                let cookies = player
+                    .lock()
+                    .expect("Could not lock player!!")
+                    .update(|uc| uc.load_manager.cookie_header(&url));
+                if let Some(cookies) = cookies {
+                    if attempt_options.header("Cookie").is_none() {
+                        attempt_options.set_header("Cookie", cookies);
+                    }
+                }
+
+                let fetch = player
+                    .lock()
+                    .expect("Could not lock player!!")
+                    .navigator()
+                    .fetch(&url, attempt_options);
+
+                let result = fetch.await;
+                if let Ok(response) = &result {
+                    player
+                        .lock()
+                        .expect("Could not lock player!!")
+                        .update(|uc| uc.load_manager.store_cookies(response));
+                }
+
+                match result {
+                    Ok(response) => {
+                        // Navigators may not enforce the limit themselves,
+                        // and decoding can make the body grow.
+                        return resume_response(received, response)
+                            .and_then(decode_content_encoding)
+                            .and_then(|response| {
+                                options.check_response_size(response.body.len())?;
+                                options.verify_digest(&response.body)?;
+                                Ok(response)
+                            });
+                    }
+                    Err(Error::FetchInterrupted {
+                        status,
+                        body,
+                        message,
+                    }) if attempt < max_attempts => {
+                        if status == 206 {
+                            received.extend(body);
+                        } else {
+                            received = body;
+                        }
+
+                        let delay = retry_policy.unwrap().delay_before_retry(attempt);
+                        log::warn!(
+                            "Fetch of {} interrupted after {} bytes ({}), resuming in {:?}",
+                            redact_url_credentials(&url),
+                            received.len(),
+                            message,
+                            delay
+                        );
+
+                        let sleep = player
+                            .lock()
+                            .expect("Could not lock player!!")
+                            .navigator()
+                            .sleep(delay);
+                        sleep.await?;
+
+                        attempt += 1;
+                    }
+                    Err(Error::FetchError(message)) if attempt < max_attempts => {
+                        // A failed resume may mean the server rejected the
+                        // range, so start over from the beginning.
+                        received.clear();
+
+                        let delay = retry_policy.unwrap().delay_before_retry(attempt);
+                        log::warn!(
+                            "Fetch of {} failed ({}), retrying in {:?}",
+                            redact_url_credentials(&url),
+                            message,
+                            delay
+                        );
+
+                        let sleep = player
+                            .lock()
+                            .expect("Could not lock player!!")
+                            .navigator()
+                            .sleep(delay);
+                        sleep.await?;
+
+                        attempt += 1;
+                    }
+                    Err(Error::FetchInterrupted { message, .. }) => {
+                        return Err(Error::FetchError(message))
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+        })
+    }
+
+    /// Build the `Cookie` header to send with a fetch of `url`, if any.
+    fn cookie_header(&self, url: &str) -> Option<String> {
+        let url = Url::parse(url).ok()?;
+        self.cookie_jar.cookie_header(&url, Utc::now())
+    }
+
+    /// Keep the cookies set by a response.
+    fn store_cookies(&mut self, response: &Response) {
+        let url = match Url::parse(&response.url) {
+            Ok(url) => url,
+            Err(_) => return,
+        };
+
+        let now = Utc::now();
+        for (name, value) in &response.headers {
+            if name.eq_ignore_ascii_case("Set-Cookie") {
+                self.cookie_jar.store(&url, value, now);
+            }
+        }
+    }
+
+    /// Construct a future that fetches data for script code.
+    ///
+    /// Identical GET requests made while an earlier one is still in flight
+    /// share its fetch rather than making their own.
+    fn fetch_data(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        url: &str,
+        options: RequestOptions,
+    ) -> OwnedFuture<Response, Error> {
+        let key = FetchKey::new(url, &options);
+        self.share_fetch(key, |manager| manager.fetch_permitted(player, url, options))
+    }
+
+    /// Join the in-flight fetch with the given key, or start a new one with
+    /// `start_fetch` if there isn't one.
+    ///
+    /// Fetches without a key are never shared.
+    fn share_fetch(
+        &mut self,
+        key: Option<FetchKey>,
+        start_fetch: impl FnOnce(&Self) -> OwnedFuture<Response, Error>,
+    ) -> OwnedFuture<Response, Error> {
+        let key = match key {
+            Some(key) => key,
+            None => return start_fetch(self),
+        };
+
+        self.in_flight_fetches.retain(|_, fetch| {
+            fetch.upgrade().map_or(false, |fetch| {
+                fetch
+                    .try_borrow()
+                    .map_or(true, |fetch| fetch.is_in_flight())
+            })
+        });
+
+        let shared = match self.in_flight_fetches.get(&key).and_then(rc::Weak::upgrade) {
+            Some(shared) => shared,
+            None => {
+                let shared = Rc::new(RefCell::new(SharedFetch::new(start_fetch(self))));
+                self.in_flight_fetches.insert(key, Rc::downgrade(&shared));
+                shared
+            }
+        };
+
+        Box::pin(SharedFetchFuture(shared))
+    }
+
+    /// Construct a future that fetches a URL once the root movie is permitted
+    /// to load it.
+    ///
+    /// When policy file checks are enabled, loads from a different origin
+    /// than the root movie are only made once that origin's
+    /// `crossdomain.xml` permits the root movie's host.
+    fn fetch_permitted(
+        &self,
+        player: Weak<Mutex<Player>>,
+        url: &str,
+        options: RequestOptions,
+    ) -> OwnedFuture<Response, Error> {
+        let fetch = self.fetch(player.clone(), url, options);
+        if !self.check_policy_files {
+            return fetch;
+        }
+
+        let url = url.to_string();
+        let mut policy_options = RequestOptions::get();
+        apply_spoofed_headers(&mut policy_options, &self.spoofed_headers);
+
+        Box::pin(async move {
+            let player = player.upgrade().ok_or(Error::Cancelled)?;
+            let (movie_url, url) = player
+                .lock()
+                .expect("Could not lock player!!")
+                .update(|uc| {
+                    (
+                        uc.swf.url().map(|u| u.to_string()),
+                        uc.navigator.resolve_relative_url(&url).into_owned(),
+                    )
+                });
+
+            let (movie_url, url) = match (movie_url.map(|u| Url::parse(&u)), Url::parse(&url)) {
+                (Some(Ok(movie_url)), Ok(url)) => (movie_url, url),
+                // Without two absolute URLs, there's no origin to compare.
+                _ => return fetch.await,
+            };
+
+            check_policy_file(&player, &movie_url, &url, policy_options.clone()).await?;
+            let response = fetch.await?;
+
+            // A redirect to yet another origin needs that origin's permission
+            // too.
+            if let Ok(final_url) = Url::parse(&response.url) {
+                if final_url.origin() != url.origin() {
+                    check_policy_file(&player, &movie_url, &final_url, policy_options).await?;
+                }
+            }
+
+            Ok(response)
+        })
+    }
+
+    /// Construct a future that looks up a movie load in the movie cache.
+    ///
+    /// A cached movie skips the fetch, so it is only used when the fetch
+    /// would have gone ahead for the same URL: the URL's origin must permit
+    /// the load when policy file checks are enabled.
+    fn cached_movie(
+        &self,
+        player: Weak<Mutex<Player>>,
+        url: &str,
+    ) -> OwnedFuture<Option<Arc<SwfMovie>>, Error> {
+        let check_policy_files = self.check_policy_files;
+        let url = url.to_string();
+        let mut policy_options = RequestOptions::get();
+        apply_spoofed_headers(&mut policy_options, &self.spoofed_headers);
+
+        Box::pin(async move {
+            let player = player.upgrade().ok_or(Error::Cancelled)?;
+            let (movie_url, url, movie) = player.lock().expect("Could not lock player!!").update(
+                |uc| -> Result<_, Error> {
+                    let url = uc.navigator.resolve_relative_url(&url).into_owned();
+                    // A rewritten URL is fetched from where it now points,
+                    // and a blocked one isn't loaded at all.
+                    let movie = if uc.load_manager.filter_url(url.clone())? == url {
+                        uc.load_manager.movie_cache_mut().get(&url)
+                    } else {
+                        None
+                    };
+                    Ok((uc.swf.url().map(|u| u.to_string()), url, movie))
+                },
+            )?;
+
+            if check_policy_files && movie.is_some() {
+                if let (Some(Ok(movie_url)), Ok(url)) =
+                    (movie_url.map(|u| Url::parse(&u)), Url::parse(&url))
+                {
+                    check_policy_file(&player, &movie_url, &url, policy_options).await?;
+                }
+            }
+
+            Ok(movie)
+        })
+    }
+
+    /// Add a new loader to the `LoadManager`.
+    ///
+    /// This function returns the loader handle for later inspection. A loader
+    /// handle is valid for as long as the load operation. Once the load
+    /// finishes, the handle will be invalidated (and the underlying loader
+    /// deleted).
+    pub fn add_loader(&mut self, loader: Loader<'gc>) -> Handle {
+        let handle = self.loaders.insert(loader);
+        self.loaders
+            .get_mut(handle)
+            .unwrap()
+            .introduce_loader_handle(handle);
+        self.start_order.insert(handle);
+
+        handle
+    }
+
+    /// Remove a loader, invalidating its handle.
+    fn remove_loader(&mut self, handle: Handle) -> Option<Loader<'gc>> {
+        self.start_order.shift_remove(&handle);
+        self.loaders.remove(handle)
+    }
+
+    /// Cancel an in-flight load.
+    ///
+    /// The loader's future will notice that it has been removed the next
+    /// time it needs the player, and finish with `Error::Cancelled` without
+    /// firing any further events. Returns `false` if the load had already
+    /// finished or been cancelled.
+    pub fn cancel(&mut self, handle: Handle) -> bool {
+        self.remove_loader(handle).is_some()
+    }
+
+    /// Cancel every in-flight load.
+    ///
+    /// This is used when the player is reset, so that loads started by the
+    /// old movie can't affect whatever replaces it.
+    pub fn abort_all(&mut self) {
+        for handle in std::mem::take(&mut self.start_order) {
+            self.loaders.remove(handle);
+        }
+        self.pending_inits.clear();
+    }
+
+    /// Check if a handle still refers to a loader.
+    ///
+    /// Handles are generational, so a handle whose load has finished or been
+    /// cancelled stays invalid even after a new loader takes over its slot.
+    pub fn is_handle_valid(&self, handle: Handle) -> bool {
+        self.loaders.contains(handle)
+    }
+
+    /// Retrieve a loader by handle.
+    ///
+    /// Returns `None` for a handle whose load has finished or been cancelled,
+    /// even if a new loader has since taken over its slot.
+    pub fn get_loader(&self, handle: Handle) -> Option<&Loader<'gc>> {
+        self.loaders.get(handle)
+    }
+
+    /// Retrieve a loader by handle for mutation.
+    ///
+    /// As with `get_loader`, a stale handle never refers to a newer loader.
+    pub fn get_loader_mut(&mut self, handle: Handle) -> Option<&mut Loader<'gc>> {
+        self.loaders.get_mut(handle)
+    }
+
+    /// Kick off the root movie load.
+    ///
+    /// The root movie is special because it determines a few bits of player
+    /// state, such as the size of the stage and the current frame rate. Ergo,
+    /// this method should only be called once, by the player that is trying to
+    /// kick off its root movie load.
+    pub fn load_root_movie(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        url: String,
+        options: RequestOptions,
+        parameters: Vec<(String, String)>,
+        on_metadata: Box<dyn FnOnce(&swf::HeaderExt)>,
+    ) -> OwnedFuture<(), Error> {
+        let fetch = self.fetch(player.clone(), &url, options);
+        let loader = Loader::RootMovie {
+            self_handle: None,
+            url: Some(url.clone()),
+        };
+        let handle = self.add_loader(loader);
+
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.introduce_loader_handle(handle);
+
+        loader.root_movie_loader(player, fetch, url, parameters, on_metadata)
+    }
+
+    /// Kick off a movie clip load.
+    ///
+    /// When policy file checks are enabled, a movie from another origin is
+    /// only loaded once that origin's policy file permits it.
+    ///
+    /// A target that can't hold a movie fails the load with
+    /// `Error::InvalidLoadTarget` before anything is fetched.
+    ///
+    /// Returns the loader's async process, which you will need to spawn.
+    pub fn load_movie_into_clip(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        target_clip: DisplayObject<'gc>,
+        url: String,
+        options: RequestOptions,
+        mut load_options: MovieLoadOptions<'gc>,
+    ) -> OwnedFuture<(), Error> {
+        if let Err(e) = check_load_target(target_clip, load_options.requesting_clip) {
+            return Box::pin(async move { Err(e) });
+        }
+
+        // Only plain GET requests are cacheable. Cached movies would skip
+        // digest verification, so verified loads and loads that must be
+        // verified always fetch.
+        let verifiable = self.check_movie_verifiable(&url, &options);
+        let use_cache = verifiable.is_ok()
+            && matches!(options.method(), NavigationMethod::Get)
+            && options.body().is_none()
+            && options.expected_digest().is_none();
+        let fetch: OwnedFuture<Response, Error> = match verifiable {
+            Ok(()) => self.fetch_permitted(player.clone(), &url, options),
+            Err(e) => Box::pin(async move { Err(e) }),
+        };
+        let loader = Loader::Movie {
+            self_handle: None,
+            url: Some(url.clone()),
+            target_clip,
+            target_broadcaster: load_options.target_broadcaster,
+            loader_status: LoaderStatus::Pending,
+            bytes_loaded: 0,
+            bytes_total: 0,
+            start_frame: load_options.start_frame.take(),
+            on_init: load_options.on_init.take(),
+        };
+        let handle = self.add_loader(loader);
+
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.introduce_loader_handle(handle);
+
+        loader.movie_loader(
+            player,
+            MovieSource::Fetch(fetch),
+            url,
+            use_cache,
+            load_options,
+        )
+    }
+
+    /// Load an already parsed movie into a clip.
+    ///
+    /// This skips fetching and parsing, but otherwise fires the same events
+    /// as `load_movie_into_clip`. Loading into the root clip replaces the
+    /// root movie.
+    ///
+    /// Returns the loader's async process, which you will need to spawn.
+    pub fn load_parsed_movie_into_clip(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        target_clip: DisplayObject<'gc>,
+        movie: Arc<SwfMovie>,
+        target_broadcaster: Option<Object<'gc>>,
+    ) -> OwnedFuture<(), Error> {
+        if let Err(e) = check_load_target(target_clip, None) {
+            return Box::pin(async move { Err(e) });
+        }
+
+        let url = movie.url().unwrap_or("").to_string();
+        let loader = Loader::Movie {
+            self_handle: None,
+            url: movie.url().map(|url| url.to_string()),
+            target_clip,
+            target_broadcaster,
+            loader_status: LoaderStatus::Pending,
+            bytes_loaded: 0,
+            bytes_total: 0,
+            start_frame: None,
+            on_init: None,
+        };
+        let handle = self.add_loader(loader);
+
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.introduce_loader_handle(handle);
+
+        loader.movie_loader(
+            player,
+            MovieSource::Parsed(movie),
+            url,
+            false,
+            MovieLoadOptions::default(),
+        )
+    }
+
+    /// Load an already decoded image into a clip.
+    ///
+    /// The image is placed in the clip as a bitmap, with the same events as
+    /// an image loaded by `load_movie_into_clip`. Its pixels must already
+    /// have premultiplied alpha, as with every other decoded `Bitmap`.
+    ///
+    /// Returns the loader's async process, which you will need to spawn.
+    pub fn load_bitmap_into_clip(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        target_clip: DisplayObject<'gc>,
+        bitmap: Bitmap,
+        target_broadcaster: Option<Object<'gc>>,
+    ) -> OwnedFuture<(), Error> {
+        if let Err(e) = check_load_target(target_clip, None) {
+            return Box::pin(async move { Err(e) });
+        }
+
+        let loader = Loader::Movie {
+            self_handle: None,
+            url: None,
+            target_clip,
+            target_broadcaster,
+            loader_status: LoaderStatus::Pending,
+            bytes_loaded: 0,
+            bytes_total: 0,
+            start_frame: None,
+            on_init: None,
+        };
+        let handle = self.add_loader(loader);
+
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.introduce_loader_handle(handle);
+
+        loader.movie_loader(
+            player,
+            MovieSource::Bitmap(bitmap),
+            String::new(),
+            false,
+            MovieLoadOptions::default(),
+        )
+    }
+
+    /// Load a movie or image held in memory into a clip.
+    ///
+    /// The bytes are read from their start and handled as if they had been
+    /// fetched, so images are placed in the clip as a bitmap and a movie
+    /// loaded into the root clip replaces the root movie. A loaded movie
+    /// takes the URL of the movie that loaded it.
+    ///
+    /// Returns the loader's async process, which you will need to spawn.
+    pub fn load_bytes_into_clip(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        target_clip: DisplayObject<'gc>,
+        bytes: Vec<u8>,
+        mut load_options: MovieLoadOptions<'gc>,
+    ) -> OwnedFuture<(), Error> {
+        if let Err(e) = check_load_target(target_clip, load_options.requesting_clip) {
+            return Box::pin(async move { Err(e) });
+        }
+
+        let url = load_options.loader_url.clone().unwrap_or_default();
+        let response = Response {
+            url: url.clone(),
+            status: 200,
+            headers: vec![],
+            body: bytes,
+        };
+        let loader = Loader::Movie {
+            self_handle: None,
+            url: load_options.loader_url.clone(),
+            target_clip,
+            target_broadcaster: load_options.target_broadcaster,
+            loader_status: LoaderStatus::Pending,
+            bytes_loaded: 0,
+            bytes_total: 0,
+            start_frame: load_options.start_frame.take(),
+            on_init: load_options.on_init.take(),
+        };
+        let handle = self.add_loader(loader);
+
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.introduce_loader_handle(handle);
+
+        loader.movie_loader(
+            player,
+            MovieSource::Fetch(Box::pin(async move { Ok(response) })),
+            url,
+            false,
+            load_options,
+        )
+    }
+
+    /// Indicates that a movie clip has initialized (ran its first frame).
+    ///
+    /// Interested loaders will be invoked from here.
+    pub fn movie_clip_on_load(
+        &mut self,
+        loaded_clip: DisplayObject<'gc>,
+        clip_object: Option<Object<'gc>>,
+        queue: &mut ActionQueue<'gc>,
+    ) {
+        let mut invalidated_loaders = vec![];
+
+        // Flash fires `onLoadInit` in the order the loads were started.
+        for handle in self.start_order.iter() {
+            if let Some(loader) = self.loaders.get_mut(*handle) {
+                if loader.movie_clip_loaded(loaded_clip, clip_object, queue) {
+                    invalidated_loaders.push(*handle);
+                }
+            }
+        }
+
+        // These are the generational handles seen above, so a loader that
+        // has since taken over one of their slots is never removed here.
+        for handle in invalidated_loaders {
+            // The clip's first frame has been constructed, but its scripts
+            // have not run yet.
+            if let Some(Loader::Movie {
+                loader_status: LoaderStatus::Succeeded,
+                on_init: Some(on_init),
+                ..
+            }) = self.remove_loader(handle)
+            {
+                self.pending_inits.push(on_init);
+            }
+        }
+    }
+
+    /// Run the init callbacks of movie loads whose first frame has run.
+    ///
+    /// This is called once queued actions have been run.
+    pub fn run_pending_inits(&mut self) {
+        for on_init in self.pending_inits.drain(..) {
+            on_init();
+        }
+    }
+
+    /// Kick off a batch of fetches that complete together.
+    ///
+    /// `on_complete` is called once with every response, in request order,
+    /// or with the errors that failed the batch. An empty batch completes
+    /// immediately.
+    ///
+    /// Returns the loader's handle, which may be used to query the combined
+    /// progress of the batch, and one async process per request, all of
+    /// which you will need to spawn.
+    pub fn load_batch(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        requests: Vec<(String, RequestOptions)>,
+        failure_policy: BatchFailurePolicy,
+        on_complete: BatchCallback,
+    ) -> (Handle, Vec<OwnedFuture<(), Error>>) {
+        let loader = Loader::Batch {
+            self_handle: None,
+            url: None,
+            state: BatchState::new(requests.len(), failure_policy),
+            on_complete: Some(on_complete),
+        };
+        let handle = self.add_loader(loader);
+        self.get_loader_mut(handle)
+            .unwrap()
+            .introduce_loader_handle(handle);
+
+        if requests.is_empty() {
+            if let Some(Loader::Batch {
+                on_complete: Some(on_complete),
+                ..
+            }) = self.remove_loader(handle)
+            {
+                on_complete(Ok(Vec::new()));
+            }
+
+            return (handle, Vec::new());
+        }
+
+        let processes = requests
+            .into_iter()
+            .enumerate()
+            .map(|(index, (url, options))| {
+                let fetch = self.fetch(player.clone(), &url, options);
+                Loader::batch_loader(handle, index, player.clone(), fetch)
+            })
+            .collect();
+
+        (handle, processes)
+    }
+
+    /// Kick off a form data load into an AVM1 object.
+    ///
+    /// Returns the loader's async process, which you will need to spawn.
+    pub fn load_form_into_object(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        target_object: Object<'gc>,
+        url: &str,
+        options: RequestOptions,
+    ) -> OwnedFuture<(), Error> {
+        let fetch = self.fetch_data(player.clone(), url, options);
+        let loader = Loader::Form {
+            self_handle: None,
+            url: Some(url.to_string()),
+            target_object,
+        };
+        let handle = self.add_loader(loader);
+
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.introduce_loader_handle(handle);
+
+        loader.form_loader(player, fetch)
+    }
+
+    /// Kick off a form data load into the AVM1 clip at a target path.
+    ///
+    /// The target is only resolved once the load completes, relative to
+    /// `base_clip`, so it may name a clip created in the meantime. A target
+    /// of `_levelN` creates that level if it doesn't exist yet.
+    ///
+    /// Returns the loader's async process, which you will need to spawn.
+    pub fn load_form_into_target(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        base_clip: DisplayObject<'gc>,
+        target: String,
+        url: &str,
+        options: RequestOptions,
+    ) -> OwnedFuture<(), Error> {
+        let fetch = self.fetch_data(player.clone(), url, options);
+        let loader = Loader::FormTarget {
+            self_handle: None,
+            url: Some(url.to_string()),
+            base_clip,
+            target,
+        };
+        let handle = self.add_loader(loader);
+
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.introduce_loader_handle(handle);
+
+        loader.form_loader(player, fetch)
+    }
+
+    /// Kick off a form data load into an AVM1 object.
+    ///
+    /// Returns the loader's async process, which you will need to spawn.
+    pub fn load_form_into_load_vars(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        target_object: Object<'gc>,
+        url: &str,
+        options: RequestOptions,
+    ) -> OwnedFuture<(), Error> {
+        let fetch = self.fetch_data(player.clone(), url, options);
+        let loader = Loader::LoadVars {
+            self_handle: None,
+            url: Some(url.to_string()),
+            target_object,
+        };
+        let handle = self.add_loader(loader);
+
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.introduce_loader_handle(handle);
+
+        loader.load_vars_loader(player, fetch)
+    }
+
+    /// Kick off an XML data load into an XML node.
+    ///
+    /// Returns the loader's async process, which you will need to spawn.
+    pub fn load_xml_into_node(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        target_node: XmlNode<'gc>,
+        active_clip: DisplayObject<'gc>,
+        url: &str,
+        options: RequestOptions,
+    ) -> OwnedFuture<(), Error> {
+        let fetch = self.fetch_data(player.clone(), url, options);
+        let loader = Loader::Xml {
+            self_handle: None,
+            url: Some(url.to_string()),
+            active_clip,
+            target_node,
+        };
+        let handle = self.add_loader(loader);
+
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.introduce_loader_handle(handle);
+
+        loader.xml_loader(player, fetch)
+    }
+
+    /// Kick off a JSON data load into an AVM2 object.
+    ///
+    /// The parsed document is stored in the object's `data` property, and
+    /// `complete` is dispatched on it. Invalid JSON dispatches `parseError`
+    /// instead, and a failed fetch dispatches `ioError`.
+    ///
+    /// Returns the loader's async process, which you will need to spawn.
+    pub fn load_json(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        target_object: Avm2Object<'gc>,
+        url: &str,
+        options: RequestOptions,
+    ) -> OwnedFuture<(), Error> {
+        let fetch = self.fetch_data(player.clone(), url, options);
+        let loader = Loader::Json {
+            self_handle: None,
+            url: Some(url.to_string()),
+            target_object,
+        };
+        let handle = self.add_loader(loader);
+
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.introduce_loader_handle(handle);
+
+        loader.json_loader(player, fetch)
+    }
+
+    /// Kick off an MP3 load into an AVM1 `Sound` object.
+    ///
+    /// Streaming sounds start playing as soon as they have loaded. Event
+    /// sounds only play once `start` is called, which is deferred until the
+    /// load finishes if it's called earlier.
+    ///
+    /// Returns the loader's async process, which you will need to spawn.
+    pub fn load_sound_avm1(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        target_object: SoundObject<'gc>,
+        url: &str,
+        options: RequestOptions,
+        is_streaming: bool,
+    ) -> OwnedFuture<(), Error> {
+        let fetch = self.fetch(player.clone(), url, options);
+        let loader = Loader::Sound {
+            self_handle: None,
+            url: Some(url.to_string()),
+            target_object,
+        };
+        let handle = self.add_loader(loader);
+
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.introduce_loader_handle(handle);
+
+        loader.sound_loader_avm1(player, fetch, is_streaming)
+    }
+}
+
+impl<'gc> Default for LoadManager<'gc> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The completion status of a `Loader` loading a movie.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Collect)]
+#[collect(require_static)]
+pub enum LoaderStatus {
+    /// The movie hasn't been loaded yet.
+    Pending,
+    /// The movie loaded successfully.
+    Succeeded,
+    /// An error occurred while loading the movie.
+    Failed,
+}
+
+/// How a batch load treats a failed fetch.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum BatchFailurePolicy {
+    /// Fail the whole batch as soon as any fetch fails.
+    FailFast,
+
+    /// Wait for every fetch to finish, then report all failures together.
+    CollectAll,
+}
+
+/// Called when a batch load finishes, with either every response in request
+/// order, or the errors that failed the batch.
+pub type BatchCallback = Box<dyn FnOnce(Result<Vec<Response>, Vec<Error>>)>;
+
+/// The results gathered so far by a batch load.
+pub struct BatchState {
+    /// The result of each fetch in the batch, once it has finished.
+    results: Vec<Option<Result<Response, Error>>>,
+
+    failure_policy: BatchFailurePolicy,
+
+    /// Whether the batch has already reported its outcome.
+    finished: bool,
+}
+
+impl BatchState {
+    fn new(len: usize, failure_policy: BatchFailurePolicy) -> Self {
+        Self {
+            results: (0..len).map(|_| None).collect(),
+            failure_policy,
+            finished: false,
+        }
+    }
+
+    /// The combined size of every successful fetch so far, as both the bytes
+    /// loaded and the bytes total.
+    fn progress(&self) -> (usize, usize) {
+        let bytes = self
+            .results
+            .iter()
+            .flatten()
+            .filter_map(|result| result.as_ref().ok())
+            .map(|response| response.body.len())
+            .sum();
+
+        (bytes, bytes)
+    }
+
+    /// Record the result of one fetch in the batch.
+    ///
+    /// Returns the outcome of the batch if this result finished it.
+    fn record(
+        &mut self,
+        index: usize,
+        result: Result<Response, Error>,
+    ) -> Option<Result<Vec<Response>, Vec<Error>>> {
+        if self.finished {
+            return None;
+        }
+
+        match result {
+            Err(e) if self.failure_policy == BatchFailurePolicy::FailFast => {
+                self.finished = true;
+                return Some(Err(vec![e]));
+            }
+            result => self.results[index] = Some(result),
+        }
+
+        if self.results.iter().any(Option::is_none) {
+            return None;
+        }
+
+        self.finished = true;
+        let (responses, errors): (Vec<_>, Vec<_>) = self
+            .results
+            .drain(..)
+            .flatten()
+            .partition(|result| result.is_ok());
+
+        if errors.is_empty() {
+            Some(Ok(responses.into_iter().filter_map(Result::ok).collect()))
+        } else {
+            Some(Err(errors.into_iter().filter_map(Result::err).collect()))
+        }
+    }
+}
+
+/// The kind of load a `Loader` is performing.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum LoaderKind {
+    RootMovie,
+    Movie,
+    Form,
+    LoadVars,
+    Xml,
+    Json,
+    Sound,
+    Batch,
+}
+
+/// A point-in-time description of an in-flight `Loader`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LoaderInfoSnapshot {
+    /// The handle of the loader.
+    pub handle: Handle,
+
+    /// What the loader is loading.
+    pub kind: LoaderKind,
+
+    /// The URL being loaded, or `None` if it hasn't been resolved yet.
+    pub url: Option<String>,
+
+    /// The path of the display object the load targets, if any.
+    pub target_path: Option<String>,
+
+    /// The completion status of the load.
+    ///
+    /// Only movie loads track their completion, so other loads are always
+    /// reported as pending.
+    pub status: LoaderStatus,
+
+    /// The number of bytes loaded, as of the last progress event.
+    pub bytes_loaded: usize,
+
+    /// The total number of bytes, as of the last progress event.
+    pub bytes_total: usize,
+}
+
+/// A struct that holds garbage-collected pointers for asynchronous code.
+#[derive(Collect)]
+#[collect(no_drop)]
+pub enum Loader<'gc> {
+    /// Loader that is loading the root movie of a player.
+    RootMovie {
+        /// The handle to refer to this loader instance.
+        #[collect(require_static)]
+        self_handle: Option<Handle>,
+
+        /// The URL being loaded, if known.
+        #[collect(require_static)]
+        url: Option<String>,
+    },
+
+    /// Loader that is loading a new movie into a MovieClip.
+    Movie {
+        /// The handle to refer to this loader instance.
+        #[collect(require_static)]
+        self_handle: Option<Handle>,
+
+        /// The URL being loaded, if known.
+        #[collect(require_static)]
+        url: Option<String>,
+
+        /// The target movie clip to load the movie into.
+        target_clip: DisplayObject<'gc>,
+
+        /// Event broadcaster (typically a `MovieClipLoader`) to fire events
+        /// into.
+        target_broadcaster: Option<Object<'gc>>,
+
+        /// Indicates the completion status of this loader.
+        ///
+        /// This flag exists to prevent a situation in which loading a movie
+        /// into a clip that has not yet fired its Load event causes the
+        /// loader to be prematurely removed. This flag is only set when either
+        /// the movie has been replaced (and thus Load events can be trusted)
+        /// or an error has occurred (in which case we don't care about the
+        /// loader anymore).
+        loader_status: LoaderStatus,
+
+        /// The number of compressed bytes loaded, as of the last progress
+        /// event.
+        bytes_loaded: usize,
+
+        /// The total number of compressed bytes, as of the last progress
+        /// event.
+        bytes_total: usize,
+
+        /// The frame the loaded movie starts playing from, if not the first.
+        #[collect(require_static)]
+        start_frame: Option<FrameLabelOrNumber>,
+
+        /// Embedder callback to run once the loaded movie has run its first
+        /// frame.
+        #[collect(require_static)]
+        on_init: Option<Box<dyn FnOnce()>>,
+    },
+
+    /// Loader that is loading form data into an AVM1 object scope.
+    Form {
+        /// The handle to refer to this loader instance.
+        #[collect(require_static)]
+        self_handle: Option<Handle>,
+
+        /// The URL being loaded, if known.
+        #[collect(require_static)]
+        url: Option<String>,
+
+        /// The target AVM1 object to load form data into.
+        target_object: Object<'gc>,
+    },
+
+    /// Loader that is loading form data into the AVM1 clip at a target path.
+    FormTarget {
+        /// The handle to refer to this loader instance.
+        #[collect(require_static)]
+        self_handle: Option<Handle>,
+
+        /// The URL being loaded, if known.
+        #[collect(require_static)]
+        url: Option<String>,
+
+        /// The clip the target path is relative to.
+        base_clip: DisplayObject<'gc>,
+
+        /// The path or `_levelN` of the clip to load form data into.
+        #[collect(require_static)]
+        target: String,
+    },
+
+    /// Loader that is loading form data into an AVM1 LoadVars object.
+    LoadVars {
+        /// The handle to refer to this loader instance.
+        #[collect(require_static)]
+        self_handle: Option<Handle>,
+
+        /// The URL being loaded, if known.
+        #[collect(require_static)]
+        url: Option<String>,
+
+        /// The target AVM1 object to load form data into.
+        target_object: Object<'gc>,
+    },
+
+    /// Loader that is loading XML data into an XML tree.
+    Xml {
+        /// The handle to refer to this loader instance.
+        #[collect(require_static)]
+        self_handle: Option<Handle>,
+
+        /// The URL being loaded, if known.
+        #[collect(require_static)]
+        url: Option<String>,
+
+        /// The active movie clip at the time of load invocation.
+        ///
+        /// This property is a technicality: Under normal circumstances, it's
+        /// not supposed to be a load factor, and only exists so that the
+        /// runtime can do *something* in really contrived scenarios where we
+        /// actually need an active clip.
+        active_clip: DisplayObject<'gc>,
+
+        /// The target node whose contents will be replaced with the parsed XML.
+        target_node: XmlNode<'gc>,
+    },
+
+    /// Loader that is loading JSON data into an AVM2 object.
+    Json {
+        /// The handle to refer to this loader instance.
         #[collect(require_static)]
         self_handle: Option<Handle>,
 
-        /// The target movie clip to load the movie into.
-        target_clip: DisplayObject<'gc>,
+        /// The URL being loaded, if known.
+        #[collect(require_static)]
+        url: Option<String>,
+
+        /// The target AVM2 object to store the parsed data in.
+        target_object: Avm2Object<'gc>,
+    },
+
+    /// Loader that is loading an MP3 into an AVM1 `Sound` object.
+    Sound {
+        /// The handle to refer to this loader instance.
+        #[collect(require_static)]
+        self_handle: Option<Handle>,
+
+        /// The URL being loaded, if known.
+        #[collect(require_static)]
+        url: Option<String>,
+
+        /// The sound object the loaded sound is attached to.
+        target_object: SoundObject<'gc>,
+    },
+
+    /// Loader that is fetching several URLs that complete together.
+    Batch {
+        /// The handle to refer to this loader instance.
+        #[collect(require_static)]
+        self_handle: Option<Handle>,
+
+        /// Always `None`, as a batch has no single URL.
+        #[collect(require_static)]
+        url: Option<String>,
+
+        /// The results gathered so far.
+        #[collect(require_static)]
+        state: BatchState,
+
+        /// Embedder callback to run once the batch finishes.
+        #[collect(require_static)]
+        on_complete: Option<BatchCallback>,
+    },
+}
+
+/// Work out why root movie data that couldn't be parsed can't be played.
+///
+/// A file with a SWF header naming a version newer than any Flash Player is
+/// assumed to be a genuine movie that needs that newer player.
+fn invalid_movie_reason(data: &[u8]) -> InvalidMovieReason {
+    let has_swf_header = data.len() >= 4 && matches!(&data[..3], b"FWS" | b"CWS" | b"ZWS");
+    if has_swf_header && data[3] > NEWEST_PLAYER_VERSION {
+        InvalidMovieReason::UnsupportedVersion(data[3])
+    } else {
+        InvalidMovieReason::NotSwf
+    }
+}
+
+/// Parse a `_levelN` target into its level number.
+fn parse_level(target: &str) -> Option<i32> {
+    target.strip_prefix("_level")?.parse().ok()
+}
+
+/// Find the object that a form load into a target path should fill in.
+///
+/// Returns `None` if the path doesn't lead to a clip, except for levels,
+/// which are created on demand.
+fn resolve_form_target<'gc>(
+    uc: &mut UpdateContext<'_, 'gc, '_>,
+    base_clip: DisplayObject<'gc>,
+    target: &str,
+) -> Result<Option<Object<'gc>>, Error> {
+    let version = base_clip.swf_version();
+    let globals = uc.avm1.global_object_cell();
+    let mut activation = Activation::from_nothing(
+        uc.reborrow(),
+        ActivationIdentifier::root("[Form Loader]"),
+        version,
+        globals,
+        base_clip,
+    );
+
+    let clip = match parse_level(target) {
+        Some(level_id) => Some(activation.resolve_level(level_id)),
+        None => {
+            let target = AvmString::new(activation.context.gc_context, target);
+            activation.resolve_target_display_object(base_clip, target.into(), true)?
+        }
+    };
+
+    Ok(clip.map(|clip| clip.object().coerce_to_object(&mut activation)))
+}
+
+/// How a failed movie load is reported to its broadcaster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct MovieLoadFailure {
+    /// The HTTP status to fire `onLoadComplete` with before `onLoadError`.
+    ///
+    /// Flash considers a movie that was downloaded but couldn't be used to
+    /// have completed loading, so this is only `None` if the server was never
+    /// reached or responded with an error.
+    completed_status: Option<u16>,
+
+    /// The error code passed to `onLoadError`.
+    error_code: &'static str,
+
+    /// The HTTP status passed to `onLoadError`.
+    http_status: u16,
+}
+
+impl MovieLoadFailure {
+    /// Describe a movie load that failed with `error`, after receiving a
+    /// successful response with `response_status`, if it got that far.
+    fn new(error: &Error, response_status: Option<u16>) -> Self {
+        match (error, response_status) {
+            (Error::HttpNotOk { status, .. }, _) => Self {
+                completed_status: None,
+                error_code: "URLNotFound",
+                http_status: *status,
+            },
+            (_, Some(status)) => Self {
+                completed_status: Some(status),
+                error_code: "LoadNeverCompleted",
+                http_status: status,
+            },
+            (_, None) => Self {
+                completed_status: None,
+                error_code: "LoadNeverCompleted",
+                http_status: 0,
+            },
+        }
+    }
+}
+
+/// Report a failed movie load to its broadcaster, and mark it as failed.
+fn movie_load_failed<'gc>(
+    uc: &mut UpdateContext<'_, 'gc, '_>,
+    handle: Handle,
+    clip: DisplayObject<'gc>,
+    broadcaster: Option<Object<'gc>>,
+    failure: MovieLoadFailure,
+) {
+    if let Some(broadcaster) = broadcaster {
+        if let Some(status) = failure.completed_status {
+            Avm1::run_stack_frame_for_method(
+                clip,
+                broadcaster,
+                NEWEST_PLAYER_VERSION,
+                uc,
+                "broadcastMessage",
+                &[
+                    "onLoadComplete".into(),
+                    Value::Object(broadcaster),
+                    status.into(),
+                ],
+            );
+        }
+
+        Avm1::run_stack_frame_for_method(
+            clip,
+            broadcaster,
+            NEWEST_PLAYER_VERSION,
+            uc,
+            "broadcastMessage",
+            &[
+                "onLoadError".into(),
+                Value::Object(broadcaster),
+                failure.error_code.into(),
+                failure.http_status.into(),
+            ],
+        );
+    }
+
+    uc.load_manager
+        .set_loader_status(handle, LoaderStatus::Failed);
+}
+
+impl<'gc> Loader<'gc> {
+    /// Describe this loader for debugging purposes.
+    fn snapshot(&self, handle: Handle) -> LoaderInfoSnapshot {
+        let (kind, url, target_path, status, bytes_loaded, bytes_total) = match self {
+            Loader::RootMovie { url, .. } => (
+                LoaderKind::RootMovie,
+                url,
+                None,
+                LoaderStatus::Pending,
+                0,
+                0,
+            ),
+            Loader::Movie {
+                url,
+                target_clip,
+                loader_status,
+                bytes_loaded,
+                bytes_total,
+                ..
+            } => (
+                LoaderKind::Movie,
+                url,
+                Some(target_clip.path()),
+                *loader_status,
+                *bytes_loaded,
+                *bytes_total,
+            ),
+            Loader::Form { url, .. } => (LoaderKind::Form, url, None, LoaderStatus::Pending, 0, 0),
+            Loader::FormTarget { url, target, .. } => (
+                LoaderKind::Form,
+                url,
+                Some(target.clone()),
+                LoaderStatus::Pending,
+                0,
+                0,
+            ),
+            Loader::LoadVars { url, .. } => {
+                (LoaderKind::LoadVars, url, None, LoaderStatus::Pending, 0, 0)
+            }
+            Loader::Xml {
+                url, active_clip, ..
+            } => (
+                LoaderKind::Xml,
+                url,
+                Some(active_clip.path()),
+                LoaderStatus::Pending,
+                0,
+                0,
+            ),
+            Loader::Json { url, .. } => (LoaderKind::Json, url, None, LoaderStatus::Pending, 0, 0),
+            Loader::Sound { url, .. } => {
+                (LoaderKind::Sound, url, None, LoaderStatus::Pending, 0, 0)
+            }
+            Loader::Batch { url, state, .. } => {
+                let (bytes_loaded, bytes_total) = state.progress();
+                (
+                    LoaderKind::Batch,
+                    url,
+                    None,
+                    LoaderStatus::Pending,
+                    bytes_loaded,
+                    bytes_total,
+                )
+            }
+        };
+
+        LoaderInfoSnapshot {
+            handle,
+            kind,
+            url: url.clone(),
+            target_path,
+            status,
+            bytes_loaded,
+            bytes_total,
+        }
+    }
+
+    /// Set the loader handle for this loader.
+    ///
+    /// An active loader handle is required before asynchronous loader code can
+    /// run.
+    pub fn introduce_loader_handle(&mut self, handle: Handle) {
+        match self {
+            Loader::RootMovie { self_handle, .. } => *self_handle = Some(handle),
+            Loader::Movie { self_handle, .. } => *self_handle = Some(handle),
+            Loader::Form { self_handle, .. } => *self_handle = Some(handle),
+            Loader::FormTarget { self_handle, .. } => *self_handle = Some(handle),
+            Loader::LoadVars { self_handle, .. } => *self_handle = Some(handle),
+            Loader::Xml { self_handle, .. } => *self_handle = Some(handle),
+            Loader::Json { self_handle, .. } => *self_handle = Some(handle),
+            Loader::Sound { self_handle, .. } => *self_handle = Some(handle),
+            Loader::Batch { self_handle, .. } => *self_handle = Some(handle),
+        }
+    }
+
+    /// Construct a future for one fetch of a batch load.
+    fn batch_loader(
+        handle: Handle,
+        index: usize,
+        player: Weak<Mutex<Player>>,
+        fetch: OwnedFuture<Response, Error>,
+    ) -> OwnedFuture<(), Error> {
+        Box::pin(async move {
+            let result = fetch.await;
+            let player = player.upgrade().ok_or(Error::Cancelled)?;
+
+            let finished = player.lock().expect("Could not lock player!!").update(
+                |uc| -> Result<_, Error> {
+                    let outcome = match uc.load_manager.get_loader_mut(handle) {
+                        Some(Loader::Batch { state, .. }) => state.record(index, result),
+                        None => return Err(Error::Cancelled),
+                        _ => unreachable!(),
+                    };
+
+                    Ok(
+                        outcome.and_then(|outcome| match uc.load_manager.remove_loader(handle) {
+                            Some(Loader::Batch {
+                                on_complete: Some(on_complete),
+                                ..
+                            }) => Some((on_complete, outcome)),
+                            _ => None,
+                        }),
+                    )
+                },
+            )?;
+
+            // Run the callback outside of the player lock, in case it needs
+            // the player itself.
+            if let Some((on_complete, outcome)) = finished {
+                on_complete(outcome);
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Construct a future for the root movie loader.
+    pub fn root_movie_loader(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        fetch: OwnedFuture<Response, Error>,
+        mut url: String,
+        parameters: Vec<(String, String)>,
+        on_metadata: Box<dyn FnOnce(&swf::HeaderExt)>,
+    ) -> OwnedFuture<(), Error> {
+        let handle = match self {
+            Loader::RootMovie { self_handle, .. } => {
+                self_handle.expect("Loader not self-introduced")
+            }
+            _ => return Box::pin(async { Err(Error::NotMovieLoader) }),
+        };
+
+        let player = player
+            .upgrade()
+            .expect("Could not upgrade weak reference to player");
+
+        Box::pin(async move {
+            player
+                .lock()
+                .expect("Could not lock player!!")
+                .update(|uc| -> Result<(), Error> {
+                    url = uc.navigator.resolve_relative_url(&url).into_owned();
+
+                    Ok(())
+                })?;
+
+            let data = (fetch.await).and_then(reject_http_error);
+
+            // An aborted root movie load must not replace whatever the
+            // player has moved on to.
+            let is_cancelled = player
+                .lock()
+                .expect("Could not lock player!!")
+                .update(|uc| uc.load_manager.get_loader(handle).is_none());
+            if is_cancelled {
+                return Err(Error::Cancelled);
+            }
+
+            let body = match data {
+                Ok(response) => response.body,
+                Err(e) => {
+                    log::warn!(
+                        "Could not download root movie {}: {}",
+                        redact_url_credentials(&url),
+                        e
+                    );
+                    player
+                        .lock()
+                        .unwrap()
+                        .ui()
+                        .display_root_movie_download_failed_message();
+                    return Err(Error::FetchError(url));
+                }
+            };
+
+            let max_swf_version = player
+                .lock()
+                .expect("Could not lock player!!")
+                .update(|uc| uc.load_manager.max_swf_version);
+            match SwfMovie::from_data(&body, Some(url.clone()), None) {
+                Ok(mut movie) => {
+                    if let Err(e) = check_swf_version(&movie, max_swf_version, &url) {
+                        log::warn!(
+                            "Could not load root movie {}: {}",
+                            redact_url_credentials(&url),
+                            e
+                        );
+                        player
+                            .lock()
+                            .unwrap()
+                            .ui()
+                            .display_root_movie_invalid_message(
+                                InvalidMovieReason::UnsupportedVersion(movie.version()),
+                            );
+                        return Err(e);
+                    }
+
+                    on_metadata(movie.header());
+                    movie.append_parameters(parameters);
+                    player.lock().unwrap().set_root_movie(Arc::new(movie));
+                    Ok(())
+                }
+                Err(e) => {
+                    log::warn!(
+                        "Root movie {} is not a valid SWF: {}",
+                        redact_url_credentials(&url),
+                        e
+                    );
+                    player
+                        .lock()
+                        .unwrap()
+                        .ui()
+                        .display_root_movie_invalid_message(invalid_movie_reason(&body));
+                    Err(e.into())
+                }
+            }
+        })
+    }
+
+    /// Construct a future for the given movie loader.
+    ///
+    /// The given future should be passed immediately to an executor; it will
+    /// take responsibility for running the loader to completion.
+    ///
+    /// If the loader is not a movie then the returned future will yield an
+    /// error immediately once spawned.
+    ///
+    /// The target broadcaster of `load_options` is taken from the loader
+    /// instead.
+    pub fn movie_loader(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        mut source: MovieSource,
+        mut url: String,
+        use_cache: bool,
+        load_options: MovieLoadOptions<'gc>,
+    ) -> OwnedFuture<(), Error> {
+        let handle = match self {
+            Loader::Movie { self_handle, .. } => self_handle.expect("Loader not self-introduced"),
+            _ => return Box::pin(async { Err(Error::NotMovieLoader) }),
+        };
+
+        let player = player
+            .upgrade()
+            .expect("Could not upgrade weak reference to player");
+
+        let MovieLoadOptions {
+            parameters,
+            loader_url,
+            force_content_type,
+            on_metadata,
+            ..
+        } = load_options;
+        let mut replacing_root_movie = false;
+
+        Box::pin(async move {
+            if use_cache {
+                let lookup = player
+                    .lock()
+                    .expect("Could not lock player!!")
+                    .update(|uc| uc.load_manager.cached_movie(Arc::downgrade(&player), &url));
+                // A lookup is only refused when the fetch would be refused
+                // too, so the load fails the same way without fetching.
+                match lookup.await {
+                    Ok(Some(movie)) => source = MovieSource::Parsed(movie),
+                    Ok(None) => {}
+                    Err(e) => source = MovieSource::Fetch(Box::pin(async move { Err(e) })),
+                }
+            }
+
+            player
+                .lock()
+                .expect("Could not lock player!!")
+                .update(|uc| -> Result<(), Error> {
+                    url = uc.navigator.resolve_relative_url(&url).into_owned();
+
+                    let (clip, broadcaster) = match uc.load_manager.get_loader(handle) {
+                        Some(Loader::Movie {
+                            target_clip,
+                            target_broadcaster,
+                            ..
+                        }) => (*target_clip, *target_broadcaster),
+                        None => return Err(Error::Cancelled),
+                        _ => unreachable!(),
+                    };
+
+                    replacing_root_movie = DisplayObject::ptr_eq(clip, uc.stage.root_clip());
+
+                    // The old content is removed and unloaded before the new
+                    // load reports anything, and stays gone if it fails.
+                    if let Some(container) = clip.as_container() {
+                        let old_children: Vec<_> = container.iter_render_list().collect();
+                        for child in old_children {
+                            dispatch_removed_event(child, uc);
+                        }
+                    }
+                    clip.as_movie_clip().unwrap().unload(uc);
+
+                    clip.as_movie_clip()
+                        .unwrap()
+                        .replace_with_movie(uc.gc_context, None);
+
+                    if let Some(broadcaster) = broadcaster {
+                        Avm1::run_stack_frame_for_method(
+                            clip,
+                            broadcaster,
+                            NEWEST_PLAYER_VERSION,
+                            uc,
+                            "broadcastMessage",
+                            &["onLoadStart".into(), Value::Object(broadcaster)],
+                        );
+                    }
+
+                    Ok(())
+                })?;
+
+            // The status of a successful response, even if its content turns
+            // out to be unusable.
+            let mut response_status = None;
+            // Loaded images come with an empty movie, which the image is
+            // placed into once it's loaded into the clip.
+            let data = match source {
+                MovieSource::Parsed(movie) => Ok((movie.compressed_len(), movie, None)),
+                MovieSource::Bitmap(bitmap) => {
+                    let bitmap = into_rgba(bitmap);
+                    let length = bitmap.width as usize * bitmap.height as usize * 4;
+                    let movie = SwfMovie::empty(NEWEST_PLAYER_VERSION);
+                    Ok((length, Arc::new(movie), Some(bitmap)))
+                }
+                // Error pages are reported as such rather than sniffed.
+                MovieSource::Fetch(fetch) => {
+                    let response = fetch.await.and_then(reject_http_error);
+                    response.and_then(|response| {
+                        response_status = Some(response.status);
+                        match parse_movie_response(
+                            &response,
+                            force_content_type,
+                            loader_url.clone(),
+                        ) {
+                            Err(Error::UnknownContentType(_)) if force_content_type.is_none() => {
+                                player
+                                    .lock()
+                                    .expect("Could not lock player!!")
+                                    .update(|uc| uc.load_manager.decode_custom_content(&response))
+                            }
+                            result => result,
+                        }
+                    })
+                }
+            };
+            let data = match data {
+                Ok((length, movie, None)) => {
+                    let (player_avm, max_swf_version) = player
+                        .lock()
+                        .expect("Could not lock player!!")
+                        .update(|uc| (uc.swf.avm_type(), uc.load_manager.max_swf_version));
+                    check_swf_version(&movie, max_swf_version, &url)
+                        .and_then(|()| {
+                            if replacing_root_movie {
+                                Ok(())
+                            } else {
+                                check_avm_type(&movie, player_avm, &url)
+                            }
+                        })
+                        .map(|()| (length, movie, None))
+                }
+                data => data,
+            };
+            if let Err(e) = &data {
+                log::warn!(
+                    "Could not load movie {}: {}",
+                    redact_url_credentials(&url),
+                    e
+                );
+            }
+            // Nothing may be touched on behalf of a load that was cancelled
+            // while it was being fetched.
+            let is_cancelled = player
+                .lock()
+                .expect("Could not lock player!!")
+                .update(|uc| uc.load_manager.get_loader(handle).is_none());
+            if is_cancelled {
+                return Err(Error::Cancelled);
+            }
+
+            match data {
+                Ok((length, movie, bitmap)) => {
+                    // Images have no SWF header to report.
+                    if let (Some(on_metadata), None) = (on_metadata, &bitmap) {
+                        on_metadata(movie.header());
+                    }
+
+                    // Images are only kept as the clip's content, so can't be
+                    // cached as movies.
+                    if use_cache && bitmap.is_none() {
+                        player
+                            .lock()
+                            .expect("Could not lock player!!")
+                            .update(|uc| {
+                                uc.load_manager
+                                    .movie_cache_mut()
+                                    .insert(url.clone(), movie.clone())
+                            });
+                    }
+
+                    let parameters = merge_parameters(&url, parameters);
+                    let movie = movie_for_load(movie, parameters, use_cache && bitmap.is_none());
+
+                    // Images loaded into the root clip replace its content, not
+                    // the root movie.
+                    if replacing_root_movie && bitmap.is_none() {
+                        player.lock().unwrap().set_root_movie(movie);
+                        return Ok(());
+                    }
+
+                    player
+                        .lock()
+                        .expect("Could not lock player!!")
+                        .update(|uc| {
+                            let (clip, broadcaster) = match uc.load_manager.get_loader(handle) {
+                                Some(Loader::Movie {
+                                    target_clip,
+                                    target_broadcaster,
+                                    ..
+                                }) => (*target_clip, *target_broadcaster),
+                                None => return Err(Error::Cancelled),
+                                _ => unreachable!(),
+                            };
+
+                            let mut activation = Avm2Activation::from_nothing(uc.reborrow());
+                            let parent_domain = activation.avm2().global_domain();
+                            let domain = Avm2Domain::movie_domain(&mut activation, parent_domain);
+                            uc.library
+                                .library_for_movie_mut(movie.clone())
+                                .set_avm2_domain(domain);
+
+                            if let Some(Loader::Movie {
+                                bytes_loaded,
+                                bytes_total,
+                                ..
+                            }) = uc.load_manager.get_loader_mut(handle)
+                            {
+                                *bytes_loaded = length;
+                                *bytes_total = length;
+                            }
+
+                            if let Some(broadcaster) = broadcaster {
+                                Avm1::run_stack_frame_for_method(
+                                    clip,
+                                    broadcaster,
+                                    NEWEST_PLAYER_VERSION,
+                                    uc,
+                                    "broadcastMessage",
+                                    &[
+                                        "onLoadProgress".into(),
+                                        Value::Object(broadcaster),
+                                        length.into(),
+                                        length.into(),
+                                    ],
+                                );
+                            }
+
+                            let mut mc = clip
+                                .as_movie_clip()
+                                .expect("Attempted to load movie into not movie clip");
+
+                            mc.replace_with_movie(uc.gc_context, Some(movie.clone()));
+                            mc.post_instantiation(uc, clip, None, Instantiator::Movie, false);
+
+                            let mut morph_shapes = fnv::FnvHashMap::default();
+                            mc.preload(uc, &mut morph_shapes);
+
+                            // Finalize morph shapes.
+                            for (id, static_data) in morph_shapes {
+                                let morph_shape = MorphShape::new(uc.gc_context, static_data);
+                                uc.library
+                                    .library_for_movie_mut(movie.clone())
+                                    .register_character(
+                                        id,
+                                        crate::character::Character::MorphShape(morph_shape),
+                                    );
+                            }
+
+                            if uc.load_manager.share_loaded_fonts {
+                                let fonts = uc
+                                    .library
+                                    .library_for_movie_mut(movie.clone())
+                                    .exported_fonts();
+                                for font in fonts {
+                                    uc.library.register_shared_font(font);
+                                }
+                            }
+
+                            if let Some(Bitmap {
+                                width,
+                                height,
+                                data: BitmapFormat::Rgba(rgba),
+                            }) = bitmap
+                            {
+                                match uc.renderer.register_bitmap_raw(width, height, rgba) {
+                                    Ok(bitmap_handle) => {
+                                        let bitmap: DisplayObject<'_> =
+                                            crate::display_object::Bitmap::new(
+                                                uc,
+                                                0,
+                                                bitmap_handle,
+                                                width as u16,
+                                                height as u16,
+                                            )
+                                            .into();
+                                        bitmap.post_instantiation(
+                                            uc,
+                                            bitmap,
+                                            None,
+                                            Instantiator::Movie,
+                                            false,
+                                        );
+                                        mc.replace_at_depth(uc, bitmap, 1);
+                                    }
+                                    Err(e) => log::error!("Could not register loaded image: {}", e),
+                                }
+
+                                // Images have no first frame to wait for.
+                                let on_init = match uc.load_manager.get_loader_mut(handle) {
+                                    Some(Loader::Movie { on_init, .. }) => on_init.take(),
+                                    _ => None,
+                                };
+                                if let Some(on_init) = on_init {
+                                    uc.load_manager.pending_inits.push(on_init);
+                                }
+                            }
+
+                            let start_frame = match uc.load_manager.get_loader_mut(handle) {
+                                Some(Loader::Movie { start_frame, .. }) => start_frame.take(),
+                                _ => None,
+                            };
+                            match start_frame {
+                                Some(FrameLabelOrNumber::Number(frame)) => {
+                                    mc.goto_frame(uc, frame, false)
+                                }
+                                Some(FrameLabelOrNumber::Label(label)) => {
+                                    match mc.frame_label_to_number(&label) {
+                                        Some(frame) => mc.goto_frame(uc, frame, false),
+                                        None => {
+                                            log::warn!(
+                                                "Could not load movie {}: no frame labelled {}",
+                                                redact_url_credentials(&url),
+                                                label
+                                            );
+                                            let error = Error::FrameLabelNotFound(label);
+                                            movie_load_failed(
+                                                uc,
+                                                handle,
+                                                clip,
+                                                broadcaster,
+                                                MovieLoadFailure::new(&error, response_status),
+                                            );
+                                            return Err(error);
+                                        }
+                                    }
+                                }
+                                None => {}
+                            }
+
+                            if let Some(broadcaster) = broadcaster {
+                                Avm1::run_stack_frame_for_method(
+                                    clip,
+                                    broadcaster,
+                                    NEWEST_PLAYER_VERSION,
+                                    uc,
+                                    "broadcastMessage",
+                                    &["onLoadComplete".into(), Value::Object(broadcaster)],
+                                );
+                            }
+
+                            uc.load_manager
+                                .set_loader_status(handle, LoaderStatus::Succeeded);
+
+                            Ok(())
+                        })
+                }
+                Err(e) => player.lock().expect("Could not lock player!!").update(
+                    |uc| -> Result<(), Error> {
+                        let (clip, broadcaster) = match uc.load_manager.get_loader(handle) {
+                            Some(Loader::Movie {
+                                target_clip,
+                                target_broadcaster,
+                                ..
+                            }) => (*target_clip, *target_broadcaster),
+                            None => return Err(Error::Cancelled),
+                            _ => unreachable!(),
+                        };
+
+                        movie_load_failed(
+                            uc,
+                            handle,
+                            clip,
+                            broadcaster,
+                            MovieLoadFailure::new(&e, response_status),
+                        );
+
+                        Ok(())
+                    },
+                ),
+            }
+        })
+    }
+
+    pub fn form_loader(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        fetch: OwnedFuture<Response, Error>,
+    ) -> OwnedFuture<(), Error> {
+        let handle = match self {
+            Loader::Form { self_handle, .. } | Loader::FormTarget { self_handle, .. } => {
+                self_handle.expect("Loader not self-introduced")
+            }
+            _ => return Box::pin(async { Err(Error::NotFormLoader) }),
+        };
+
+        let player = player
+            .upgrade()
+            .expect("Could not upgrade weak reference to player");
+
+        Box::pin(async move {
+            let data = fetch.await?.body;
+
+            // Fire the load handler.
+            player.lock().unwrap().update(|uc| {
+                let loader = uc.load_manager.get_loader(handle);
+                let that = match loader {
+                    Some(&Loader::Form { target_object, .. }) => target_object,
+                    Some(Loader::FormTarget {
+                        base_clip, target, ..
+                    }) => {
+                        let (base_clip, target) = (*base_clip, target.clone());
+                        match resolve_form_target(uc, base_clip, &target)? {
+                            Some(target_object) => target_object,
+                            None => {
+                                log::warn!("Could not load variables into missing {}", target);
+                                return Ok(());
+                            }
+                        }
+                    }
+                    None => return Err(Error::Cancelled),
+                    _ => return Err(Error::NotFormLoader),
+                };
+
+                let mut activation = Activation::from_stub(
+                    uc.reborrow(),
+                    ActivationIdentifier::root("[Form Loader]"),
+                );
+
+                for (k, v) in form_urlencoded::parse(&data) {
+                    that.set(
+                        &k,
+                        AvmString::new(activation.context.gc_context, v.into_owned()).into(),
+                        &mut activation,
+                    )?;
+                }
+
+                Ok(())
+            })
+        })
+    }
+
+    /// Creates a future for a LoadVars load call.
+    pub fn load_vars_loader(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        fetch: OwnedFuture<Response, Error>,
+    ) -> OwnedFuture<(), Error> {
+        let handle = match self {
+            Loader::LoadVars { self_handle, .. } => {
+                self_handle.expect("Loader not self-introduced")
+            }
+            _ => return Box::pin(async { Err(Error::NotLoadVarsLoader) }),
+        };
+
+        let player = player
+            .upgrade()
+            .expect("Could not upgrade weak reference to player");
+
+        Box::pin(async move {
+            let data = fetch.await;
+
+            // Fire the load handler.
+            player.lock().unwrap().update(|uc| {
+                let loader = uc.load_manager.get_loader(handle);
+                let that = match loader {
+                    Some(&Loader::LoadVars { target_object, .. }) => target_object,
+                    None => return Err(Error::Cancelled),
+                    _ => return Err(Error::NotLoadVarsLoader),
+                };
+
+                let mut activation = Activation::from_stub(
+                    uc.reborrow(),
+                    ActivationIdentifier::root("[Form Loader]"),
+                );
+
+                let (status, body) = http_status_and_body(&data);
+                let _ = that.call_method("onHTTPStatus", &[status.into()], &mut activation);
+
+                match body {
+                    Some(body) => {
+                        // The whole body arrives at once, so its length is
+                        // only known once it has all loaded.
+                        for &name in &["_bytesLoaded", "_bytesTotal"] {
+                            if that.has_property(&mut activation, name) {
+                                let _ = that.set(name, body.len().into(), &mut activation);
+                            } else {
+                                that.define_value(
+                                    activation.context.gc_context,
+                                    name,
+                                    body.len().into(),
+                                    Attribute::DONT_DELETE | Attribute::DONT_ENUM,
+                                );
+                            }
+                        }
+
+                        // Fire the onData method with the loaded string.
+                        let content_type = data
+                            .as_ref()
+                            .ok()
+                            .and_then(|response| response.header("Content-Type"));
+                        let string_data = AvmString::new(
+                            activation.context.gc_context,
+                            decode_text(body, content_type),
+                        );
+                        let _ = that.call_method("onData", &[string_data.into()], &mut activation);
+                    }
+                    None => {
+                        // TODO: Log "Error opening URL" trace similar to the Flash Player?
+                        // Fire the onData method with no data to indicate an unsuccessful load.
+                        let _ = that.call_method("onData", &[Value::Undefined], &mut activation);
+                    }
+                }
+
+                Ok(())
+            })
+        })
+    }
+
+    pub fn json_loader(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        fetch: OwnedFuture<Response, Error>,
+    ) -> OwnedFuture<(), Error> {
+        let (handle, url) = match self {
+            Loader::Json {
+                self_handle, url, ..
+            } => (
+                self_handle.expect("Loader not self-introduced"),
+                url.clone().unwrap_or_default(),
+            ),
+            _ => return Box::pin(async { Err(Error::NotJsonLoader) }),
+        };
+
+        let player = player
+            .upgrade()
+            .expect("Could not upgrade weak reference to player");
+
+        Box::pin(async move {
+            let data = fetch.await.and_then(reject_http_error);
+
+            player
+                .lock()
+                .expect("Could not lock player!!")
+                .update(|uc| {
+                    let mut target = match uc.load_manager.remove_loader(handle) {
+                        Some(Loader::Json { target_object, .. }) => target_object,
+                        None => return Err(Error::Cancelled),
+                        _ => return Err(Error::NotJsonLoader),
+                    };
+
+                    let json = data.map(|response| parse_json_response(&response));
+                    let event = match json {
+                        Ok(Ok(json)) => {
+                            let mut activation = Avm2Activation::from_nothing(uc.reborrow());
+                            let stored = json_to_avm2(&mut activation, &json).and_then(|value| {
+                                let name = Avm2QName::new(Avm2Namespace::public(), "data");
+                                target.set_property(target, &name, value, &mut activation)
+                            });
+                            if let Err(e) = stored {
+                                log::error!(
+                                    "Could not store JSON loaded from {}: {}",
+                                    redact_url_credentials(&url),
+                                    e
+                                );
+                            }
+
+                            "complete"
+                        }
+                        Ok(Err(e)) => {
+                            log::warn!(
+                                "Could not parse JSON loaded from {}: {}",
+                                redact_url_credentials(&url),
+                                e
+                            );
+                            "parseError"
+                        }
+                        Err(e) => {
+                            log::warn!(
+                                "Could not load JSON from {}: {}",
+                                redact_url_credentials(&url),
+                                e
+                            );
+                            "ioError"
+                        }
+                    };
+
+                    if let Err(e) = Avm2::dispatch_event(uc, Avm2Event::new(event), target) {
+                        log::error!("Encountered AVM2 error when dispatching event: {}", e);
+                    }
+
+                    Ok(())
+                })
+        })
+    }
+
+    /// Creates a future for an AVM1 `Sound.loadSound` call.
+    pub fn sound_loader_avm1(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        fetch: OwnedFuture<Response, Error>,
+        is_streaming: bool,
+    ) -> OwnedFuture<(), Error> {
+        let (handle, url) = match self {
+            Loader::Sound {
+                self_handle, url, ..
+            } => (
+                self_handle.expect("Loader not self-introduced"),
+                url.clone().unwrap_or_default(),
+            ),
+            _ => return Box::pin(async { Err(Error::NotSoundLoader) }),
+        };
+
+        let player = player
+            .upgrade()
+            .expect("Could not upgrade weak reference to player");
+
+        Box::pin(async move {
+            let data = fetch.await.and_then(reject_http_error);
+
+            player
+                .lock()
+                .expect("Could not lock player!!")
+                .update(|uc| {
+                    let sound_object = match uc.load_manager.remove_loader(handle) {
+                        Some(Loader::Sound { target_object, .. }) => target_object,
+                        None => return Err(Error::Cancelled),
+                        _ => return Err(Error::NotSoundLoader),
+                    };
+
+                    let sound = match data {
+                        Ok(response) => match uc.audio.register_mp3(&response.body) {
+                            Ok(sound) => Some(sound),
+                            Err(e) => {
+                                log::warn!(
+                                    "Could not decode sound loaded from {}: {}",
+                                    redact_url_credentials(&url),
+                                    e
+                                );
+                                None
+                            }
+                        },
+                        Err(e) => {
+                            log::warn!(
+                                "Could not load sound from {}: {}",
+                                redact_url_credentials(&url),
+                                e
+                            );
+                            None
+                        }
+                    };
+
+                    let queued_start = sound_object.finish_loading(uc.gc_context);
+                    if let Some(sound) = sound {
+                        let duration = uc.audio.get_sound_duration(sound);
+                        sound_object.set_sound(uc.gc_context, Some(sound));
+                        sound_object
+                            .set_duration(uc.gc_context, duration.map(|d| d.round() as u32));
+                        sound_object.set_position(uc.gc_context, 0);
+                    }
+
+                    let mut activation = Activation::from_stub(
+                        uc.reborrow(),
+                        ActivationIdentifier::root("[Sound Loader]"),
+                    );
+
+                    if sound.is_some() {
+                        let start_args = match queued_start {
+                            Some((start_offset, loops)) => {
+                                Some(vec![start_offset.into(), loops.into()])
+                            }
+                            None if is_streaming => Some(vec![]),
+                            None => None,
+                        };
+                        if let Some(start_args) = start_args {
+                            let _ = sound_object.call_method("start", &start_args, &mut activation);
+                        }
+                    }
+
+                    let _ = sound_object.call_method(
+                        "onLoad",
+                        &[sound.is_some().into()],
+                        &mut activation,
+                    );
+
+                    Ok(())
+                })
+        })
+    }
+
+    /// Event handler morally equivalent to `onLoad` on a movie clip.
+    ///
+    /// Returns `true` if the loader has completed and should be removed.
+    ///
+    /// Used to fire listener events on clips and terminate completed loaders.
+    pub fn movie_clip_loaded(
+        &mut self,
+        loaded_clip: DisplayObject<'gc>,
+        clip_object: Option<Object<'gc>>,
+        queue: &mut ActionQueue<'gc>,
+    ) -> bool {
+        let (clip, broadcaster, loader_status) = match self {
+            Loader::Movie {
+                target_clip,
+                target_broadcaster,
+                loader_status,
+                ..
+            } => (*target_clip, *target_broadcaster, *loader_status),
+            _ => return false,
+        };
+
+        if !DisplayObject::ptr_eq(loaded_clip, clip) {
+            return false;
+        }
+
+        match loader_status {
+            LoaderStatus::Pending => false,
+            LoaderStatus::Failed => true,
+            LoaderStatus::Succeeded => {
+                if let Some(broadcaster) = broadcaster {
+                    queue.queue_actions(
+                        clip,
+                        ActionType::Method {
+                            object: broadcaster,
+                            name: "broadcastMessage",
+                            args: vec![
+                                "onLoadInit".into(),
+                                clip_object.map(|co| co.into()).unwrap_or(Value::Undefined),
+                            ],
+                        },
+                        false,
+                    );
+                }
+                true
+            }
+        }
+    }
+
+    pub fn xml_loader(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        fetch: OwnedFuture<Response, Error>,
+    ) -> OwnedFuture<(), Error> {
+        let handle = match self {
+            Loader::Xml { self_handle, .. } => self_handle.expect("Loader not self-introduced"),
+            _ => return Box::pin(async { Err(Error::NotXmlLoader) }),
+        };
+
+        let player = player
+            .upgrade()
+            .expect("Could not upgrade weak reference to player");
+
+        Box::pin(async move {
+            let data = fetch.await;
+            if let Ok(response) = data {
+                let status = response.status;
+                let xmlstring = String::from_utf8(response.body)?;
+
+                player.lock().expect("Could not lock player!!").update(
+                    |uc| -> Result<(), Error> {
+                        let (mut node, active_clip) = match uc.load_manager.get_loader(handle) {
+                            Some(Loader::Xml {
+                                target_node,
+                                active_clip,
+                                ..
+                            }) => (*target_node, *active_clip),
+                            None => return Err(Error::Cancelled),
+                            _ => unreachable!(),
+                        };
+
+                        let object =
+                            node.script_object(uc.gc_context, Some(uc.avm1.prototypes().xml_node));
+                        Avm1::run_stack_frame_for_method(
+                            active_clip,
+                            object,
+                            NEWEST_PLAYER_VERSION,
+                            uc,
+                            "onHTTPStatus",
+                            &[status.into()],
+                        );
+
+                        Avm1::run_stack_frame_for_method(
+                            active_clip,
+                            object,
+                            NEWEST_PLAYER_VERSION,
+                            uc,
+                            "onData",
+                            &[AvmString::new(uc.gc_context, xmlstring).into()],
+                        );
+
+                        Ok(())
+                    },
+                )?;
+            } else {
+                player.lock().expect("Could not lock player!!").update(
+                    |uc| -> Result<(), Error> {
+                        let (mut node, active_clip) = match uc.load_manager.get_loader(handle) {
+                            Some(Loader::Xml {
+                                target_node,
+                                active_clip,
+                                ..
+                            }) => (*target_node, *active_clip),
+                            None => return Err(Error::Cancelled),
+                            _ => unreachable!(),
+                        };
+
+                        let object =
+                            node.script_object(uc.gc_context, Some(uc.avm1.prototypes().xml_node));
+
+                        Avm1::run_stack_frame_for_method(
+                            active_clip,
+                            object,
+                            NEWEST_PLAYER_VERSION,
+                            uc,
+                            "onHTTPStatus",
+                            &[404.into()],
+                        );
+
+                        Avm1::run_stack_frame_for_method(
+                            active_clip,
+                            object,
+                            NEWEST_PLAYER_VERSION,
+                            uc,
+                            "onData",
+                            &[],
+                        );
+
+                        Ok(())
+                    },
+                )?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display_object::MovieClip;
+    use crate::tag_utils::SwfSlice;
+
+    #[test]
+    fn retry_delay_doubles() {
+        let policy = RetryPolicy {
+            max_attempts: 4,
+            base_delay: Duration::from_millis(100),
+        };
+
+        assert_eq!(policy.delay_before_retry(1), Duration::from_millis(100));
+        assert_eq!(policy.delay_before_retry(2), Duration::from_millis(200));
+        assert_eq!(policy.delay_before_retry(3), Duration::from_millis(400));
+    }
+
+    #[test]
+    fn movie_cache_evicts_least_recently_used() {
+        let mut cache = MovieCache::new(2);
+        let first = Arc::new(SwfMovie::empty(10));
+        let second = Arc::new(SwfMovie::empty(10));
+        let third = Arc::new(SwfMovie::empty(10));
+
+        cache.insert("first.swf".to_string(), first.clone());
+        cache.insert("second.swf".to_string(), second);
+        assert!(Arc::ptr_eq(&cache.get("first.swf").unwrap(), &first));
+
+        cache.insert("third.swf".to_string(), third);
+        assert!(cache.get("second.swf").is_none());
+        assert!(cache.get("first.swf").is_some());
+        assert!(cache.get("third.swf").is_some());
+    }
+
+    #[test]
+    fn avm1_errors_keep_their_kind() {
+        let error = Error::from(crate::avm1::error::Error::ExecutionTimeout);
+        match error {
+            Error::Avm1Error(script_error) => {
+                assert_eq!(script_error.kind, ScriptErrorKind::ExecutionTimeout);
+                assert_eq!(script_error.code, None);
+            }
+            _ => panic!("expected an AVM1 error"),
+        }
+
+        let error = Error::from(crate::avm1::error::Error::FunctionRecursionLimit(256));
+        match error {
+            Error::Avm1Error(script_error) => {
+                assert_eq!(script_error.kind, ScriptErrorKind::RecursionLimit);
+                assert!(script_error.message.contains("256"));
+            }
+            _ => panic!("expected an AVM1 error"),
+        }
+    }
+
+    #[test]
+    fn spoofed_headers_respect_request_headers() {
+        let spoofed_headers = vec![
+            SpoofedHeader {
+                name: "x-flash-version".to_string(),
+                value: "32,0,0,465".to_string(),
+                force: false,
+            },
+            SpoofedHeader {
+                name: "Referer".to_string(),
+                value: "http://example.com/game.swf".to_string(),
+                force: false,
+            },
+            SpoofedHeader {
+                name: "User-Agent".to_string(),
+                value: "Shockwave Flash".to_string(),
+                force: true,
+            },
+        ];
+
+        let mut options = RequestOptions::get();
+        options.set_header("referer", "http://example.com/index.html");
+        options.set_header("User-Agent", "Ruffle");
+        apply_spoofed_headers(&mut options, &spoofed_headers);
+
+        assert_eq!(options.header("X-Flash-Version"), Some("32,0,0,465"));
+        assert_eq!(
+            options.header("Referer"),
+            Some("http://example.com/index.html")
+        );
+        assert_eq!(options.header("User-Agent"), Some("Shockwave Flash"));
+        assert_eq!(options.headers().len(), 3);
+    }
+
+    fn encoded_response(encoding: &str, body: Vec<u8>) -> Response {
+        Response {
+            url: "http://example.com/data.xml".to_string(),
+            status: 200,
+            headers: vec![("Content-Encoding".to_string(), encoding.to_string())],
+            body,
+        }
+    }
+
+    #[test]
+    fn decode_gzip_and_deflate_responses() {
+        use flate2::write::{GzEncoder, ZlibEncoder};
+        use flate2::Compression;
+        use std::io::Write;
+
+        let text = b"<data><value>Hello</value></data>";
+
+        let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+        gzip.write_all(text).unwrap();
+        let response = encoded_response("gzip", gzip.finish().unwrap());
+        let response = decode_content_encoding(response).unwrap();
+        assert_eq!(response.body, text);
+        assert_eq!(response.header("Content-Encoding"), None);
+
+        let mut zlib = ZlibEncoder::new(Vec::new(), Compression::default());
+        zlib.write_all(text).unwrap();
+        let response = encoded_response("deflate", zlib.finish().unwrap());
+        assert_eq!(decode_content_encoding(response).unwrap().body, text);
+
+        let response = encoded_response("identity", text.to_vec());
+        assert_eq!(decode_content_encoding(response).unwrap().body, text);
+    }
+
+    #[test]
+    fn decode_mislabeled_response_fails() {
+        let response = encoded_response("gzip", b"not actually gzip".to_vec());
+        assert!(matches!(
+            decode_content_encoding(response),
+            Err(Error::ContentEncodingError(_))
+        ));
+
+        let response = encoded_response("br", vec![0, 1, 2, 3]);
+        assert!(matches!(
+            decode_content_encoding(response),
+            Err(Error::ContentEncodingError(_))
+        ));
+    }
+
+    #[test]
+    fn active_loaders_lists_in_flight_loads() {
+        gc_arena::rootless_arena(|mc| {
+            let mut manager = LoadManager::new();
+            let root = manager.add_loader(Loader::RootMovie {
+                self_handle: None,
+                url: Some("http://example.com/root.swf".to_string()),
+            });
+            let form = manager.add_loader(Loader::Form {
+                self_handle: None,
+                url: None,
+                target_object: crate::avm1::ScriptObject::object(mc, None).into(),
+            });
+
+            let mut loaders = manager.active_loaders();
+            loaders.sort_by_key(|loader| loader.handle != root);
+            assert_eq!(loaders.len(), 2);
+
+            assert_eq!(loaders[0].handle, root);
+            assert_eq!(loaders[0].kind, LoaderKind::RootMovie);
+            assert_eq!(
+                loaders[0].url.as_deref(),
+                Some("http://example.com/root.swf")
+            );
+
+            assert_eq!(loaders[1].handle, form);
+            assert_eq!(loaders[1].kind, LoaderKind::Form);
+            assert_eq!(loaders[1].url, None);
+            assert_eq!(loaders[1].status, LoaderStatus::Pending);
+        })
+    }
+
+    fn batch_response(body: &[u8]) -> Result<Response, Error> {
+        Ok(Response {
+            url: "http://example.com/asset".to_string(),
+            status: 200,
+            headers: vec![],
+            body: body.to_vec(),
+        })
+    }
+
+    #[test]
+    fn batch_completes_once_with_summed_bytes() {
+        let mut batch = BatchState::new(3, BatchFailurePolicy::FailFast);
+
+        assert!(batch.record(1, batch_response(b"second")).is_none());
+        assert!(batch.record(0, batch_response(b"first")).is_none());
+        assert_eq!(batch.progress(), (11, 11));
+
+        let responses = batch.record(2, batch_response(b"third")).unwrap().unwrap();
+        let bodies: Vec<&[u8]> = responses.iter().map(|r| &r.body[..]).collect();
+        assert_eq!(bodies, vec![&b"first"[..], b"second", b"third"]);
+    }
+
+    #[test]
+    fn batch_failure_policies() {
+        let mut batch = BatchState::new(3, BatchFailurePolicy::FailFast);
+        assert!(batch.record(0, batch_response(b"ok")).is_none());
+        let errors = batch
+            .record(1, Err(Error::FetchError("first".to_string())))
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(batch
+            .record(2, Err(Error::FetchError("second".to_string())))
+            .is_none());
+
+        let mut batch = BatchState::new(3, BatchFailurePolicy::CollectAll);
+        assert!(batch
+            .record(0, Err(Error::FetchError("first".to_string())))
+            .is_none());
+        assert!(batch.record(1, batch_response(b"ok")).is_none());
+        let errors = batch
+            .record(2, Err(Error::FetchError("second".to_string())))
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(errors.len(), 2);
+    }
+
+    #[test]
+    fn http_error_pages_are_not_sniffed() {
+        let not_found = Response {
+            url: "https://example.com/missing.swf".to_string(),
+            status: 404,
+            headers: vec![],
+            body: b"<html>Not Found</html>".to_vec(),
+        };
+        match reject_http_error(not_found) {
+            Err(Error::HttpNotOk { status, body }) => {
+                assert_eq!(status, 404);
+                assert_eq!(body, b"<html>Not Found</html>");
+            }
+            _ => panic!("404 response should be an error"),
+        }
+
+        // A 200 error page is left for content sniffing to reject.
+        let soft_error = Response {
+            url: "https://example.com/missing.swf".to_string(),
+            status: 200,
+            headers: vec![],
+            body: b"<html>Not Found</html>".to_vec(),
+        };
+        let response = reject_http_error(soft_error).unwrap();
+        assert_eq!(ContentType::sniff(&response.body), ContentType::Unknown);
+
+        // Nothing is cached, so a 304 has no content to load, unlike a 204.
+        let bodiless = |status| Response {
+            url: "https://example.com/data.json".to_string(),
+            status,
+            headers: vec![],
+            body: vec![],
+        };
+        let not_modified = reject_http_error(bodiless(304));
+        assert!(matches!(
+            not_modified,
+            Err(Error::HttpNotOk { status: 304, .. })
+        ));
+        assert_eq!(http_status_and_body(&not_modified), (304, None));
+
+        // A JSON load of a 204 completes with null data.
+        let no_content = reject_http_error(bodiless(204)).unwrap();
+        assert_eq!(parse_json_response(&no_content).unwrap(), JsonValue::Null);
+    }
+
+    #[test]
+    fn forced_content_type_skips_sniffing() {
+        let response = |body: Vec<u8>| Response {
+            url: "http://example.com/asset.bin".to_string(),
+            status: 200,
+            headers: vec![],
+            body,
+        };
+
+        let mut swf = Vec::new();
+        swf::write_swf(&swf::Header::default_with_swf_version(8), &[], &mut swf).unwrap();
+        let (length, _, _) = parse_movie_response(&response(swf.clone()), None, None).unwrap();
+        assert_eq!(length, swf.len());
+        assert!(matches!(
+            parse_movie_response(&response(swf), Some(ContentType::Unknown), None),
+            Err(Error::UnknownContentType(_))
+        ));
+
+        // An asset without a SWF signature is parsed when forced, and fails
+        // cleanly instead of being reported as an unknown type.
+        let headerless = response(vec![0x0A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        assert!(matches!(
+            parse_movie_response(&headerless, None, None),
+            Err(Error::UnknownContentType(_))
+        ));
+        assert!(matches!(
+            parse_movie_response(&headerless, Some(ContentType::Swf), None),
+            Err(Error::InvalidSwf(_))
+        ));
+    }
+
+    #[test]
+    fn verified_movie_loads() {
+        let mut manager: LoadManager<'static> = LoadManager::new();
+        let unsigned = RequestOptions::get();
+        let mut signed = RequestOptions::get();
+        signed
+            .set_digest("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+            .unwrap();
+
+        assert!(manager.check_movie_verifiable("lib.swf", &unsigned).is_ok());
+
+        manager.set_require_verified_movies(true);
+        assert!(matches!(
+            manager.check_movie_verifiable("lib.swf", &unsigned),
+            Err(Error::UnverifiedMovie(url)) if url == "lib.swf"
+        ));
+        assert!(manager.check_movie_verifiable("lib.swf", &signed).is_ok());
+
+        // A tampered library fails its digest check once fetched.
+        assert!(signed.verify_digest(b"abc").is_ok());
+        assert!(matches!(
+            signed.verify_digest(b"abc, tampered"),
+            Err(Error::DigestMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn avm_version_mismatch() {
+        let mut avm2_swf = Vec::new();
+        swf::write_swf(
+            &swf::Header::default_with_swf_version(10),
+            &[swf::Tag::FileAttributes(
+                swf::FileAttributes::IS_ACTION_SCRIPT_3,
+            )],
+            &mut avm2_swf,
+        )
+        .unwrap();
+        let avm2_movie = SwfMovie::from_data(&avm2_swf, None, None).unwrap();
+        let avm1_movie = SwfMovie::empty(8);
+
+        assert!(matches!(
+            check_avm_type(&avm2_movie, AvmType::Avm1, "child.swf"),
+            Err(Error::AvmVersionMismatch(url)) if url == "child.swf"
+        ));
+        assert!(matches!(
+            check_avm_type(&avm1_movie, AvmType::Avm2, "child.swf"),
+            Err(Error::AvmVersionMismatch(_))
+        ));
+        assert!(check_avm_type(&avm1_movie, AvmType::Avm1, "child.swf").is_ok());
+        assert!(check_avm_type(&avm2_movie, AvmType::Avm2, "child.swf").is_ok());
+    }
+
+    #[test]
+    fn decode_text_honors_charset_and_bom() {
+        let (shift_jis, _, _) = encoding_rs::SHIFT_JIS.encode("name=日本語");
+        assert_eq!(
+            decode_text(&shift_jis, Some("text/plain; charset=Shift_JIS")),
+            "name=日本語"
+        );
+
+        let (windows_1252, _, _) = encoding_rs::WINDOWS_1252.encode("café");
+        assert_eq!(
+            decode_text(&windows_1252, Some("text/plain;charset=\"windows-1252\"")),
+            "café"
+        );
+
+        let utf8_bom = b"\xEF\xBB\xBFcaf\xC3\xA9";
+        assert_eq!(
+            decode_text(utf8_bom, Some("text/plain; charset=windows-1252")),
+            "café"
+        );
+
+        assert_eq!(
+            decode_text("café".as_bytes(), Some("text/plain; charset=bogus")),
+            "café"
+        );
+        assert_eq!(decode_text("café".as_bytes(), None), "café");
+    }
+
+    #[test]
+    fn http_status_reported_to_content() {
+        let server_error: Result<Response, Error> = Err(Error::HttpNotOk {
+            status: 500,
+            body: b"error=database".to_vec(),
+        });
+        assert_eq!(
+            http_status_and_body(&server_error),
+            (500, Some(&b"error=database"[..]))
+        );
+
+        let not_found: Result<Response, Error> = Err(Error::HttpNotOk {
+            status: 404,
+            body: vec![],
+        });
+        assert_eq!(http_status_and_body(&not_found), (404, None));
+
+        let refused: Result<Response, Error> =
+            Err(Error::FetchError("Connection refused".to_string()));
+        assert_eq!(http_status_and_body(&refused), (0, None));
+    }
+
+    #[test]
+    fn invalid_movies_complete_before_failing() {
+        let invalid = Response {
+            url: "http://example.com/broken.swf".to_string(),
+            status: 200,
+            headers: vec![],
+            body: b"FWS\x0a\x20\x00\x00\x00".to_vec(),
+        };
+        let error = match parse_movie_response(&invalid, None, None) {
+            Err(e) => e,
+            Ok(_) => panic!("Invalid SWF was parsed"),
+        };
+        assert_eq!(
+            MovieLoadFailure::new(&error, Some(invalid.status)),
+            MovieLoadFailure {
+                completed_status: Some(200),
+                error_code: "LoadNeverCompleted",
+                http_status: 200,
+            }
+        );
+
+        let not_found = Error::HttpNotOk {
+            status: 404,
+            body: vec![],
+        };
+        assert_eq!(
+            MovieLoadFailure::new(&not_found, None),
+            MovieLoadFailure {
+                completed_status: None,
+                error_code: "URLNotFound",
+                http_status: 404,
+            }
+        );
+
+        let refused = Error::FetchError("Connection refused".to_string());
+        assert_eq!(
+            MovieLoadFailure::new(&refused, None),
+            MovieLoadFailure {
+                completed_status: None,
+                error_code: "LoadNeverCompleted",
+                http_status: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn url_filter_rewrites_and_blocks() {
+        let mut manager: LoadManager<'static> = LoadManager::new();
+        let url = "http://example.com/movie.swf".to_string();
+        assert_eq!(manager.filter_url(url.clone()).unwrap(), url);
+
+        manager.set_url_filter(Some(Box::new(|url| match url {
+            "http://ads.example.com/banner.swf" => UrlDecision::Deny,
+            "http://example.com/old.swf" => {
+                UrlDecision::RewriteTo("http://mirror.example.com/old.swf".to_string())
+            }
+            _ => UrlDecision::Allow,
+        })));
+
+        assert!(matches!(
+            manager.filter_url("http://ads.example.com/banner.swf".to_string()),
+            Err(Error::Blocked(_))
+        ));
+        assert_eq!(
+            manager
+                .filter_url("http://example.com/old.swf".to_string())
+                .unwrap(),
+            "http://mirror.example.com/old.swf"
+        );
+        assert_eq!(manager.filter_url(url.clone()).unwrap(), url);
+    }
+
+    #[test]
+    fn host_rewrites_keep_the_query() {
+        let mut manager: LoadManager<'static> = LoadManager::new();
+        manager.set_url_filter(Some(Box::new(|url| {
+            let url = Url::parse(url).unwrap();
+            match url.host_str() {
+                // Moves the load to a mirror, without its query.
+                Some("example.com") => {
+                    UrlDecision::RewriteTo(format!("http://mirror.example.com{}", url.path()))
+                }
+                Some("old.example.com") => {
+                    UrlDecision::RewriteTo("http://example.com/game.swf?v=2".to_string())
+                }
+                _ => UrlDecision::Allow,
+            }
+        })));
+        let rewrite = |url: &str| manager.filter_url(url.to_string()).unwrap();
+
+        let movie_url = "http://example.com/game.swf?level=3";
+        assert_eq!(
+            rewrite(movie_url),
+            "http://mirror.example.com/game.swf?level=3"
+        );
+
+        // Relative loads are resolved against the movie's original URL, and
+        // are rewritten the same way.
+        let asset = Url::parse(movie_url)
+            .unwrap()
+            .join("assets/map.xml?level=3")
+            .unwrap();
+        assert_eq!(
+            rewrite(asset.as_str()),
+            "http://mirror.example.com/assets/map.xml?level=3"
+        );
+
+        // A rewrite with its own query is used as-is.
+        assert_eq!(
+            rewrite("http://old.example.com/game.swf?v=1"),
+            "http://example.com/game.swf?v=2"
+        );
+    }
+
+    #[test]
+    fn loaders_are_listed_in_start_order() {
+        gc_arena::rootless_arena(|mc| {
+            let mut manager = LoadManager::new();
+            let load_vars = |url: &str| Loader::LoadVars {
+                self_handle: None,
+                url: Some(url.to_string()),
+                target_object: crate::avm1::ScriptObject::object(mc, None).into(),
+            };
+
+            let first = manager.add_loader(load_vars("first.txt"));
+            let second = manager.add_loader(load_vars("second.txt"));
+            manager.cancel(first);
+            // The new loader takes over the first loader's arena slot.
+            let third = manager.add_loader(load_vars("third.txt"));
+            let fourth = manager.add_loader(load_vars("fourth.txt"));
+
+            let handles: Vec<Handle> = manager
+                .active_loaders()
+                .iter()
+                .map(|loader| loader.handle)
+                .collect();
+            assert_eq!(handles, vec![second, third, fourth]);
+        })
+    }
+
+    #[test]
+    fn finished_handles_stay_invalid() {
+        let mut manager: LoadManager<'static> = LoadManager::new();
+        let root_movie = || Loader::RootMovie {
+            self_handle: None,
+            url: Some("http://example.com/movie.swf".to_string()),
+        };
+
+        let finished = manager.add_loader(root_movie());
+        assert!(manager.is_handle_valid(finished));
+        manager.remove_loader(finished);
+        assert!(!manager.is_handle_valid(finished));
+
+        let reused = manager.add_loader(root_movie());
+        assert_eq!(reused.into_raw_parts().0, finished.into_raw_parts().0);
+        assert!(manager.is_handle_valid(reused));
+        assert!(!manager.is_handle_valid(finished));
+        assert!(manager.get_loader(finished).is_none());
+        assert!(manager.get_loader_mut(finished).is_none());
+        assert!(!manager.cancel(finished));
+        assert!(manager.is_handle_valid(reused));
+    }
+
+    #[test]
+    fn finished_movie_loads_only_remove_themselves() {
+        gc_arena::rootless_arena(|mc| {
+            let mut manager = LoadManager::new();
+            let mut queue = ActionQueue::new();
+            let movie = Arc::new(SwfMovie::empty(10));
+            let clip: DisplayObject<'_> = MovieClip::new(SwfSlice::empty(movie), mc).into();
+            let movie_loader = |loader_status| Loader::Movie {
+                self_handle: None,
+                url: Some("http://example.com/movie.swf".to_string()),
+                target_clip: clip,
+                target_broadcaster: None,
+                loader_status,
+                bytes_loaded: 0,
+                bytes_total: 0,
+                start_frame: None,
+                on_init: None,
+            };
+
+            let finished = manager.add_loader(movie_loader(LoaderStatus::Succeeded));
+            let pending = manager.add_loader(movie_loader(LoaderStatus::Pending));
+            manager.movie_clip_on_load(clip, None, &mut queue);
+            assert!(!manager.is_handle_valid(finished));
+            assert!(manager.is_handle_valid(pending));
+
+            // A load started in the same frame takes over the finished
+            // load's slot, and must survive anything done with the old handle.
+            let started = manager.add_loader(movie_loader(LoaderStatus::Pending));
+            assert_eq!(started.into_raw_parts().0, finished.into_raw_parts().0);
+            assert!(manager.remove_loader(finished).is_none());
+            manager.movie_clip_on_load(clip, None, &mut queue);
+
+            let handles: Vec<Handle> = manager
+                .active_loaders()
+                .iter()
+                .map(|loader| loader.handle)
+                .collect();
+            assert_eq!(handles, vec![pending, started]);
+        })
+    }
+
+    #[test]
+    fn identical_gets_share_one_fetch() {
+        let mut manager: LoadManager<'static> = LoadManager::new();
+        let url = "http://example.com/scores.txt";
+        let fetches = Rc::new(std::cell::Cell::new(0));
+        let load = |manager: &mut LoadManager<'static>, options: RequestOptions| {
+            let fetches = fetches.clone();
+            manager.share_fetch(FetchKey::new(url, &options), move |_| {
+                fetches.set(fetches.get() + 1);
+                Box::pin(async { batch_response(b"score=10") })
+            })
+        };
+
+        let gets: Vec<_> = (0..3)
+            .map(|_| load(&mut manager, RequestOptions::get()))
+            .collect();
+        assert_eq!(fetches.get(), 1);
+
+        let posts: Vec<_> = (0..2)
+            .map(|_| load(&mut manager, RequestOptions::post(None)))
+            .collect();
+        assert_eq!(fetches.get(), 3);
+
+        // Loads that check the response differently make their own fetch.
+        let mut limited = RequestOptions::get();
+        limited.set_max_response_size(Some(4));
+        let mut verified = RequestOptions::get();
+        verified.set_expected_digest(Some((DigestAlgorithm::Sha256, vec![0; 32])));
+        let checked = vec![load(&mut manager, limited), load(&mut manager, verified)];
+        assert_eq!(fetches.get(), 5);
+
+        let (mut executor, sender) = crate::backend::navigator::NullExecutor::new();
+        let bodies = Rc::new(RefCell::new(Vec::new()));
+        for fetch in gets.into_iter().chain(posts).chain(checked) {
+            let bodies = bodies.clone();
+            sender
+                .send(Box::pin(async move {
+                    bodies.borrow_mut().push(fetch.await?.body);
+                    Ok(())
+                }))
+                .unwrap();
+        }
+        executor.block_all().unwrap();
+        assert_eq!(*bodies.borrow(), vec![b"score=10".to_vec(); 7]);
+
+        // Once the fetch completes, the next load fetches again.
+        let _refetch = load(&mut manager, RequestOptions::get());
+        assert_eq!(fetches.get(), 6);
+    }
+
+    #[test]
+    fn cancel_removes_loader() {
+        gc_arena::rootless_arena(|mc| {
+            let mut manager = LoadManager::new();
+            let handle = manager.add_loader(Loader::LoadVars {
+                self_handle: None,
+                url: Some("http://example.com/vars.txt".to_string()),
+                target_object: crate::avm1::ScriptObject::object(mc, None).into(),
+            });
+
+            assert!(manager.cancel(handle));
+            assert!(manager.get_loader(handle).is_none());
+            assert!(manager.active_loaders().is_empty());
+            assert!(!manager.cancel(handle));
+        })
+    }
+
+    #[test]
+    fn abort_all_cancels_every_loader() {
+        gc_arena::rootless_arena(|mc| {
+            let mut manager = LoadManager::new();
+            let root = manager.add_loader(Loader::RootMovie {
+                self_handle: None,
+                url: Some("http://example.com/root.swf".to_string()),
+            });
+            let vars = manager.add_loader(Loader::LoadVars {
+                self_handle: None,
+                url: Some("http://example.com/vars.txt".to_string()),
+                target_object: crate::avm1::ScriptObject::object(mc, None).into(),
+            });
+
+            manager.abort_all();
+            assert!(manager.get_loader(root).is_none());
+            assert!(manager.get_loader(vars).is_none());
+            assert!(manager.active_loaders().is_empty());
+
+            // Handles of aborted loads stay invalid once slots are reused.
+            let next = manager.add_loader(Loader::RootMovie {
+                self_handle: None,
+                url: Some("http://example.com/next.swf".to_string()),
+            });
+            assert!(manager.get_loader(root).is_none());
+            assert!(manager.get_loader(vars).is_none());
+            assert!(!manager.cancel(root));
+            assert_eq!(manager.active_loaders().len(), 1);
+            assert!(manager.cancel(next));
+        })
+    }
+
+    #[test]
+    fn resume_interrupted_fetch_at_halfway_point() {
+        let movie: Vec<u8> = (0..100).collect();
+        let (received, rest) = movie.split_at(50);
+
+        let response = Response {
+            url: "http://example.com/movie.swf".to_string(),
+            status: 206,
+            headers: vec![("Content-Range".to_string(), "bytes 50-99/100".to_string())],
+            body: rest.to_vec(),
+        };
+        let response = resume_response(received.to_vec(), response).unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, movie);
+        assert_eq!(response.header("Content-Range"), None);
+
+        // A server that ignores the range sends the whole movie again.
+        let response = Response {
+            url: "http://example.com/movie.swf".to_string(),
+            status: 200,
+            headers: vec![],
+            body: movie.clone(),
+        };
+        let response = resume_response(received.to_vec(), response).unwrap();
+        assert_eq!(response.body, movie);
+
+        let response = Response {
+            url: "http://example.com/movie.swf".to_string(),
+            status: 206,
+            headers: vec![("Content-Range".to_string(), "bytes 40-99/100".to_string())],
+            body: movie[40..].to_vec(),
+        };
+        assert!(matches!(
+            resume_response(received.to_vec(), response),
+            Err(Error::FetchError(_))
+        ));
+    }
+
+    #[test]
+    fn explicit_parameters_override_query_string() {
+        let parameters = merge_parameters(
+            "http://example.com/child.swf?foo=query&level=1",
+            vec![
+                ("foo".to_string(), "bar".to_string()),
+                ("extra".to_string(), "value".to_string()),
+            ],
+        );
+
+        assert_eq!(
+            parameters,
+            vec![
+                ("level".to_string(), "1".to_string()),
+                ("foo".to_string(), "bar".to_string()),
+                ("extra".to_string(), "value".to_string()),
+            ]
+        );
+
+        assert!(merge_parameters("http://example.com/child.swf", vec![]).is_empty());
+    }
+
+    #[test]
+    fn parse_json_bodies() {
+        let json = parse_json(
+            b"\xEF\xBB\xBF{\"level\": 3, \"names\": [\"a\", \"b\"]}\r\n\n",
+            None,
+        )
+        .unwrap();
+        assert_eq!(json["level"], 3);
+        assert_eq!(json["names"][1], "b");
+
+        let json = parse_json(b"\"caf\xE9\"", Some("application/json; charset=iso-8859-1"));
+        assert_eq!(json.unwrap(), "caf\u{e9}");
+
+        assert!(parse_json(b"{\"level\": 3,}", None).is_err());
+        assert!(parse_json(b"<html>Not Found</html>", None).is_err());
+        assert!(parse_json(b"", None).is_err());
+    }
+
+    #[test]
+    fn invalid_root_movie_reasons() {
+        assert_eq!(
+            invalid_movie_reason(b"<html>Not Found</html>"),
+            InvalidMovieReason::NotSwf
+        );
+        assert_eq!(invalid_movie_reason(b""), InvalidMovieReason::NotSwf);
+        assert_eq!(
+            invalid_movie_reason(b"CWS\x0Acorrupt"),
+            InvalidMovieReason::NotSwf
+        );
+        assert_eq!(
+            invalid_movie_reason(b"FWS\x30corrupt"),
+            InvalidMovieReason::UnsupportedVersion(48)
+        );
+    }
+
+    #[test]
+    fn parse_level_targets() {
+        assert_eq!(parse_level("_level0"), Some(0));
+        assert_eq!(parse_level("_level5"), Some(5));
+        assert_eq!(parse_level("_level"), None);
+        assert_eq!(parse_level("_levelfoo"), None);
+        assert_eq!(parse_level("_root.menu"), None);
+        assert_eq!(parse_level("/menu"), None);
+    }
+
+    #[test]
+    fn sniff_content_type() {
+        assert_eq!(ContentType::sniff(b"FWS\x0a\x10\x00"), ContentType::Swf);
+        assert_eq!(ContentType::sniff(b"CWS"), ContentType::Swf);
+        assert_eq!(ContentType::sniff(b"ZWS\x0d"), ContentType::Swf);
+        assert_eq!(ContentType::sniff(b"Hello, world!"), ContentType::Unknown);
+        assert_eq!(ContentType::sniff(b"FW"), ContentType::Unknown);
+        assert_eq!(ContentType::sniff(b""), ContentType::Unknown);
+    }
+
+    #[test]
+    fn sniff_svg() {
+        assert_eq!(
+            ContentType::sniff(b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>"),
+            ContentType::Svg
+        );
+        assert_eq!(
+            ContentType::sniff(
+                b"\xEF\xBB\xBF<?xml version=\"1.0\"?>\n<!-- icon -->\n\
+                <!DOCTYPE svg PUBLIC \"-//W3C//DTD SVG 1.1//EN\" \"svg11.dtd\">\n<svg>"
+            ),
+            ContentType::Svg
+        );
+
+        // Other XML documents aren't mistaken for SVG.
+        assert_eq!(
+            ContentType::sniff(b"<?xml version=\"1.0\"?><config><svg/></config>"),
+            ContentType::Unknown
+        );
+        assert_eq!(ContentType::sniff(b"<svgfont/>"), ContentType::Unknown);
+
+        let svg = Response {
+            url: "http://example.com/logo.svg".to_string(),
+            status: 200,
+            headers: vec![],
+            body: b"<svg width=\"10\" height=\"10\"></svg>".to_vec(),
+        };
+        assert!(matches!(
+            parse_movie_response(&svg, None, None),
+            Err(Error::UnsupportedSvg(url)) if url == "http://example.com/logo.svg"
+        ));
+    }
+
+    /// Build a BMP image with a `BITMAPINFOHEADER`, a palette and the given
+    /// rows, which must already be padded.
+    fn bmp(width: i32, height: i32, bits_per_pixel: u16, palette: &[u8], rows: &[u8]) -> Vec<u8> {
+        let pixels_offset = (BMP_HEADER_LEN + palette.len()) as u32;
+        let mut data = b"BM".to_vec();
+        data.extend_from_slice(&(pixels_offset + rows.len() as u32).to_le_bytes());
+        data.extend_from_slice(&[0; 4]);
+        data.extend_from_slice(&pixels_offset.to_le_bytes());
+        data.extend_from_slice(&40u32.to_le_bytes());
+        data.extend_from_slice(&width.to_le_bytes());
+        data.extend_from_slice(&height.to_le_bytes());
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&bits_per_pixel.to_le_bytes());
+        data.extend_from_slice(&[0; 16]);
+        data.extend_from_slice(&((palette.len() / 4) as u32).to_le_bytes());
+        data.extend_from_slice(&[0; 4]);
+        data.extend_from_slice(palette);
+        data.extend_from_slice(rows);
+        data
+    }
+
+    fn bmp_pixels(data: &[u8]) -> Vec<u8> {
+        match decode_bmp(data).unwrap().data {
+            BitmapFormat::Rgba(rgba) => rgba,
+            BitmapFormat::Rgb(_) => panic!("expected RGBA pixels"),
+        }
+    }
+
+    #[test]
+    fn decode_bmp_images() {
+        // Red, green / blue, white, stored bottom-up with padded rows.
+        let rows = [
+            0xff, 0x00, 0x00, 0xff, 0xff, 0xff, 0x00, 0x00, // blue, white
+            0x00, 0x00, 0xff, 0x00, 0xff, 0x00, 0x00, 0x00, // red, green
+        ];
+        let expected = [
+            0xff, 0x00, 0x00, 0xff, 0x00, 0xff, 0x00, 0xff, // red, green
+            0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, // blue, white
+        ];
+        let bottom_up = bmp(2, 2, 24, &[], &rows);
+        assert_eq!(ContentType::sniff(&bottom_up), ContentType::Bmp);
+        let response = Response {
+            url: "http://example.com/image.bmp".to_string(),
+            status: 200,
+            headers: vec![],
+            body: bottom_up.clone(),
+        };
+        let (_, _, bitmap) = parse_movie_response(&response, None, None).unwrap();
+        let bitmap = bitmap.expect("a BMP should load as an image");
+        assert_eq!((bitmap.width, bitmap.height), (2, 2));
+        assert_eq!(bmp_pixels(&bottom_up), expected);
+
+        let mut top_down_rows = rows[8..].to_vec();
+        top_down_rows.extend_from_slice(&rows[..8]);
+        assert_eq!(bmp_pixels(&bmp(2, -2, 24, &[], &top_down_rows)), expected);
+
+        let palette = [0x00, 0x00, 0xff, 0x00, 0xff, 0x00, 0x00, 0x00];
+        assert_eq!(
+            bmp_pixels(&bmp(2, 1, 8, &palette, &[1, 0, 0, 0])),
+            [0x00, 0xff, 0x00, 0xff, 0xff, 0x00, 0x00, 0xff]
+        );
+
+        // An unused alpha channel is opaque, and a used one is premultiplied.
+        assert_eq!(
+            bmp_pixels(&bmp(1, 1, 32, &[], &[0x00, 0x00, 0xff, 0x00])),
+            [0xff, 0x00, 0x00, 0xff]
+        );
+        assert_eq!(
+            bmp_pixels(&bmp(1, 1, 32, &[], &[0x00, 0x00, 0xff, 0x80])),
+            [0x80, 0x00, 0x00, 0x80]
+        );
+
+        assert!(matches!(
+            decode_bmp(&bmp(2, 2, 24, &[], &rows[..8])),
+            Err(Error::InvalidBmp(_))
+        ));
+        assert!(matches!(
+            decode_bmp(&bmp(2, 2, 16, &[], &rows)),
+            Err(Error::InvalidBmp(_))
+        ));
+
+        // Huge sizes and offsets are rejected before anything is allocated.
+        assert!(matches!(
+            decode_bmp(&bmp(0xffff, -0xffff, 32, &[], &rows)),
+            Err(Error::InvalidBmp("truncated pixel data"))
+        ));
+        let mut far_pixels = bottom_up.clone();
+        far_pixels[10..14].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(
+            decode_bmp(&far_pixels),
+            Err(Error::InvalidBmp("truncated pixel data"))
+        ));
+    }
+
+    #[test]
+    fn sniff_bmp_headers() {
+        // Text that happens to start with "BM" is not an image.
+        let text = format!("BMX={}", "1".repeat(BMP_HEADER_LEN));
+        assert_eq!(ContentType::sniff(text.as_bytes()), ContentType::Unknown);
+
+        let image = bmp(1, 1, 24, &[], &[0; 4]);
+        let mut core_header = image.clone();
+        core_header[14..18].copy_from_slice(&12u32.to_le_bytes());
+        assert_eq!(ContentType::sniff(&core_header), ContentType::Unknown);
+        let mut two_planes = image.clone();
+        two_planes[26..28].copy_from_slice(&2u16.to_le_bytes());
+        assert_eq!(ContentType::sniff(&two_planes), ContentType::Unknown);
+        assert!(matches!(
+            decode_bmp(&two_planes),
+            Err(Error::InvalidBmp("unsupported header"))
+        ));
+    }
+
+    /// Encode an 8-bit PNG image with the given pixels.
+    fn png(width: u32, height: u32, color_type: png::ColorType, pixels: &[u8]) -> Vec<u8> {
+        let mut data = Vec::new();
+        let mut encoder = png::Encoder::new(&mut data, width, height);
+        encoder.set_color(color_type);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .unwrap()
+            .write_image_data(pixels)
+            .unwrap();
+        data
+    }
+
+    fn image_pixels(data: &[u8]) -> Vec<u8> {
+        let response = Response {
+            url: "http://example.com/image".to_string(),
+            status: 200,
+            headers: vec![],
+            body: data.to_vec(),
+        };
+        let (_, _, bitmap) = parse_movie_response(&response, None, None).unwrap();
+        match bitmap.map(|bitmap| bitmap.data) {
+            Some(BitmapFormat::Rgba(rgba)) => rgba,
+            _ => panic!("expected an RGBA image"),
+        }
+    }
+
+    #[test]
+    fn decode_loaded_images() {
+        // Translucent red and transparent green keep their alpha.
+        let rgba = png(2, 1, png::ColorType::Rgba, &[255, 0, 0, 128, 0, 255, 0, 0]);
+        assert_eq!(ContentType::sniff(&rgba), ContentType::Png);
+        assert_eq!(image_pixels(&rgba), [128, 0, 0, 128, 0, 0, 0, 0]);
+
+        let gray_alpha = png(1, 1, png::ColorType::GrayscaleAlpha, &[200, 255]);
+        assert_eq!(image_pixels(&gray_alpha), [200, 200, 200, 255]);
+
+        // Opaque images gain an opaque alpha channel.
+        let gray = png(1, 1, png::ColorType::Grayscale, &[7]);
+        assert_eq!(image_pixels(&gray), [7, 7, 7, 255]);
+
+        assert_eq!(ContentType::sniff(b"GIF87a\x01\x00"), ContentType::Gif);
+        assert_eq!(ContentType::sniff(b"GIF89a\x01\x00"), ContentType::Gif);
+        assert_eq!(ContentType::sniff(b"\xff\xd8\xff\xe0"), ContentType::Jpeg);
+
+        let truncated = Response {
+            url: "http://example.com/image.png".to_string(),
+            status: 200,
+            headers: vec![],
+            body: rgba[..20].to_vec(),
+        };
+        assert!(matches!(
+            parse_movie_response(&truncated, None, None),
+            Err(Error::ContentDecodeFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn cached_movies_get_their_own_library() {
+        let cached = Arc::new(SwfMovie::empty(10));
+        let first = movie_for_load(cached.clone(), Vec::new(), true);
+        let second = movie_for_load(cached.clone(), Vec::new(), true);
+        assert!(!Arc::ptr_eq(&first, &cached));
+        assert!(!Arc::ptr_eq(&first, &second));
+
+        let uncached = movie_for_load(cached.clone(), Vec::new(), false);
+        assert!(Arc::ptr_eq(&uncached, &cached));
+
+        let parameters = vec![("level".to_string(), "2".to_string())];
+        let with_parameters = movie_for_load(cached.clone(), parameters.clone(), false);
+        assert!(!Arc::ptr_eq(&with_parameters, &cached));
+        assert_eq!(with_parameters.parameters(), &parameters[..]);
+        assert!(cached.parameters().is_empty());
+    }
+
+    #[test]
+    fn movie_cache_disabled_at_zero_capacity() {
+        let mut cache = MovieCache::new(0);
+        cache.insert("movie.swf".to_string(), Arc::new(SwfMovie::empty(10)));
+        assert!(cache.get("movie.swf").is_none());
+    }
+
+    /// Build a baseline grayscale JPEG of a solid mid-gray.
+    ///
+    /// Every 8x8 block holds only a zero DC difference and an end of block,
+    /// each coded by the single 1-bit code of its Huffman table.
+    fn jpeg(width: u16, height: u16, orientation: Option<u16>) -> Vec<u8> {
+        let mut data = vec![0xff, 0xd8];
+        if let Some(orientation) = orientation {
+            data.extend_from_slice(&[0xff, 0xe1, 0, 34]);
+            data.extend_from_slice(b"Exif\0\0MM\0\x2a\0\0\0\x08\0\x01");
+            data.extend_from_slice(&[0x01, 0x12, 0, 3, 0, 0, 0, 1]);
+            data.extend_from_slice(&orientation.to_be_bytes());
+            data.extend_from_slice(&[0; 6]);
+        }
+        data.extend_from_slice(&[0xff, 0xdb, 0, 67, 0]);
+        data.extend_from_slice(&[1; 64]);
+        data.extend_from_slice(&[0xff, 0xc0, 0, 11, 8]);
+        data.extend_from_slice(&height.to_be_bytes());
+        data.extend_from_slice(&width.to_be_bytes());
+        data.extend_from_slice(&[1, 1, 0x11, 0]);
+        for &table in &[0x00, 0x10] {
+            data.extend_from_slice(&[0xff, 0xc4, 0, 20, table, 1]);
+            data.extend_from_slice(&[0; 16]);
+        }
+        data.extend_from_slice(&[0xff, 0xda, 0, 8, 1, 1, 0, 0, 63, 0]);
+
+        // Two zero bits per block, padded with one bits.
+        let blocks = ((usize::from(width) + 7) / 8) * ((usize::from(height) + 7) / 8);
+        let bits = blocks * 2;
+        data.extend(std::iter::repeat(0).take(bits / 8));
+        if bits % 8 != 0 {
+            data.push(0xff >> (bits % 8));
+        }
+        data.extend_from_slice(&[0xff, 0xd9]);
+        data
+    }
 
-        /// Event broadcaster (typically a `MovieClipLoader`) to fire events
-        /// into.
-        target_broadcaster: Option<Object<'gc>>,
+    #[test]
+    fn loaded_jpegs_are_turned_upright() {
+        let decode = |data: Vec<u8>| {
+            let response = Response {
+                url: "http://example.com/photo.jpg".to_string(),
+                status: 200,
+                headers: vec![],
+                body: data,
+            };
+            let bitmap = decode_image(&response, ContentType::Jpeg).unwrap();
+            (bitmap.width, bitmap.height)
+        };
 
-        /// Indicates the completion status of this loader.
-        ///
-        /// This flag exists to prevent a situation in which loading a movie
-        /// into a clip that has not yet fired its Load event causes the
-        /// loader to be prematurely removed. This flag is only set when either
-        /// the movie has been replaced (and thus Load events can be trusted)
-        /// or an error has occurred (in which case we don't care about the
-        /// loader anymore).
-        loader_status: LoaderStatus,
-    },
+        assert_eq!(jpeg_orientation(&jpeg(16, 8, None)), 1);
+        assert_eq!(jpeg_orientation(&jpeg(16, 8, Some(6))), 6);
+        assert_eq!(decode(jpeg(16, 8, None)), (16, 8));
+        assert_eq!(decode(jpeg(16, 8, Some(1))), (16, 8));
+        assert_eq!(decode(jpeg(16, 8, Some(6))), (8, 16));
+    }
 
-    /// Loader that is loading form data into an AVM1 object scope.
-    Form {
-        /// The handle to refer to this loader instance.
-        #[collect(require_static)]
-        self_handle: Option<Handle>,
+    #[test]
+    fn apply_exif_orientations() {
+        // Each pixel's red channel is its index in this 3x2 image:
+        // 0 1 2
+        // 3 4 5
+        let rgba: Vec<u8> = (0..6).flat_map(|i| vec![i, 0, 0, 255]).collect();
+        let orient = |orientation| {
+            let bitmap = Bitmap {
+                width: 3,
+                height: 2,
+                data: BitmapFormat::Rgba(rgba.clone()),
+            };
+            let bitmap = apply_orientation(bitmap, orientation);
+            let red = match bitmap.data {
+                BitmapFormat::Rgba(rgba) => rgba.iter().step_by(4).copied().collect(),
+                BitmapFormat::Rgb(_) => vec![],
+            };
+            (bitmap.width, bitmap.height, red)
+        };
 
-        /// The target AVM1 object to load form data into.
-        target_object: Object<'gc>,
-    },
+        assert_eq!(orient(1), (3, 2, vec![0, 1, 2, 3, 4, 5]));
+        assert_eq!(orient(2), (3, 2, vec![2, 1, 0, 5, 4, 3]));
+        assert_eq!(orient(3), (3, 2, vec![5, 4, 3, 2, 1, 0]));
+        assert_eq!(orient(4), (3, 2, vec![3, 4, 5, 0, 1, 2]));
+        assert_eq!(orient(5), (2, 3, vec![0, 3, 1, 4, 2, 5]));
+        assert_eq!(orient(6), (2, 3, vec![3, 0, 4, 1, 5, 2]));
+        assert_eq!(orient(7), (2, 3, vec![5, 2, 4, 1, 3, 0]));
+        assert_eq!(orient(8), (2, 3, vec![2, 5, 1, 4, 0, 3]));
+        // Unknown orientations are left alone.
+        assert_eq!(orient(9), (3, 2, vec![0, 1, 2, 3, 4, 5]));
+    }
 
-    /// Loader that is loading form data into an AVM1 LoadVars object.
-    LoadVars {
-        /// The handle to refer to this loader instance.
-        #[collect(require_static)]
-        self_handle: Option<Handle>,
+    /// Record the bytes loaded and total reported by `onLoadProgress` on the
+    /// listener.
+    fn record_load_progress<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+        this: Object<'gc>,
+        args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, crate::avm1::Error<'gc>> {
+        let loaded = args.get(1).cloned().unwrap_or(Value::Undefined);
+        this.set("loaded", loaded, activation)?;
+        let total = args.get(2).cloned().unwrap_or(Value::Undefined);
+        this.set("total", total, activation)?;
+        Ok(Value::Undefined)
+    }
 
-        /// The target AVM1 object to load form data into.
-        target_object: Object<'gc>,
-    },
+    /// Record the error code reported by `onLoadError` on the listener.
+    fn record_load_error<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+        this: Object<'gc>,
+        args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, crate::avm1::Error<'gc>> {
+        let error = args.get(1).cloned().unwrap_or(Value::Undefined);
+        this.set("error", error, activation)?;
+        Ok(Value::Undefined)
+    }
 
-    /// Loader that is loading XML data into an XML tree.
-    Xml {
-        /// The handle to refer to this loader instance.
-        #[collect(require_static)]
-        self_handle: Option<Handle>,
+    /// Trace `onLoadComplete` and the HTTP status it was given.
+    fn trace_load_complete<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+        _this: Object<'gc>,
+        args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, crate::avm1::Error<'gc>> {
+        let status = args.get(1).cloned().unwrap_or(Value::Undefined);
+        let status = status.coerce_to_f64(activation)?;
+        activation
+            .context
+            .log
+            .avm_trace(&format!("onLoadComplete {}", status));
+        Ok(Value::Undefined)
+    }
 
-        /// The active movie clip at the time of load invocation.
-        ///
-        /// This property is a technicality: Under normal circumstances, it's
-        /// not supposed to be a load factor, and only exists so that the
-        /// runtime can do *something* in really contrived scenarios where we
-        /// actually need an active clip.
-        active_clip: DisplayObject<'gc>,
+    /// Trace `onLoadError` and the error code and HTTP status it was given.
+    fn trace_load_error<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+        _this: Object<'gc>,
+        args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, crate::avm1::Error<'gc>> {
+        let error = args.get(1).cloned().unwrap_or(Value::Undefined);
+        let error = error.coerce_to_string(activation)?.to_string();
+        let status = args.get(2).cloned().unwrap_or(Value::Undefined);
+        let status = status.coerce_to_f64(activation)?;
+        activation
+            .context
+            .log
+            .avm_trace(&format!("onLoadError {} {}", error, status));
+        Ok(Value::Undefined)
+    }
 
-        /// The target node whose contents will be replaced with the parsed XML.
-        target_node: XmlNode<'gc>,
-    },
-}
+    /// Record how many children the clip at depth 1 has when `onLoadStart`
+    /// fires.
+    fn record_load_start<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+        this: Object<'gc>,
+        _args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, crate::avm1::Error<'gc>> {
+        let children = activation
+            .context
+            .stage
+            .root_clip()
+            .as_container()
+            .and_then(|root| root.child_by_depth(1))
+            .and_then(|clip| clip.as_container())
+            .map_or(0, |clip| clip.num_children());
+        this.set("children", (children as f64).into(), activation)?;
+        Ok(Value::Undefined)
+    }
 
-impl<'gc> Loader<'gc> {
-    /// Set the loader handle for this loader.
+    /// Record whether `onLoad` reported a sound as loaded.
+    fn record_sound_load<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+        this: Object<'gc>,
+        args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, crate::avm1::Error<'gc>> {
+        let success = args.get(0).cloned().unwrap_or(Value::Undefined);
+        this.set("loaded", success, activation)?;
+        Ok(Value::Undefined)
+    }
+
+    /// Record the bytes loaded that `LoadVars` reports when `onData` fires.
+    fn record_bytes_on_data<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+        this: Object<'gc>,
+        _args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, crate::avm1::Error<'gc>> {
+        let bytes_loaded = this.call_method("getBytesLoaded", &[], activation)?;
+        this.set("bytesOnData", bytes_loaded, activation)?;
+        Ok(Value::Undefined)
+    }
+
+    /// Report the `getBytesLoaded` and `getBytesTotal` of the global `vars`
+    /// object, and the bytes loaded that its `onData` saw.
+    fn load_vars_bytes(activation: &mut Activation<'_, '_, '_>) -> Vec<Option<f64>> {
+        let vars = activation
+            .context
+            .avm1
+            .global_object_cell()
+            .get("vars", activation)
+            .unwrap()
+            .coerce_to_object(activation);
+        let values = [
+            vars.call_method("getBytesLoaded", &[], activation).unwrap(),
+            vars.call_method("getBytesTotal", &[], activation).unwrap(),
+            vars.get("bytesOnData", activation).unwrap(),
+        ];
+        values
+            .iter()
+            .map(|value| match value {
+                Value::Undefined => None,
+                value => Some(value.coerce_to_f64(activation).unwrap()),
+            })
+            .collect()
+    }
+
+    /// Create a player with null backends, which fetches files from `dir`.
     ///
-    /// An active loader handle is required before asynchronous loader code can
-    /// run.
-    pub fn introduce_loader_handle(&mut self, handle: Handle) {
-        match self {
-            Loader::RootMovie { self_handle, .. } => *self_handle = Some(handle),
-            Loader::Movie { self_handle, .. } => *self_handle = Some(handle),
-            Loader::Form { self_handle, .. } => *self_handle = Some(handle),
-            Loader::LoadVars { self_handle, .. } => *self_handle = Some(handle),
-            Loader::Xml { self_handle, .. } => *self_handle = Some(handle),
-        }
+    /// The executor must be kept alive for as long as the player is used.
+    fn null_player(
+        dir: &std::path::Path,
+    ) -> (Arc<Mutex<Player>>, crate::backend::navigator::NullExecutor) {
+        use crate::backend::log::NullLogBackend;
+
+        null_player_with_log(dir, Box::new(NullLogBackend::new()))
     }
 
-    /// Construct a future for the root movie loader.
-    pub fn root_movie_loader(
-        &mut self,
-        player: Weak<Mutex<Player>>,
-        fetch: OwnedFuture<Vec<u8>, Error>,
-        mut url: String,
-        parameters: Vec<(String, String)>,
-        on_metadata: Box<dyn FnOnce(&swf::HeaderExt)>,
-    ) -> OwnedFuture<(), Error> {
-        let _handle = match self {
-            Loader::RootMovie { self_handle, .. } => {
-                self_handle.expect("Loader not self-introduced")
-            }
-            _ => return Box::pin(async { Err(Error::NotMovieLoader) }),
+    /// Create a player like `null_player`, which sends traces to `log`.
+    fn null_player_with_log(
+        dir: &std::path::Path,
+        log: Box<dyn crate::backend::log::LogBackend>,
+    ) -> (Arc<Mutex<Player>>, crate::backend::navigator::NullExecutor) {
+        use crate::backend::{
+            audio::NullAudioBackend, locale::NullLocaleBackend, navigator::NullExecutor,
+            navigator::NullNavigatorBackend, render::NullRenderer, storage::MemoryStorageBackend,
+            ui::NullUiBackend, video::NullVideoBackend,
         };
 
-        let player = player
-            .upgrade()
-            .expect("Could not upgrade weak reference to player");
+        let (executor, channel) = NullExecutor::new();
+        let player = Player::new(
+            Box::new(NullRenderer::new()),
+            Box::new(NullAudioBackend::new()),
+            Box::new(NullNavigatorBackend::with_base_path(dir, channel)),
+            Box::new(MemoryStorageBackend::default()),
+            Box::new(NullLocaleBackend::new()),
+            Box::new(NullVideoBackend::new()),
+            log,
+            Box::new(NullUiBackend::new()),
+        )
+        .unwrap();
+        (player, executor)
+    }
 
-        Box::pin(async move {
-            player
-                .lock()
-                .expect("Could not lock player!!")
-                .update(|uc| -> Result<(), Error> {
-                    url = uc.navigator.resolve_relative_url(&url).into_owned();
+    /// A log backend that records traces, so tests can see when scripts run.
+    struct TraceLog(std::rc::Rc<std::cell::RefCell<Vec<String>>>);
 
-                    Ok(())
-                })?;
+    impl crate::backend::log::LogBackend for TraceLog {
+        fn avm_trace(&self, message: &str) {
+            self.0.borrow_mut().push(message.to_string());
+        }
+    }
 
-            let data = (fetch.await).and_then(|data| {
-                Ok((
-                    data.len(),
-                    SwfMovie::from_data(&data, Some(url.clone()), None)?,
-                ))
-            });
+    /// Construct a `MovieClipLoader` which listens to itself with the given
+    /// event handlers, and store it in the global `loader`.
+    fn movie_clip_loader<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+        listeners: &[(&str, crate::avm1::function::NativeFunction)],
+    ) -> Object<'gc> {
+        use crate::avm1::function::{Executable, FunctionObject};
 
-            if let Ok((_length, mut movie)) = data {
-                on_metadata(movie.header());
-                movie.append_parameters(parameters);
-                player.lock().unwrap().set_root_movie(Arc::new(movie));
-                Ok(())
-            } else {
-                player
-                    .lock()
-                    .unwrap()
-                    .ui()
-                    .display_root_movie_download_failed_message();
-                Err(Error::FetchError(url))
-            }
-        })
+        let globals = activation.context.avm1.global_object_cell();
+        let loader = globals
+            .get("MovieClipLoader", activation)
+            .unwrap()
+            .coerce_to_object(activation)
+            .construct(activation, &[])
+            .unwrap()
+            .coerce_to_object(activation);
+        let fn_proto = activation.context.avm1.prototypes().function;
+        for &(event, record) in listeners {
+            let function = FunctionObject::function(
+                activation.context.gc_context,
+                Executable::Native(record),
+                Some(fn_proto),
+                fn_proto,
+            );
+            loader.set(event, function.into(), activation).unwrap();
+        }
+        globals.set("loader", loader.into(), activation).unwrap();
+        loader
     }
 
-    /// Construct a future for the given movie loader.
-    ///
-    /// The given future should be passed immediately to an executor; it will
-    /// take responsibility for running the loader to completion.
+    /// Load a URL into the root clip with a new `MovieClipLoader`, which
+    /// listens to itself.
     ///
-    /// If the loader is not a movie then the returned future will yield an
-    /// error immediately once spawned.
-    pub fn movie_loader(
-        &mut self,
-        player: Weak<Mutex<Player>>,
-        fetch: OwnedFuture<Vec<u8>, Error>,
-        mut url: String,
-        loader_url: Option<String>,
-    ) -> OwnedFuture<(), Error> {
-        let handle = match self {
-            Loader::Movie { self_handle, .. } => self_handle.expect("Loader not self-introduced"),
-            _ => return Box::pin(async { Err(Error::NotMovieLoader) }),
+    /// Returns the bytes it last saw loaded and the error code it was given,
+    /// if any.
+    fn load_with_movie_clip_loader(
+        player: &Arc<Mutex<Player>>,
+        url: &str,
+        options: RequestOptions,
+    ) -> (Option<f64>, Option<String>) {
+        use crate::avm1::function::NativeFunction;
+
+        let load = player.lock().unwrap().update(|uc| {
+            let mut activation =
+                Activation::from_stub(uc.reborrow(), ActivationIdentifier::root("[Test]"));
+            let listeners: [(&str, NativeFunction); 2] = [
+                ("onLoadProgress", record_load_progress),
+                ("onLoadError", record_load_error),
+            ];
+            let loader = movie_clip_loader(&mut activation, &listeners);
+
+            let root = activation.context.stage.root_clip();
+            activation.context.load_manager.load_movie_into_clip(
+                activation.context.player.clone().unwrap(),
+                root,
+                url.to_string(),
+                options,
+                MovieLoadOptions {
+                    target_broadcaster: Some(loader),
+                    ..Default::default()
+                },
+            )
+        });
+        Player::run_loader_to_completion(player, load).unwrap();
+
+        player.lock().unwrap().update(|uc| {
+            let mut activation =
+                Activation::from_stub(uc.reborrow(), ActivationIdentifier::root("[Test]"));
+            let globals = activation.context.avm1.global_object_cell();
+            let loader = globals
+                .get("loader", &mut activation)
+                .unwrap()
+                .coerce_to_object(&mut activation);
+            let loaded = match loader.get("loaded", &mut activation).unwrap() {
+                Value::Undefined => None,
+                loaded => Some(loaded.coerce_to_f64(&mut activation).unwrap()),
+            };
+            let error = match loader.get("error", &mut activation).unwrap() {
+                Value::Undefined => None,
+                error => Some(error.coerce_to_string(&mut activation).unwrap().to_string()),
+            };
+            (loaded, error)
+        })
+    }
+
+    #[test]
+    fn movie_clip_loaders_see_image_loads() {
+        let dir = std::env::temp_dir().join(format!("ruffle-image-loads-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let image = png(1, 1, png::ColorType::Rgba, &[255, 0, 0, 128]);
+        std::fs::write(dir.join("image.png"), &image).unwrap();
+        std::fs::write(dir.join("empty.png"), b"").unwrap();
+
+        let (player, _executor) = null_player(&dir);
+        let load = |url| load_with_movie_clip_loader(&player, url, RequestOptions::get());
+
+        assert_eq!(load("image.png"), (Some(image.len() as f64), None));
+        // Empty and undecodable images fail without reporting progress.
+        assert_eq!(
+            load("empty.png"),
+            (None, Some("LoadNeverCompleted".to_string()))
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn movie_clip_loaders_see_digest_checks() {
+        let dir = std::env::temp_dir().join(format!("ruffle-digest-loads-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut swf = Vec::new();
+        swf::write_swf(&swf::Header::default_with_swf_version(8), &[], &mut swf).unwrap();
+        std::fs::write(dir.join("movie.swf"), &swf).unwrap();
+
+        let (player, _executor) = null_player(&dir);
+        let loaded = (Some(swf.len() as f64), None);
+        // A mismatch fails once the body has arrived, before it is parsed.
+        let mismatched = (None, Some("LoadNeverCompleted".to_string()));
+
+        let digest = DigestAlgorithm::Sha256.digest(&swf);
+        let mut tampered = digest.clone();
+        tampered[0] ^= 1;
+        for (digest, expected) in vec![(digest.clone(), &loaded), (tampered, &mismatched)] {
+            let mut options = RequestOptions::get();
+            options.set_expected_digest(Some((DigestAlgorithm::Sha256, digest)));
+            assert_eq!(
+                &load_with_movie_clip_loader(&player, "movie.swf", options),
+                expected
+            );
+        }
+
+        // `URLRequest.digest` gives the same digest in hex.
+        let hex: String = digest.iter().map(|byte| format!("{:02x}", byte)).collect();
+        let wrong_hex = "0".repeat(64);
+        for (hex, expected) in vec![(hex, &loaded), (wrong_hex, &mismatched)] {
+            let mut options = RequestOptions::get();
+            options.set_digest(&hex).unwrap();
+            assert_eq!(
+                &load_with_movie_clip_loader(&player, "movie.swf", options),
+                expected
+            );
+        }
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn parsed_movies_load_into_child_clips() {
+        let (player, _executor) = null_player(&std::env::temp_dir());
+        let movie = Arc::new(SwfMovie::empty(8));
+
+        let load = player.lock().unwrap().update(|uc| {
+            let root = uc.stage.root_clip();
+            let child: DisplayObject<'_> =
+                MovieClip::new(SwfSlice::empty(root.movie().unwrap()), uc.gc_context).into();
+            root.as_container().unwrap().replace_at_depth(uc, child, 1);
+            uc.load_manager.load_parsed_movie_into_clip(
+                uc.player.clone().unwrap(),
+                child,
+                movie.clone(),
+                None,
+            )
+        });
+        Player::run_loader_to_completion(&player, load).unwrap();
+
+        player.lock().unwrap().update(|uc| {
+            let root = uc.stage.root_clip();
+            let child = root.as_container().unwrap().child_by_depth(1).unwrap();
+            assert!(Arc::ptr_eq(&child.movie().unwrap(), &movie));
+            assert!(uc.load_manager.active_loaders().is_empty());
+        });
+    }
+
+    #[test]
+    fn decoded_bitmaps_load_into_clips() {
+        let (player, _executor) = null_player(&std::env::temp_dir());
+        let red = Bitmap {
+            width: 2,
+            height: 2,
+            data: BitmapFormat::Rgba([255, 0, 0, 255].repeat(4)),
         };
 
-        let player = player
-            .upgrade()
-            .expect("Could not upgrade weak reference to player");
+        let load = player.lock().unwrap().update(|uc| {
+            let root = uc.stage.root_clip();
+            uc.load_manager
+                .load_bitmap_into_clip(uc.player.clone().unwrap(), root, red, None)
+        });
+        Player::run_loader_to_completion(&player, load).unwrap();
 
-        let mut replacing_root_movie = false;
+        player.lock().unwrap().update(|uc| {
+            let root = uc.stage.root_clip();
+            let bitmap = root
+                .as_container()
+                .unwrap()
+                .child_by_depth(1)
+                .and_then(|bitmap| bitmap.as_bitmap())
+                .unwrap();
+            assert_eq!((bitmap.width(), bitmap.height()), (2, 2));
+            assert!(uc.load_manager.active_loaders().is_empty());
+        });
+    }
 
-        Box::pin(async move {
-            player
-                .lock()
-                .expect("Could not lock player!!")
-                .update(|uc| -> Result<(), Error> {
-                    url = uc.navigator.resolve_relative_url(&url).into_owned();
+    #[test]
+    fn parsed_movies_replace_the_root_movie() {
+        let (player, _executor) = null_player(&std::env::temp_dir());
+        let header = swf::Header {
+            stage_size: swf::Rectangle {
+                x_min: swf::Twips::ZERO,
+                x_max: swf::Twips::from_pixels(320.0),
+                y_min: swf::Twips::ZERO,
+                y_max: swf::Twips::from_pixels(240.0),
+            },
+            frame_rate: swf::Fixed8::from_f64(12.0),
+            ..swf::Header::default_with_swf_version(8)
+        };
+        let mut data = Vec::new();
+        swf::write_swf(&header, &[], &mut data).unwrap();
+        let movie = Arc::new(SwfMovie::from_data(&data, None, None).unwrap());
 
-                    let (clip, broadcaster) = match uc.load_manager.get_loader(handle) {
-                        Some(Loader::Movie {
-                            target_clip,
-                            target_broadcaster,
-                            ..
-                        }) => (*target_clip, *target_broadcaster),
-                        None => return Err(Error::Cancelled),
-                        _ => unreachable!(),
-                    };
+        let load = player.lock().unwrap().update(|uc| {
+            let root = uc.stage.root_clip();
+            uc.load_manager.load_parsed_movie_into_clip(
+                uc.player.clone().unwrap(),
+                root,
+                movie.clone(),
+                None,
+            )
+        });
+        Player::run_loader_to_completion(&player, load).unwrap();
 
-                    replacing_root_movie = DisplayObject::ptr_eq(clip, uc.stage.root_clip());
+        let mut player = player.lock().unwrap();
+        assert_eq!((player.movie_width(), player.movie_height()), (320, 240));
+        assert_eq!(player.frame_rate(), 12.0);
+    }
 
-                    clip.as_movie_clip().unwrap().unload(uc);
+    #[test]
+    fn init_callbacks_run_after_the_first_frame() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
 
-                    clip.as_movie_clip()
-                        .unwrap()
-                        .replace_with_movie(uc.gc_context, None);
+        let dir = std::env::temp_dir().join(format!("ruffle-init-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
 
-                    if let Some(broadcaster) = broadcaster {
-                        Avm1::run_stack_frame_for_method(
-                            clip,
-                            broadcaster,
-                            NEWEST_PLAYER_VERSION,
-                            uc,
-                            "broadcastMessage",
-                            &["onLoadStart".into(), Value::Object(broadcaster)],
-                        );
-                    }
+        // `trace("frame 1")`
+        let action = b"\x96\x09\x00\x00frame 1\x00\x26\x00";
+        let header = swf::Header::default_with_swf_version(8);
+        let mut data = Vec::new();
+        swf::write_swf(
+            &header,
+            &[swf::Tag::DoAction(&action[..]), swf::Tag::ShowFrame],
+            &mut data,
+        )
+        .unwrap();
+        std::fs::write(dir.join("child.swf"), &data).unwrap();
 
-                    Ok(())
-                })?;
+        let traces = Rc::new(RefCell::new(Vec::new()));
+        let (player, _executor) = null_player_with_log(&dir, Box::new(TraceLog(traces.clone())));
 
-            let data = (fetch.await).and_then(|data| {
-                Ok((
-                    data.len(),
-                    SwfMovie::from_data(&data, Some(url.clone()), loader_url.clone())?,
-                ))
+        let on_init_traces = traces.clone();
+        let load = player.lock().unwrap().update(|uc| {
+            let root = uc.stage.root_clip();
+            let child: DisplayObject<'_> =
+                MovieClip::new(SwfSlice::empty(root.movie().unwrap()), uc.gc_context).into();
+            root.as_container().unwrap().replace_at_depth(uc, child, 1);
+            uc.load_manager.load_movie_into_clip(
+                uc.player.clone().unwrap(),
+                child,
+                "child.swf".to_string(),
+                RequestOptions::get(),
+                MovieLoadOptions {
+                    on_init: Some(Box::new(move || {
+                        on_init_traces.borrow_mut().push("init".to_string())
+                    })),
+                    ..Default::default()
+                },
+            )
+        });
+        Player::run_loader_to_completion(&player, load).unwrap();
+        assert_eq!(*traces.borrow(), vec!["frame 1", "init"]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn init_callbacks_run_once_images_are_placed() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let dir = std::env::temp_dir().join(format!("ruffle-image-init-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let image = png(1, 1, png::ColorType::Rgba, &[255, 0, 0, 128]);
+        std::fs::write(dir.join("image.png"), &image).unwrap();
+
+        let (player, _executor) = null_player(&dir);
+        let inits = Rc::new(Cell::new(0));
+        let on_init_inits = inits.clone();
+        let load = player.lock().unwrap().update(|uc| {
+            let root = uc.stage.root_clip();
+            uc.load_manager.load_movie_into_clip(
+                uc.player.clone().unwrap(),
+                root,
+                "image.png".to_string(),
+                RequestOptions::get(),
+                MovieLoadOptions {
+                    on_init: Some(Box::new(move || on_init_inits.set(on_init_inits.get() + 1))),
+                    ..Default::default()
+                },
+            )
+        });
+        Player::run_loader_to_completion(&player, load).unwrap();
+
+        // The image's empty movie has run its first frame by now, which
+        // doesn't run the callback again.
+        assert_eq!(inits.get(), 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn batched_image_loads_complete_once() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let dir = std::env::temp_dir().join(format!("ruffle-batch-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let images = [
+            png(1, 1, png::ColorType::Rgba, &[255, 0, 0, 255]),
+            png(2, 1, png::ColorType::Rgb, &[0, 255, 0, 0, 0, 255]),
+            png(1, 2, png::ColorType::Grayscale, &[0, 255]),
+        ];
+        let mut requests = Vec::new();
+        for (i, image) in images.iter().enumerate() {
+            let name = format!("image{}.png", i);
+            std::fs::write(dir.join(&name), image).unwrap();
+            requests.push((name, RequestOptions::get()));
+        }
+
+        let (player, _executor) = null_player(&dir);
+        let outcomes = Rc::new(RefCell::new(Vec::new()));
+        let on_complete_outcomes = outcomes.clone();
+        let (handle, processes) = player.lock().unwrap().update(|uc| {
+            let (handle, processes) = uc.load_manager.load_batch(
+                uc.player.clone().unwrap(),
+                requests,
+                BatchFailurePolicy::FailFast,
+                Box::new(move |outcome| on_complete_outcomes.borrow_mut().push(outcome)),
+            );
+            assert_eq!(uc.load_manager.loader_progress(handle), Some((0, 0)));
+            (handle, processes)
+        });
+        for process in processes {
+            Player::run_loader_to_completion(&player, process).unwrap();
+        }
+
+        let outcomes = outcomes.borrow();
+        assert_eq!(outcomes.len(), 1);
+        let responses = outcomes[0].as_ref().ok().unwrap();
+        let bodies: Vec<_> = responses.iter().map(|r| r.body.clone()).collect();
+        assert_eq!(bodies, images.to_vec());
+        let total: usize = responses.iter().map(|r| r.body.len()).sum();
+        assert_eq!(total, images.iter().map(Vec::len).sum());
+        player.lock().unwrap().update(|uc| {
+            assert_eq!(uc.load_manager.loader_progress(handle), None);
+        });
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn loaded_image_bytes_become_bitmaps() {
+        let (player, _executor) = null_player(&std::env::temp_dir());
+        let image = png(2, 1, png::ColorType::Rgb, &[255, 0, 0, 0, 0, 255]);
+
+        let load = player.lock().unwrap().update(|uc| {
+            let root = uc.stage.root_clip();
+            let child: DisplayObject<'_> =
+                MovieClip::new(SwfSlice::empty(root.movie().unwrap()), uc.gc_context).into();
+            root.as_container().unwrap().replace_at_depth(uc, child, 1);
+            uc.load_manager.load_bytes_into_clip(
+                uc.player.clone().unwrap(),
+                child,
+                image,
+                MovieLoadOptions::default(),
+            )
+        });
+        Player::run_loader_to_completion(&player, load).unwrap();
+
+        player.lock().unwrap().update(|uc| {
+            let root = uc.stage.root_clip();
+            let child = root.as_container().unwrap().child_by_depth(1).unwrap();
+            let bitmap = child
+                .as_container()
+                .unwrap()
+                .child_by_depth(1)
+                .and_then(|bitmap| bitmap.as_bitmap())
+                .unwrap();
+            assert_eq!((bitmap.width(), bitmap.height()), (2, 1));
+        });
+    }
+
+    #[test]
+    fn loaded_movies_start_from_a_labelled_frame() {
+        let (player, mut executor) = null_player(&std::env::temp_dir());
+        let mut header = swf::Header::default_with_swf_version(8);
+        header.num_frames = 3;
+        let mut data = Vec::new();
+        swf::write_swf(
+            &header,
+            &[
+                swf::Tag::ShowFrame,
+                swf::Tag::FrameLabel(swf::FrameLabel {
+                    label: swf::SwfStr::from_utf8_str("middle"),
+                    is_anchor: false,
+                }),
+                swf::Tag::ShowFrame,
+                swf::Tag::ShowFrame,
+            ],
+            &mut data,
+        )
+        .unwrap();
+
+        let load_into_child = |start_frame| {
+            player.lock().unwrap().update(|uc| {
+                let root = uc.stage.root_clip();
+                let child: DisplayObject<'_> =
+                    MovieClip::new(SwfSlice::empty(root.movie().unwrap()), uc.gc_context).into();
+                root.as_container().unwrap().replace_at_depth(uc, child, 1);
+                uc.load_manager.load_bytes_into_clip(
+                    uc.player.clone().unwrap(),
+                    child,
+                    data.clone(),
+                    MovieLoadOptions {
+                        start_frame: Some(start_frame),
+                        ..Default::default()
+                    },
+                )
+            })
+        };
+
+        // The clip is checked before any frames run, as running them would
+        // move it on from the frame it started at.
+        let load = load_into_child(FrameLabelOrNumber::Label("Middle".to_string()));
+        player
+            .lock()
+            .unwrap()
+            .update(|uc| uc.navigator.spawn_future(load));
+        executor.block_all().unwrap();
+        player.lock().unwrap().update(|uc| {
+            let root = uc.stage.root_clip();
+            let child = root.as_container().unwrap().child_by_depth(1).unwrap();
+            assert_eq!(child.as_movie_clip().unwrap().current_frame(), 2);
+        });
+
+        let load = load_into_child(FrameLabelOrNumber::Label("missing".to_string()));
+        assert!(matches!(
+            Player::run_loader_to_completion(&player, load),
+            Err(Error::FrameLabelNotFound(label)) if label == "missing"
+        ));
+    }
+
+    #[test]
+    fn replaced_content_is_unloaded_before_the_new_load_starts() {
+        use crate::avm1::function::NativeFunction;
+
+        let (player, _executor) = null_player(&std::env::temp_dir());
+        let image = png(1, 1, png::ColorType::Rgb, &[255, 0, 0]);
+
+        let load = player.lock().unwrap().update(|uc| {
+            let root = uc.stage.root_clip();
+            let child: DisplayObject<'_> =
+                MovieClip::new(SwfSlice::empty(root.movie().unwrap()), uc.gc_context).into();
+            root.as_container().unwrap().replace_at_depth(uc, child, 1);
+            uc.load_manager.load_bytes_into_clip(
+                uc.player.clone().unwrap(),
+                child,
+                image,
+                MovieLoadOptions::default(),
+            )
+        });
+        Player::run_loader_to_completion(&player, load).unwrap();
+
+        // The second load fails, but the image is gone by the time it starts.
+        let load = player.lock().unwrap().update(|uc| {
+            let mut activation =
+                Activation::from_stub(uc.reborrow(), ActivationIdentifier::root("[Test]"));
+            let listeners: [(&str, NativeFunction); 2] = [
+                ("onLoadStart", record_load_start),
+                ("onLoadError", record_load_error),
+            ];
+            let loader = movie_clip_loader(&mut activation, &listeners);
+
+            let root = activation.context.stage.root_clip();
+            let child = root.as_container().unwrap().child_by_depth(1).unwrap();
+            activation.context.load_manager.load_bytes_into_clip(
+                activation.context.player.clone().unwrap(),
+                child,
+                b"not a movie".to_vec(),
+                MovieLoadOptions {
+                    target_broadcaster: Some(loader),
+                    ..Default::default()
+                },
+            )
+        });
+        Player::run_loader_to_completion(&player, load).unwrap();
+
+        player.lock().unwrap().update(|uc| {
+            let mut activation =
+                Activation::from_stub(uc.reborrow(), ActivationIdentifier::root("[Test]"));
+            let loader = activation
+                .context
+                .avm1
+                .global_object_cell()
+                .get("loader", &mut activation)
+                .unwrap()
+                .coerce_to_object(&mut activation);
+            let children = loader.get("children", &mut activation).unwrap();
+            assert_eq!(children.coerce_to_f64(&mut activation).unwrap(), 0.0);
+            let error = loader.get("error", &mut activation).unwrap();
+            assert_eq!(
+                error.coerce_to_string(&mut activation).unwrap().to_string(),
+                "LoadNeverCompleted"
+            );
+
+            let root = activation.context.stage.root_clip();
+            let child = root.as_container().unwrap().child_by_depth(1).unwrap();
+            assert_eq!(child.as_container().unwrap().num_children(), 0);
+        });
+    }
+
+    #[test]
+    fn variables_load_into_levels_created_on_demand() {
+        let dir = std::env::temp_dir().join(format!("ruffle-level-vars-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("vars.txt"), b"greeting=hello&count=2").unwrap();
+
+        let (player, _executor) = null_player(&dir);
+        for target in &["_level1", "missing"] {
+            let load = player.lock().unwrap().update(|uc| {
+                let root = uc.stage.root_clip();
+                uc.load_manager.load_form_into_target(
+                    uc.player.clone().unwrap(),
+                    root,
+                    target.to_string(),
+                    "vars.txt",
+                    RequestOptions::get(),
+                )
+            });
+            // A path that leads nowhere drops the variables without failing.
+            Player::run_loader_to_completion(&player, load).unwrap();
+        }
+
+        player.lock().unwrap().update(|uc| {
+            let level = uc
+                .stage
+                .child_by_depth(1)
+                .expect("_level1 should be created");
+            let mut activation =
+                Activation::from_stub(uc.reborrow(), ActivationIdentifier::root("[Test]"));
+            let level = level.object().coerce_to_object(&mut activation);
+            let greeting = level.get("greeting", &mut activation).unwrap();
+            assert_eq!(
+                greeting
+                    .coerce_to_string(&mut activation)
+                    .unwrap()
+                    .to_string(),
+                "hello"
+            );
+            let count = level.get("count", &mut activation).unwrap();
+            assert_eq!(
+                count.coerce_to_string(&mut activation).unwrap().to_string(),
+                "2"
+            );
+        });
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn status_observer_sees_movie_and_image_loads_finish() {
+        let dir = std::env::temp_dir().join(format!("ruffle-load-status-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut swf = Vec::new();
+        swf::write_swf(&swf::Header::default_with_swf_version(8), &[], &mut swf).unwrap();
+        std::fs::write(dir.join("movie.swf"), &swf).unwrap();
+        let image = png(1, 1, png::ColorType::Rgb, &[255, 0, 0]);
+        std::fs::write(dir.join("image.png"), &image).unwrap();
+        std::fs::write(dir.join("empty.png"), b"").unwrap();
+
+        let (player, _executor) = null_player(&dir);
+        let changes = Rc::new(RefCell::new(Vec::new()));
+        let observed = changes.clone();
+        player.lock().unwrap().update(|uc| {
+            uc.load_manager
+                .set_status_observer(Some(Box::new(move |_handle, status| {
+                    observed.borrow_mut().push(status)
+                })))
+        });
+
+        // Neither kind of load has a parsing step, so each only reports how
+        // it finished.
+        for url in &["movie.swf", "image.png", "empty.png"] {
+            let load = player.lock().unwrap().update(|uc| {
+                let root = uc.stage.root_clip();
+                uc.load_manager.load_movie_into_clip(
+                    uc.player.clone().unwrap(),
+                    root,
+                    url.to_string(),
+                    RequestOptions::get(),
+                    MovieLoadOptions::default(),
+                )
             });
-            if let Ok((length, movie)) = data {
-                let movie = Arc::new(movie);
-                if replacing_root_movie {
-                    player.lock().unwrap().set_root_movie(movie);
-                    return Ok(());
+            Player::run_loader_to_completion(&player, load).unwrap();
+        }
+
+        assert_eq!(
+            *changes.borrow(),
+            vec![
+                LoaderStatus::Succeeded,
+                LoaderStatus::Succeeded,
+                LoaderStatus::Failed
+            ]
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn streaming_and_event_sounds_load() {
+        use crate::avm1::function::{Executable, FunctionObject};
+
+        let dir = std::env::temp_dir().join(format!("ruffle-sound-loads-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        // Three MPEG-1 Layer III frames: 128kbps, 44.1kHz, joint stereo.
+        let mut mp3 = Vec::new();
+        for _ in 0..3 {
+            let mut frame = vec![0; 417];
+            frame[0..4].copy_from_slice(&[0xFF, 0xFB, 0x90, 0x44]);
+            mp3.extend_from_slice(&frame);
+        }
+        std::fs::write(dir.join("sound.mp3"), &mp3).unwrap();
+
+        let (player, mut executor) = null_player(&dir);
+
+        // Load the sound into a new `Sound` object, then report whether
+        // `onLoad` had been called before the load ran, what it reported
+        // afterwards, and whether the sound ended up playing.
+        let mut load = |is_streaming: bool, start_early: bool| {
+            let loaded_early = player.lock().unwrap().update(|uc| {
+                let mut activation =
+                    Activation::from_stub(uc.reborrow(), ActivationIdentifier::root("[Test]"));
+                let globals = activation.context.avm1.global_object_cell();
+                let sound = globals
+                    .get("Sound", &mut activation)
+                    .unwrap()
+                    .coerce_to_object(&mut activation)
+                    .construct(&mut activation, &[])
+                    .unwrap()
+                    .coerce_to_object(&mut activation);
+                let fn_proto = activation.context.avm1.prototypes().function;
+                let on_load = FunctionObject::function(
+                    activation.context.gc_context,
+                    Executable::Native(record_sound_load),
+                    Some(fn_proto),
+                    fn_proto,
+                );
+                sound
+                    .set("onLoad", on_load.into(), &mut activation)
+                    .unwrap();
+                globals.set("sound", sound.into(), &mut activation).unwrap();
+
+                let url = AvmString::new(activation.context.gc_context, "sound.mp3");
+                sound
+                    .call_method(
+                        "loadSound",
+                        &[url.into(), is_streaming.into()],
+                        &mut activation,
+                    )
+                    .unwrap();
+                if start_early {
+                    sound.call_method("start", &[], &mut activation).unwrap();
                 }
+                !matches!(
+                    sound.get("loaded", &mut activation).unwrap(),
+                    Value::Undefined
+                )
+            });
 
-                player
-                    .lock()
-                    .expect("Could not lock player!!")
-                    .update(|uc| {
-                        let (clip, broadcaster) = match uc.load_manager.get_loader(handle) {
-                            Some(Loader::Movie {
-                                target_clip,
-                                target_broadcaster,
-                                ..
-                            }) => (*target_clip, *target_broadcaster),
-                            None => return Err(Error::Cancelled),
-                            _ => unreachable!(),
-                        };
+            // The loader locks the player itself, so it mustn't be locked
+            // while the load runs.
+            executor.block_all().unwrap();
 
-                        let mut activation = Avm2Activation::from_nothing(uc.reborrow());
-                        let parent_domain = activation.avm2().global_domain();
-                        let domain = Avm2Domain::movie_domain(&mut activation, parent_domain);
-                        uc.library
-                            .library_for_movie_mut(movie.clone())
-                            .set_avm2_domain(domain);
-
-                        if let Some(broadcaster) = broadcaster {
-                            Avm1::run_stack_frame_for_method(
-                                clip,
-                                broadcaster,
-                                NEWEST_PLAYER_VERSION,
-                                uc,
-                                "broadcastMessage",
-                                &[
-                                    "onLoadProgress".into(),
-                                    Value::Object(broadcaster),
-                                    length.into(),
-                                    length.into(),
-                                ],
-                            );
-                        }
+            player.lock().unwrap().update(|uc| {
+                let mut activation =
+                    Activation::from_stub(uc.reborrow(), ActivationIdentifier::root("[Test]"));
+                let sound = activation
+                    .context
+                    .avm1
+                    .global_object_cell()
+                    .get("sound", &mut activation)
+                    .unwrap()
+                    .coerce_to_object(&mut activation);
+                let loaded = match sound.get("loaded", &mut activation).unwrap() {
+                    Value::Bool(loaded) => Some(loaded),
+                    _ => None,
+                };
+                let sound = sound.as_sound_object().unwrap();
+                assert!(!sound.is_loading());
+                (loaded_early, loaded, sound.sound_instance().is_some())
+            })
+        };
 
-                        let mut mc = clip
-                            .as_movie_clip()
-                            .expect("Attempted to load movie into not movie clip");
+        // Streaming sounds play as soon as they load.
+        assert_eq!(load(true, false), (false, Some(true), true));
+        // Event sounds wait for `start`, unless it was called while loading.
+        assert_eq!(load(false, false), (false, Some(true), false));
+        assert_eq!(load(false, true), (false, Some(true), true));
 
-                        mc.replace_with_movie(uc.gc_context, Some(movie.clone()));
-                        mc.post_instantiation(uc, clip, None, Instantiator::Movie, false);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 
-                        let mut morph_shapes = fnv::FnvHashMap::default();
-                        mc.preload(uc, &mut morph_shapes);
+    #[test]
+    fn load_vars_reports_bytes_on_completion() {
+        use crate::avm1::function::{Executable, FunctionObject};
 
-                        // Finalize morph shapes.
-                        for (id, static_data) in morph_shapes {
-                            let morph_shape = MorphShape::new(uc.gc_context, static_data);
-                            uc.library
-                                .library_for_movie_mut(movie.clone())
-                                .register_character(
-                                    id,
-                                    crate::character::Character::MorphShape(morph_shape),
-                                );
-                        }
+        let dir = std::env::temp_dir().join(format!("ruffle-load-vars-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let body = b"name=ruffle&score=100";
+        std::fs::write(dir.join("vars.txt"), body).unwrap();
 
-                        if let Some(broadcaster) = broadcaster {
-                            Avm1::run_stack_frame_for_method(
-                                clip,
-                                broadcaster,
-                                NEWEST_PLAYER_VERSION,
-                                uc,
-                                "broadcastMessage",
-                                &["onLoadComplete".into(), Value::Object(broadcaster)],
-                            );
-                        }
+        let (player, mut executor) = null_player(&dir);
 
-                        if let Some(Loader::Movie { loader_status, .. }) =
-                            uc.load_manager.get_loader_mut(handle)
-                        {
-                            *loader_status = LoaderStatus::Succeeded;
-                        };
+        let before = player.lock().unwrap().update(|uc| {
+            let mut activation =
+                Activation::from_stub(uc.reborrow(), ActivationIdentifier::root("[Test]"));
+            let globals = activation.context.avm1.global_object_cell();
+            let vars = globals
+                .get("LoadVars", &mut activation)
+                .unwrap()
+                .coerce_to_object(&mut activation)
+                .construct(&mut activation, &[])
+                .unwrap()
+                .coerce_to_object(&mut activation);
+            let fn_proto = activation.context.avm1.prototypes().function;
+            let on_data = FunctionObject::function(
+                activation.context.gc_context,
+                Executable::Native(record_bytes_on_data),
+                Some(fn_proto),
+                fn_proto,
+            );
+            vars.set("onData", on_data.into(), &mut activation).unwrap();
+            globals.set("vars", vars.into(), &mut activation).unwrap();
 
-                        Ok(())
-                    })
-            } else {
-                //TODO: Inspect the fetch error.
-                //This requires cooperation from the backend to send abstract
-                //error types we can actually inspect.
-                //This also can get errors from decoding an invalid SWF file,
-                //too. We should distinguish those to player code.
-                player
-                    .lock()
-                    .expect("Could not lock player!!")
-                    .update(|uc| -> Result<(), Error> {
-                        let (clip, broadcaster) = match uc.load_manager.get_loader(handle) {
-                            Some(Loader::Movie {
-                                target_clip,
-                                target_broadcaster,
-                                ..
-                            }) => (*target_clip, *target_broadcaster),
-                            None => return Err(Error::Cancelled),
-                            _ => unreachable!(),
-                        };
+            let url = AvmString::new(activation.context.gc_context, "vars.txt");
+            vars.call_method("load", &[url.into()], &mut activation)
+                .unwrap();
+            load_vars_bytes(&mut activation)
+        });
+        assert_eq!(before, [Some(0.0), None, None]);
 
-                        if let Some(broadcaster) = broadcaster {
-                            Avm1::run_stack_frame_for_method(
-                                clip,
-                                broadcaster,
-                                NEWEST_PLAYER_VERSION,
-                                uc,
-                                "broadcastMessage",
-                                &[
-                                    "onLoadError".into(),
-                                    Value::Object(broadcaster),
-                                    "LoadNeverCompleted".into(),
-                                ],
-                            );
-                        }
+        // The loader locks the player itself, so it mustn't be locked while
+        // the load runs.
+        executor.block_all().unwrap();
 
-                        if let Some(Loader::Movie { loader_status, .. }) =
-                            uc.load_manager.get_loader_mut(handle)
-                        {
-                            *loader_status = LoaderStatus::Failed;
-                        };
+        let after = player.lock().unwrap().update(|uc| {
+            let mut activation =
+                Activation::from_stub(uc.reborrow(), ActivationIdentifier::root("[Test]"));
+            load_vars_bytes(&mut activation)
+        });
+        let len = Some(body.len() as f64);
+        assert_eq!(after, [len, len, len]);
 
-                        Ok(())
-                    })
-            }
-        })
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 
-    pub fn form_loader(
-        &mut self,
-        player: Weak<Mutex<Player>>,
-        fetch: OwnedFuture<Vec<u8>, Error>,
-    ) -> OwnedFuture<(), Error> {
-        let handle = match self {
-            Loader::Form { self_handle, .. } => self_handle.expect("Loader not self-introduced"),
-            _ => return Box::pin(async { Err(Error::NotFormLoader) }),
-        };
+    #[test]
+    fn movies_newer_than_the_version_limit_fail_to_load() {
+        let dir = std::env::temp_dir().join(format!("ruffle-swf-version-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut swf = Vec::new();
+        swf::write_swf(&swf::Header::default_with_swf_version(32), &[], &mut swf).unwrap();
+        std::fs::write(dir.join("new.swf"), &swf).unwrap();
 
-        let player = player
-            .upgrade()
-            .expect("Could not upgrade weak reference to player");
+        let (player, _executor) = null_player(&dir);
+        let load = || load_with_movie_clip_loader(&player, "new.swf", RequestOptions::get());
 
-        Box::pin(async move {
-            let data = fetch.await?;
+        // Any version is accepted until a limit is set.
+        assert_eq!(load(), (Some(swf.len() as f64), None));
 
-            // Fire the load handler.
-            player.lock().unwrap().update(|uc| {
-                let loader = uc.load_manager.get_loader(handle);
-                let that = match loader {
-                    Some(&Loader::Form { target_object, .. }) => target_object,
-                    None => return Err(Error::Cancelled),
-                    _ => return Err(Error::NotFormLoader),
-                };
+        player
+            .lock()
+            .unwrap()
+            .update(|uc| uc.load_manager.set_max_swf_version(Some(20)));
+        assert_eq!(load(), (None, Some("LoadNeverCompleted".to_string())));
 
-                let mut activation = Activation::from_stub(
-                    uc.reborrow(),
-                    ActivationIdentifier::root("[Form Loader]"),
-                );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 
-                for (k, v) in form_urlencoded::parse(&data) {
-                    that.set(
-                        &k,
-                        AvmString::new(activation.context.gc_context, v.into_owned()).into(),
-                        &mut activation,
-                    )?;
-                }
+    #[test]
+    fn custom_content_loads_into_clips() {
+        let dir =
+            std::env::temp_dir().join(format!("ruffle-custom-content-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("level.pak"), b"PAK\x09").unwrap();
+        std::fs::write(dir.join("broken.pak"), b"PAK").unwrap();
+        std::fs::write(dir.join("image.pix"), b"PIX").unwrap();
 
-                Ok(())
-            })
-        })
-    }
+        let (player, _executor) = null_player(&dir);
+        player.lock().unwrap().update(|uc| {
+            uc.load_manager.register_content_decoder(
+                Box::new(|data| data.starts_with(b"PAK")),
+                Box::new(|data| match data.get(3) {
+                    Some(&version) => Ok(DecodedContent::Movie(Arc::new(SwfMovie::empty(version)))),
+                    None => Err("missing version".to_string()),
+                }),
+            );
+            uc.load_manager.register_content_decoder(
+                Box::new(|data| data.starts_with(b"PIX")),
+                Box::new(|_| {
+                    Ok(DecodedContent::Bitmap(Bitmap {
+                        width: 1,
+                        height: 1,
+                        data: BitmapFormat::Rgb(vec![1, 2, 3]),
+                    }))
+                }),
+            );
+        });
+        let load = |url| load_with_movie_clip_loader(&player, url, RequestOptions::get());
 
-    /// Creates a future for a LoadVars load call.
-    pub fn load_vars_loader(
-        &mut self,
-        player: Weak<Mutex<Player>>,
-        fetch: OwnedFuture<Vec<u8>, Error>,
-    ) -> OwnedFuture<(), Error> {
-        let handle = match self {
-            Loader::LoadVars { self_handle, .. } => {
-                self_handle.expect("Loader not self-introduced")
-            }
-            _ => return Box::pin(async { Err(Error::NotLoadVarsLoader) }),
-        };
+        assert_eq!(load("level.pak"), (Some(4.0), None));
+        assert_eq!(load("image.pix"), (Some(3.0), None));
+        player.lock().unwrap().update(|uc| {
+            let root = uc.stage.root_clip();
+            let bitmap = root
+                .as_container()
+                .unwrap()
+                .child_by_depth(1)
+                .and_then(|bitmap| bitmap.as_bitmap())
+                .unwrap();
+            assert_eq!((bitmap.width(), bitmap.height()), (1, 1));
+        });
 
-        let player = player
-            .upgrade()
-            .expect("Could not upgrade weak reference to player");
+        // A decoding error fails the load like an invalid movie.
+        assert_eq!(
+            load("broken.pak"),
+            (None, Some("LoadNeverCompleted".to_string()))
+        );
 
-        Box::pin(async move {
-            let data = fetch.await;
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 
-            // Fire the load handler.
-            player.lock().unwrap().update(|uc| {
-                let loader = uc.load_manager.get_loader(handle);
-                let that = match loader {
-                    Some(&Loader::LoadVars { target_object, .. }) => target_object,
-                    None => return Err(Error::Cancelled),
-                    _ => return Err(Error::NotLoadVarsLoader),
-                };
+    #[test]
+    fn instant_loads_report_their_length() {
+        use crate::avm1::function::NativeFunction;
 
-                let mut activation = Activation::from_stub(
-                    uc.reborrow(),
-                    ActivationIdentifier::root("[Form Loader]"),
-                );
+        let dir = std::env::temp_dir().join(format!("ruffle-instant-loads-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("empty.swf"), b"").unwrap();
+        let mut swf = Vec::new();
+        swf::write_swf(&swf::Header::default_with_swf_version(8), &[], &mut swf).unwrap();
 
-                match data {
-                    Ok(data) => {
-                        // Fire the onData method with the loaded string.
-                        let string_data =
-                            AvmString::new(activation.context.gc_context, UTF_8.decode(&data).0);
-                        let _ = that.call_method("onData", &[string_data.into()], &mut activation);
-                    }
-                    Err(_) => {
-                        // TODO: Log "Error opening URL" trace similar to the Flash Player?
-                        // Simulate 404 HTTP status. This should probably be fired elsewhere
-                        // because a failed local load doesn't fire a 404.
-                        let _ = that.call_method("onHTTPStatus", &[404.into()], &mut activation);
+        // A movie loaded from memory fires a single progress event with its
+        // whole length as both the loaded and total bytes.
+        let (player, _executor) = null_player(&dir);
+        let load = player.lock().unwrap().update(|uc| {
+            let mut activation =
+                Activation::from_stub(uc.reborrow(), ActivationIdentifier::root("[Test]"));
+            let listeners: [(&str, NativeFunction); 1] = [("onLoadProgress", record_load_progress)];
+            let loader = movie_clip_loader(&mut activation, &listeners);
 
-                        // Fire the onData method with no data to indicate an unsuccessful load.
-                        let _ = that.call_method("onData", &[Value::Undefined], &mut activation);
-                    }
-                }
+            let root = activation.context.stage.root_clip();
+            activation.context.load_manager.load_bytes_into_clip(
+                activation.context.player.clone().unwrap(),
+                root,
+                swf.clone(),
+                MovieLoadOptions {
+                    target_broadcaster: Some(loader),
+                    ..Default::default()
+                },
+            )
+        });
+        Player::run_loader_to_completion(&player, load).unwrap();
 
-                Ok(())
-            })
-        })
+        let progress = player.lock().unwrap().update(|uc| {
+            let mut activation =
+                Activation::from_stub(uc.reborrow(), ActivationIdentifier::root("[Test]"));
+            let loader = activation
+                .context
+                .avm1
+                .global_object_cell()
+                .get("loader", &mut activation)
+                .unwrap()
+                .coerce_to_object(&mut activation);
+            let loaded = loader.get("loaded", &mut activation).unwrap();
+            let total = loader.get("total", &mut activation).unwrap();
+            (
+                loaded.coerce_to_f64(&mut activation).unwrap(),
+                total.coerce_to_f64(&mut activation).unwrap(),
+            )
+        });
+        assert_eq!(progress, (swf.len() as f64, swf.len() as f64));
+
+        // An empty response is never a movie, so it fails without reporting
+        // progress.
+        assert_eq!(
+            load_with_movie_clip_loader(&player, "empty.swf", RequestOptions::get()),
+            (None, Some("LoadNeverCompleted".to_string()))
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 
-    /// Event handler morally equivalent to `onLoad` on a movie clip.
-    ///
-    /// Returns `true` if the loader has completed and should be removed.
-    ///
-    /// Used to fire listener events on clips and terminate completed loaders.
-    pub fn movie_clip_loaded(
-        &mut self,
-        loaded_clip: DisplayObject<'gc>,
-        clip_object: Option<Object<'gc>>,
-        queue: &mut ActionQueue<'gc>,
-    ) -> bool {
-        let (clip, broadcaster, loader_status) = match self {
-            Loader::Movie {
-                target_clip,
-                target_broadcaster,
-                loader_status,
-                ..
-            } => (*target_clip, *target_broadcaster, *loader_status),
-            _ => return false,
+    #[test]
+    fn parent_movies_use_fonts_shared_by_loaded_movies() {
+        let (player, _executor) = null_player(&std::env::temp_dir());
+        let font = swf::Font {
+            version: 2,
+            id: 1,
+            name: swf::SwfStr::from_utf8_str("Shared"),
+            language: swf::Language::Unknown,
+            layout: None,
+            glyphs: vec![swf::Glyph {
+                shape_records: vec![],
+                code: u16::from(b'a'),
+                advance: Some(0),
+                bounds: None,
+            }],
+            is_small_text: false,
+            is_shift_jis: false,
+            is_ansi: false,
+            is_bold: false,
+            is_italic: false,
         };
+        let tags = [
+            swf::Tag::DefineFont2(Box::new(font)),
+            swf::Tag::ExportAssets(vec![swf::ExportedAsset {
+                id: 1,
+                name: swf::SwfStr::from_utf8_str("Shared"),
+            }]),
+        ];
+        let mut font_swf = Vec::new();
+        swf::write_swf(
+            &swf::Header::default_with_swf_version(8),
+            &tags,
+            &mut font_swf,
+        )
+        .unwrap();
 
-        if !DisplayObject::ptr_eq(loaded_clip, clip) {
-            return false;
-        }
+        // Text in the parent movie is laid out with the font it finds by name.
+        let parent_font = |player: &Arc<Mutex<Player>>| {
+            player.lock().unwrap().update(|uc| {
+                let parent = uc.stage.root_clip().movie().unwrap();
+                uc.library
+                    .font_by_name_or_shared(parent, "Shared", false, false)
+                    .map(|font| font.has_glyphs())
+            })
+        };
 
-        match loader_status {
-            LoaderStatus::Pending => false,
-            LoaderStatus::Failed => true,
-            LoaderStatus::Succeeded => {
-                if let Some(broadcaster) = broadcaster {
-                    queue.queue_actions(
-                        clip,
-                        ActionType::Method {
-                            object: broadcaster,
-                            name: "broadcastMessage",
-                            args: vec![
-                                "onLoadInit".into(),
-                                clip_object.map(|co| co.into()).unwrap_or(Value::Undefined),
-                            ],
-                        },
-                        false,
-                    );
-                }
-                true
-            }
+        for share_loaded_fonts in &[false, true] {
+            let load = player.lock().unwrap().update(|uc| {
+                uc.load_manager.set_share_loaded_fonts(*share_loaded_fonts);
+                let root = uc.stage.root_clip();
+                let child: DisplayObject<'_> =
+                    MovieClip::new(SwfSlice::empty(root.movie().unwrap()), uc.gc_context).into();
+                root.as_container().unwrap().replace_at_depth(uc, child, 1);
+                uc.load_manager.load_bytes_into_clip(
+                    uc.player.clone().unwrap(),
+                    child,
+                    font_swf.clone(),
+                    MovieLoadOptions::default(),
+                )
+            });
+            Player::run_loader_to_completion(&player, load).unwrap();
+
+            let expected = if *share_loaded_fonts {
+                Some(true)
+            } else {
+                None
+            };
+            assert_eq!(parent_font(&player), expected);
         }
     }
 
-    pub fn xml_loader(
-        &mut self,
-        player: Weak<Mutex<Player>>,
-        fetch: OwnedFuture<Vec<u8>, Error>,
-    ) -> OwnedFuture<(), Error> {
-        let handle = match self {
-            Loader::Xml { self_handle, .. } => self_handle.expect("Loader not self-introduced"),
-            _ => return Box::pin(async { Err(Error::NotXmlLoader) }),
-        };
+    #[test]
+    fn clips_cannot_be_loaded_into_their_own_ancestors() {
+        let (player, _executor) = null_player(&std::env::temp_dir());
 
-        let player = player
-            .upgrade()
-            .expect("Could not upgrade weak reference to player");
+        let load = player.lock().unwrap().update(|uc| {
+            let root = uc.stage.root_clip();
+            let movie = root.movie().unwrap();
+            let parent: DisplayObject<'_> =
+                MovieClip::new(SwfSlice::empty(movie.clone()), uc.gc_context).into();
+            let child: DisplayObject<'_> =
+                MovieClip::new(SwfSlice::empty(movie), uc.gc_context).into();
+            parent.set_name(uc.gc_context, "parent");
+            root.as_container().unwrap().replace_at_depth(uc, parent, 1);
+            parent
+                .as_container()
+                .unwrap()
+                .replace_at_depth(uc, child, 1);
 
-        Box::pin(async move {
-            let data = fetch.await;
-            if let Ok(data) = data {
-                let xmlstring = String::from_utf8(data)?;
+            assert!(matches!(
+                check_load_target(parent, Some(child)),
+                Err(Error::InvalidLoadTarget(name, "it contains the clip loading into it"))
+                    if name == "parent"
+            ));
+            // Clips may load over themselves and their children, and anything
+            // may replace the root movie.
+            assert!(check_load_target(child, Some(child)).is_ok());
+            assert!(check_load_target(child, Some(parent)).is_ok());
+            assert!(check_load_target(parent, None).is_ok());
+            assert!(check_load_target(root, Some(child)).is_ok());
 
-                player.lock().expect("Could not lock player!!").update(
-                    |uc| -> Result<(), Error> {
-                        let (mut node, active_clip) = match uc.load_manager.get_loader(handle) {
-                            Some(Loader::Xml {
-                                target_node,
-                                active_clip,
-                                ..
-                            }) => (*target_node, *active_clip),
-                            None => return Err(Error::Cancelled),
-                            _ => unreachable!(),
-                        };
+            let load = uc.load_manager.load_movie_into_clip(
+                uc.player.clone().unwrap(),
+                parent,
+                "http://example.com/movie.swf".to_string(),
+                RequestOptions::get(),
+                MovieLoadOptions {
+                    requesting_clip: Some(child),
+                    ..Default::default()
+                },
+            );
+            assert!(uc.load_manager.active_loaders().is_empty());
 
-                        let object =
-                            node.script_object(uc.gc_context, Some(uc.avm1.prototypes().xml_node));
-                        Avm1::run_stack_frame_for_method(
-                            active_clip,
-                            object,
-                            NEWEST_PLAYER_VERSION,
-                            uc,
-                            "onHTTPStatus",
-                            &[200.into()],
-                        );
+            // A clip that is its own ancestor can never be loaded into.
+            parent.set_parent(uc.gc_context, Some(child));
+            assert!(matches!(
+                check_load_target(parent, None),
+                Err(Error::InvalidLoadTarget(_, "it is contained within itself"))
+            ));
+            parent.set_parent(uc.gc_context, Some(root));
 
-                        Avm1::run_stack_frame_for_method(
-                            active_clip,
-                            object,
-                            NEWEST_PLAYER_VERSION,
-                            uc,
-                            "onData",
-                            &[AvmString::new(uc.gc_context, xmlstring).into()],
-                        );
+            load
+        });
 
-                        Ok(())
-                    },
-                )?;
-            } else {
-                player.lock().expect("Could not lock player!!").update(
-                    |uc| -> Result<(), Error> {
-                        let (mut node, active_clip) = match uc.load_manager.get_loader(handle) {
-                            Some(Loader::Xml {
-                                target_node,
-                                active_clip,
-                                ..
-                            }) => (*target_node, *active_clip),
-                            None => return Err(Error::Cancelled),
-                            _ => unreachable!(),
-                        };
+        let (mut executor, sender) = crate::backend::navigator::NullExecutor::new();
+        sender.send(load).unwrap();
+        assert!(matches!(
+            executor.block_all(),
+            Err(Error::InvalidLoadTarget(..))
+        ));
+    }
 
-                        let object =
-                            node.script_object(uc.gc_context, Some(uc.avm1.prototypes().xml_node));
+    #[test]
+    fn invalid_movies_fire_on_load_complete_before_on_load_error() {
+        use crate::avm1::function::NativeFunction;
 
-                        Avm1::run_stack_frame_for_method(
-                            active_clip,
-                            object,
-                            NEWEST_PLAYER_VERSION,
-                            uc,
-                            "onHTTPStatus",
-                            &[404.into()],
-                        );
+        let dir = std::env::temp_dir().join(format!("ruffle-invalid-movie-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("broken.swf"), b"FWS\x0a\x20\x00\x00\x00").unwrap();
 
-                        Avm1::run_stack_frame_for_method(
-                            active_clip,
-                            object,
-                            NEWEST_PLAYER_VERSION,
-                            uc,
-                            "onData",
-                            &[],
-                        );
+        let traces = Rc::new(RefCell::new(Vec::new()));
+        let (player, _executor) = null_player_with_log(&dir, Box::new(TraceLog(traces.clone())));
+        let load = |url: &str| {
+            traces.borrow_mut().clear();
+            let load = player.lock().unwrap().update(|uc| {
+                let mut activation =
+                    Activation::from_stub(uc.reborrow(), ActivationIdentifier::root("[Test]"));
+                let listeners: [(&str, NativeFunction); 2] = [
+                    ("onLoadComplete", trace_load_complete),
+                    ("onLoadError", trace_load_error),
+                ];
+                let loader = movie_clip_loader(&mut activation, &listeners);
 
-                        Ok(())
+                let root = activation.context.stage.root_clip();
+                activation.context.load_manager.load_movie_into_clip(
+                    activation.context.player.clone().unwrap(),
+                    root,
+                    url.to_string(),
+                    RequestOptions::get(),
+                    MovieLoadOptions {
+                        target_broadcaster: Some(loader),
+                        ..Default::default()
                     },
-                )?;
-            }
+                )
+            });
+            Player::run_loader_to_completion(&player, load).unwrap();
+            traces.borrow().clone()
+        };
 
-            Ok(())
-        })
+        // The server sent the movie, so the load completed before the movie
+        // turned out to be unusable.
+        assert_eq!(
+            load("broken.swf"),
+            vec!["onLoadComplete 200", "onLoadError LoadNeverCompleted 200"]
+        );
+        // A load that never reaches the server only fails.
+        assert_eq!(
+            load("missing.swf"),
+            vec!["onLoadError LoadNeverCompleted 0"]
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 }
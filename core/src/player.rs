@@ -9,7 +9,7 @@ use crate::backend::{
     audio::{AudioBackend, AudioManager},
     locale::LocaleBackend,
     log::LogBackend,
-    navigator::{NavigatorBackend, RequestOptions},
+    navigator::{NavigatorBackend, NullExecutor, OwnedFuture, RedirectPolicy, RequestOptions},
     render::RenderBackend,
     storage::StorageBackend,
     ui::{MouseCursor, UiBackend},
@@ -26,7 +26,10 @@ use crate::external::Value as ExternalValue;
 use crate::external::{ExternalInterface, ExternalInterfaceProvider};
 use crate::focus_tracker::FocusTracker;
 use crate::library::Library;
-use crate::loader::LoadManager;
+use crate::loader::{
+    Error as LoadError, LoadManager, LoaderKind, LoaderStatus, RetryPolicy, SpoofedHeader,
+    UrlDecision,
+};
 use crate::prelude::*;
 use crate::string::AvmString;
 use crate::tag_utils::SwfMovie;
@@ -357,11 +360,10 @@ impl Player {
         on_metadata: Box<dyn FnOnce(&swf::HeaderExt)>,
     ) {
         self.mutate_with_update_context(|context| {
-            let fetch = context.navigator.fetch(movie_url, RequestOptions::get());
             let process = context.load_manager.load_root_movie(
                 context.player.clone().unwrap(),
-                fetch,
                 movie_url.to_string(),
+                RequestOptions::get(),
                 parameters,
                 on_metadata,
             );
@@ -370,6 +372,47 @@ impl Player {
         });
     }
 
+    /// Run a loader's future to completion without an executor.
+    ///
+    /// This is intended for tests and headless tooling. Frames are run while
+    /// the load is in progress, and afterwards until every movie loaded into
+    /// a clip has been initialized. Fails with `LoadError::LoadTimedOut` if
+    /// this takes longer than the player's maximum execution duration, so
+    /// that content which never finishes loading can't hang the caller.
+    pub fn run_loader_to_completion(
+        player: &Arc<Mutex<Self>>,
+        future: OwnedFuture<(), LoadError>,
+    ) -> Result<(), LoadError> {
+        let (mut executor, channel) = NullExecutor::new();
+        channel
+            .send(future)
+            .expect("Executor should still be receiving futures");
+
+        let start = Instant::now();
+        let max_duration = player.lock().unwrap().max_execution_duration();
+
+        loop {
+            // The loader locks the player itself, so it mustn't be locked
+            // while polling.
+            executor.poll_all()?;
+
+            let awaiting_init = player.lock().unwrap().update(|context| {
+                context.load_manager.active_loaders().iter().any(|loader| {
+                    loader.kind == LoaderKind::Movie && loader.status == LoaderStatus::Succeeded
+                })
+            });
+            if !executor.has_work() && !awaiting_init {
+                return Ok(());
+            }
+
+            if start.elapsed() >= max_duration {
+                return Err(LoadError::LoadTimedOut);
+            }
+
+            player.lock().unwrap().run_frame();
+        }
+    }
+
     /// Change the root movie.
     ///
     /// This should only be called once, as it makes no attempt at removing
@@ -1304,6 +1347,84 @@ impl Player {
         &self.ui
     }
 
+    pub fn navigator(&self) -> &Navigator {
+        &self.navigator
+    }
+
+    /// Set how failed fetches made by loaders should be retried.
+    pub fn set_load_retry_policy(&mut self, retry_policy: Option<RetryPolicy>) {
+        self.mutate_with_update_context(|context| {
+            context.load_manager.set_retry_policy(retry_policy);
+        });
+    }
+
+    /// Set how many loaded movies should be kept around for reuse.
+    ///
+    /// A capacity of zero disables the cache.
+    pub fn set_movie_cache_capacity(&mut self, capacity: usize) {
+        self.mutate_with_update_context(|context| {
+            context
+                .load_manager
+                .movie_cache_mut()
+                .set_capacity(capacity);
+        });
+    }
+
+    /// Set a callback that may rewrite or block the URL of any load.
+    pub fn set_url_filter(&mut self, url_filter: Option<Box<dyn Fn(&str) -> UrlDecision>>) {
+        self.mutate_with_update_context(|context| {
+            context.load_manager.set_url_filter(url_filter);
+        });
+    }
+
+    /// Cancel every in-flight load, such as when the player is being reset.
+    pub fn abort_all_loads(&mut self) {
+        self.mutate_with_update_context(|context| {
+            context.load_manager.abort_all();
+        });
+    }
+
+    /// Set which redirects fetches made by the loaders may follow.
+    pub fn set_redirect_policy(&mut self, redirect_policy: RedirectPolicy) {
+        self.mutate_with_update_context(|context| {
+            context.load_manager.set_redirect_policy(redirect_policy);
+        });
+    }
+
+    /// Set the largest response body fetches made by the loaders may receive.
+    pub fn set_max_response_size(&mut self, max_response_size: Option<usize>) {
+        self.mutate_with_update_context(|context| {
+            context
+                .load_manager
+                .set_max_response_size(max_response_size);
+        });
+    }
+
+    /// Set the `User-Agent` header to send with every fetch made by the
+    /// loaders.
+    pub fn set_user_agent(&mut self, user_agent: Option<String>) {
+        self.mutate_with_update_context(|context| {
+            context.load_manager.set_user_agent(user_agent);
+        });
+    }
+
+    /// Set the headers to add to every fetch made by the loaders.
+    pub fn set_spoofed_headers(&mut self, spoofed_headers: Vec<SpoofedHeader>) {
+        self.mutate_with_update_context(|context| {
+            context.load_manager.set_spoofed_headers(spoofed_headers);
+        });
+    }
+
+    /// Set whether cross-origin data and movie loads must be permitted by the
+    /// target server's `crossdomain.xml`.
+    pub fn set_check_policy_files(&mut self, check_policy_files: bool) {
+        self.mutate_with_update_context(|context| {
+            context
+                .load_manager
+                .set_check_policy_files(check_policy_files);
+        });
+    }
+
     pub fn ui_mut(&mut self) -> &mut Ui {
         &mut self.ui
     }
@@ -1611,6 +1732,7 @@ impl Player {
             let rval = func(context);
 
             Self::run_actions(context);
+            context.load_manager.run_pending_inits();
 
             rval
         });
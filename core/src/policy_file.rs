@@ -0,0 +1,120 @@
+//! Cross-domain policy files
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+/// A parsed `crossdomain.xml` policy file.
+///
+/// Policy files list the domains whose movies are allowed to load data from
+/// the server that serves them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PolicyFile {
+    /// The `domain` attribute of every `allow-access-from` entry.
+    allowed_domains: Vec<String>,
+}
+
+impl PolicyFile {
+    /// Parse a policy file.
+    ///
+    /// Returns `None` if the data is not well-formed XML or is not a
+    /// `cross-domain-policy` document.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let mut reader = Reader::from_reader(data);
+        let mut buf = Vec::new();
+        let mut is_policy = false;
+        let mut allowed_domains = Vec::new();
+
+        loop {
+            match reader.read_event(&mut buf).ok()? {
+                Event::Start(bs) | Event::Empty(bs) => match bs.name() {
+                    b"cross-domain-policy" => is_policy = true,
+                    b"allow-access-from" if is_policy => {
+                        for attribute in bs.attributes() {
+                            let attribute = attribute.ok()?;
+                            if attribute.key == b"domain" {
+                                let domain = attribute.unescaped_value().ok()?;
+                                allowed_domains.push(String::from_utf8(domain.to_vec()).ok()?);
+                            }
+                        }
+                    }
+                    _ => {}
+                },
+                Event::Eof => break,
+                _ => {}
+            }
+
+            buf.clear();
+        }
+
+        if is_policy {
+            Some(Self { allowed_domains })
+        } else {
+            None
+        }
+    }
+
+    /// Check if movies served from the given host may load data covered by
+    /// this policy.
+    ///
+    /// Entries may be `*` to allow every domain, or start with `*.` to allow
+    /// a domain and all of its subdomains.
+    pub fn allows(&self, host: &str) -> bool {
+        let host = host.to_ascii_lowercase();
+
+        self.allowed_domains.iter().any(|domain| {
+            let domain = domain.trim().to_ascii_lowercase();
+
+            if domain == "*" {
+                true
+            } else if let Some(parent) = domain.strip_prefix("*.") {
+                host == parent || host.ends_with(&format!(".{}", parent))
+            } else {
+                host == domain
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PolicyFile;
+
+    #[test]
+    fn allows_listed_domains() {
+        let policy = PolicyFile::parse(
+            br#"<?xml version="1.0"?>
+            <cross-domain-policy>
+                <allow-access-from domain="www.example.com" />
+                <allow-access-from domain="*.example.org" />
+            </cross-domain-policy>"#,
+        )
+        .unwrap();
+
+        assert!(policy.allows("www.example.com"));
+        assert!(policy.allows("WWW.EXAMPLE.COM"));
+        assert!(!policy.allows("example.com"));
+        assert!(policy.allows("example.org"));
+        assert!(policy.allows("cdn.games.example.org"));
+        assert!(!policy.allows("badexample.org"));
+        assert!(!policy.allows("evil.com"));
+    }
+
+    #[test]
+    fn wildcard_allows_everything() {
+        let policy = PolicyFile::parse(
+            br#"<cross-domain-policy><allow-access-from domain="*"/></cross-domain-policy>"#,
+        )
+        .unwrap();
+
+        assert!(policy.allows("anywhere.com"));
+    }
+
+    #[test]
+    fn rejects_non_policy_documents() {
+        assert_eq!(PolicyFile::parse(b"<html><body>404</body></html>"), None);
+        assert_eq!(PolicyFile::parse(b"<cross-domain-policy"), None);
+        assert!(!PolicyFile::parse(b"<cross-domain-policy/>")
+            .unwrap()
+            .allows("example.com"));
+    }
+}
@@ -1,20 +1,73 @@
 //! Navigator backend for web
 
 use crate::custom_event::RuffleEvent;
-use isahc::{config::RedirectPolicy, prelude::*, AsyncReadResponseExt, HttpClient, Request};
+use futures_lite::AsyncReadExt;
+use isahc::{config::RedirectPolicy as HttpRedirectPolicy, prelude::*, HttpClient, Request};
 use ruffle_core::backend::navigator::{
-    NavigationMethod, NavigatorBackend, OwnedFuture, RequestOptions,
+    NavigationMethod, NavigatorBackend, OwnedFuture, RedirectPolicy, RequestOptions, Response,
 };
 use ruffle_core::indexmap::IndexMap;
 use ruffle_core::loader::Error;
 use std::borrow::Cow;
 use std::fs;
+use std::future::Future;
+use std::pin::Pin;
 use std::rc::Rc;
 use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
 use std::time::{Duration, Instant};
 use url::Url;
 use winit::event_loop::EventLoopProxy;
 
+/// How many redirects are followed when checking each one against a policy.
+const MAX_REDIRECTS: u32 = 20;
+
+/// Shared state between a `SleepFuture` and the thread waiting on its behalf.
+#[derive(Default)]
+struct SleepState {
+    done: bool,
+    waker: Option<Waker>,
+}
+
+/// A future that completes once a background thread has waited out its
+/// duration.
+struct SleepFuture(Arc<Mutex<SleepState>>);
+
+impl SleepFuture {
+    fn new(duration: Duration) -> Self {
+        let state = Arc::new(Mutex::new(SleepState::default()));
+        let thread_state = state.clone();
+
+        thread::spawn(move || {
+            thread::sleep(duration);
+
+            let mut state = thread_state.lock().unwrap();
+            state.done = true;
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        });
+
+        Self(state)
+    }
+}
+
+impl Future for SleepFuture {
+    type Output = Result<(), Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.0.lock().unwrap();
+        if state.done {
+            Poll::Ready(Ok(()))
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
 /// Implementation of `NavigatorBackend` for non-web environments that can call
 /// out to a web browser.
 pub struct ExternalNavigatorBackend {
@@ -49,7 +102,7 @@ impl ExternalNavigatorBackend {
         let proxy = proxy.and_then(|url| url.as_str().parse().ok());
         let builder = HttpClient::builder()
             .proxy(proxy)
-            .redirect_policy(RedirectPolicy::Follow);
+            .redirect_policy(HttpRedirectPolicy::Follow);
 
         let client = builder.build().ok().map(Rc::new);
 
@@ -111,7 +164,7 @@ impl NavigatorBackend for ExternalNavigatorBackend {
         };
     }
 
-    fn fetch(&self, url: &str, options: RequestOptions) -> OwnedFuture<Vec<u8>, Error> {
+    fn fetch(&self, url: &str, options: RequestOptions) -> OwnedFuture<Response, Error> {
         // TODO: honor sandbox type (local-with-filesystem, local-with-network, remote, ...)
         let full_url = match self.movie_url.clone().join(url) {
             Ok(url) => url,
@@ -127,44 +180,158 @@ impl NavigatorBackend for ExternalNavigatorBackend {
 
         match processed_url.scheme() {
             "file" => Box::pin(async move {
-                fs::read(processed_url.to_file_path().unwrap_or_default())
-                    .map_err(Error::NetworkError)
+                let body = fs::read(processed_url.to_file_path().unwrap_or_default())
+                    .map_err(Error::NetworkError)?;
+
+                Ok(Response {
+                    url: processed_url.to_string(),
+                    status: 200,
+                    headers: vec![],
+                    body,
+                })
             }),
             _ => Box::pin(async move {
                 let client = client.ok_or(Error::NetworkUnavailable)?;
 
-                let request = match options.method() {
-                    NavigationMethod::Get => Request::get(processed_url.to_string()),
-                    NavigationMethod::Post => Request::post(processed_url.to_string()),
+                let redirect_policy = options.redirect_policy();
+                let (body_data, _) = options.body().clone().unwrap_or_default();
+                let mut request_url = processed_url.clone();
+                let mut redirects = 0;
+
+                let mut response = loop {
+                    let mut request = match options.method() {
+                        NavigationMethod::Get => Request::get(request_url.to_string()),
+                        NavigationMethod::Post => Request::post(request_url.to_string()),
+                    };
+
+                    if redirect_policy != RedirectPolicy::FollowAll {
+                        // Redirects are followed below, so that each one can
+                        // be checked against the policy.
+                        request = request.redirect_policy(HttpRedirectPolicy::None);
+                    }
+
+                    for (name, value) in options.headers() {
+                        request = request.header(name.as_str(), value.as_str());
+                    }
+
+                    let body = request
+                        .body(body_data.clone())
+                        .map_err(|e| Error::FetchError(e.to_string()))?;
+
+                    let response = client
+                        .send_async(body)
+                        .await
+                        .map_err(|e| Error::FetchError(e.to_string()))?;
+
+                    if redirect_policy == RedirectPolicy::FollowAll
+                        || !response.status().is_redirection()
+                    {
+                        break response;
+                    }
+
+                    let location = response
+                        .headers()
+                        .get(isahc::http::header::LOCATION)
+                        .and_then(|location| location.to_str().ok())
+                        .and_then(|location| request_url.join(location).ok());
+
+                    match location {
+                        Some(location)
+                            if redirects < MAX_REDIRECTS
+                                && redirect_policy.allows(&processed_url, &location) =>
+                        {
+                            request_url = location;
+                            redirects += 1;
+                        }
+                        Some(location) => {
+                            return Err(Error::FetchError(format!(
+                                "Redirect from {} to {} is not allowed",
+                                processed_url, location
+                            )));
+                        }
+                        None => break response,
+                    }
                 };
 
-                let (body_data, _) = options.body().clone().unwrap_or_default();
-                let body = request
-                    .body(body_data)
-                    .map_err(|e| Error::FetchError(e.to_string()))?;
+                let url = response
+                    .effective_uri()
+                    .map(|uri| uri.to_string())
+                    .unwrap_or_else(|| request_url.to_string());
+                let status = response.status().as_u16();
+                // The body has already been decompressed by the HTTP client.
+                let headers = response
+                    .headers()
+                    .iter()
+                    .filter(|(name, _)| **name != isahc::http::header::CONTENT_ENCODING)
+                    .map(|(name, value)| {
+                        (
+                            name.as_str().to_string(),
+                            String::from_utf8_lossy(value.as_bytes()).into_owned(),
+                        )
+                    })
+                    .collect();
+
+                // Byte ranges refer to the encoded body, so only unencoded
+                // responses can be resumed from where they left off.
+                let resumable = response.status().is_success()
+                    && response
+                        .headers()
+                        .get(isahc::http::header::CONTENT_ENCODING)
+                        .is_none();
+
+                let declared_size = response
+                    .headers()
+                    .get(isahc::http::header::CONTENT_LENGTH)
+                    .and_then(|length| length.to_str().ok())
+                    .and_then(|length| length.parse().ok());
+                if let Some(declared_size) = declared_size {
+                    options.check_response_size(declared_size)?;
+                }
+
+                // Read one byte past the limit to tell if it was exceeded.
+                let mut body = vec![];
+                let read = match options.max_response_size() {
+                    Some(limit) => {
+                        response
+                            .body_mut()
+                            .take(limit as u64 + 1)
+                            .read_to_end(&mut body)
+                            .await
+                    }
+                    None => response.body_mut().read_to_end(&mut body).await,
+                };
+                if let Err(e) = read {
+                    return Err(if resumable && !body.is_empty() {
+                        Error::FetchInterrupted {
+                            status,
+                            body,
+                            message: e.to_string(),
+                        }
+                    } else {
+                        Error::FetchError(e.to_string())
+                    });
+                }
 
-                let mut response = client
-                    .send_async(body)
-                    .await
-                    .map_err(|e| Error::FetchError(e.to_string()))?;
+                options.check_response_size(body.len())?;
 
                 if !response.status().is_success() {
-                    return Err(Error::FetchError(format!(
-                        "HTTP status is not ok, got {}",
-                        response.status()
-                    )));
+                    return Err(Error::HttpNotOk { status, body });
                 }
 
-                let mut buffer = vec![];
-                response
-                    .copy_to(&mut buffer)
-                    .await
-                    .map_err(|e| Error::FetchError(e.to_string()))?;
-                Ok(buffer)
+                Ok(Response {
+                    url,
+                    status,
+                    headers,
+                    body,
+                })
             }),
         }
     }
 
+    fn sleep(&self, duration: Duration) -> OwnedFuture<(), Error> {
+        Box::pin(SleepFuture::new(duration))
+    }
+
     fn time_since_launch(&mut self) -> Duration {
         Instant::now().duration_since(self.start_time)
     }
@@ -1,5 +1,5 @@
 use clipboard::{ClipboardContext, ClipboardProvider};
-use ruffle_core::backend::ui::{MouseCursor, UiBackend};
+use ruffle_core::backend::ui::{InvalidMovieReason, MouseCursor, UiBackend};
 use ruffle_core::events::{KeyCode, PlayerEvent};
 use std::collections::HashSet;
 use std::rc::Rc;
@@ -79,6 +79,8 @@ https://github.com/ruffle-rs/ruffle/wiki/Frequently-Asked-Questions-For-Users";
 
 const DOWNLOAD_FAILED_MESSAGE: &str = "Ruffle failed to open or download this file.";
 
+const NOT_SWF_MESSAGE: &str = "This file is not a Flash movie, or is damaged.";
+
 impl UiBackend for DesktopUiBackend {
     fn is_key_down(&self, key: KeyCode) -> bool {
         match key {
@@ -242,6 +244,18 @@ impl UiBackend for DesktopUiBackend {
         );
     }
 
+    fn display_root_movie_invalid_message(&self, reason: InvalidMovieReason) {
+        let message = match reason {
+            InvalidMovieReason::NotSwf => NOT_SWF_MESSAGE.to_string(),
+            InvalidMovieReason::UnsupportedVersion(version) => format!(
+                "This Flash movie requires version {} of Flash Player, which doesn't exist yet, \
+                and could not be read.",
+                version
+            ),
+        };
+        message_box_ok("Ruffle - Invalid movie", &message, MessageBoxIcon::Warning);
+    }
+
     fn message(&self, message: &str) {
         message_box_ok("Ruffle", message, MessageBoxIcon::Info)
     }
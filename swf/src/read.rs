@@ -73,7 +73,10 @@ pub fn decompress_swf<'a, R: Read + 'a>(mut input: R) -> Result<SwfBuf> {
             }
             // Uncompressed length includes the 4-byte header and 4-byte uncompressed length itself,
             // subtract it here.
-            make_lzma_reader(input, uncompressed_len - 8)?
+            let uncompressed_len = uncompressed_len
+                .checked_sub(8)
+                .ok_or_else(|| Error::invalid_data("Invalid LZMA SWF length."))?;
+            make_lzma_reader(input, uncompressed_len)?
         }
     };
 
@@ -2677,6 +2680,20 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn read_truncated_lzma_swf() {
+        if cfg!(feature = "lzma") {
+            let data = std::fs::read("tests/swfs/lzma.swf").unwrap();
+            for len in [3, 8, 12, 17, data.len() / 2] {
+                // Must not panic, regardless of whether parsing succeeds.
+                let _ = decompress_swf(&data[..len]);
+            }
+        }
+
+        // A header claiming fewer bytes than the header itself.
+        assert!(decompress_swf(&b"ZWS\x0d\x04\x00\x00\x00"[..]).is_err());
+    }
+
     #[test]
     fn read_invalid_swf() {
         let junk = [0u8; 128];
@@ -10,24 +10,34 @@ use ruffle_core::backend::{
     audio::NullAudioBackend,
     locale::NullLocaleBackend,
     log::LogBackend,
-    navigator::{NullExecutor, NullNavigatorBackend},
+    navigator::{
+        NavigationMethod, NavigatorBackend, NullExecutor, NullNavigatorBackend, OwnedFuture,
+        RequestOptions, Response,
+    },
     render::NullRenderer,
     storage::{MemoryStorageBackend, StorageBackend},
-    ui::NullUiBackend,
+    ui::{InvalidMovieReason, MouseCursor, NullUiBackend, UiBackend},
     video::NullVideoBackend,
 };
 use ruffle_core::context::UpdateContext;
+use ruffle_core::events::KeyCode;
 use ruffle_core::external::Value as ExternalValue;
 use ruffle_core::external::{ExternalInterfaceMethod, ExternalInterfaceProvider};
+use ruffle_core::indexmap::IndexMap;
+use ruffle_core::loader::{Error as LoadError, MovieLoadOptions, RetryPolicy, UrlDecision};
+use ruffle_core::swf::HeaderExt;
 use ruffle_core::tag_utils::SwfMovie;
+use ruffle_core::url::Url;
 use ruffle_core::Player;
 use ruffle_render_wgpu::target::TextureTarget;
 use ruffle_render_wgpu::wgpu;
 use ruffle_render_wgpu::WgpuRenderBackend;
+use std::borrow::Cow;
 use std::cell::RefCell;
 use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::sync::mpsc::Sender;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
@@ -860,6 +870,666 @@ fn stage_scale_mode() -> Result<(), Error> {
     )
 }
 
+#[test]
+fn run_loader_to_completion() -> Result<(), Error> {
+    set_logger();
+    let swf_path = "tests/swfs/avm1/action_to_integer/test.swf";
+    let movie = SwfMovie::from_path(swf_path, None)?;
+
+    // No executor is needed to drive the load.
+    let (_executor, channel) = NullExecutor::new();
+    let player = Player::new(
+        Box::new(NullRenderer),
+        Box::new(NullAudioBackend::new()),
+        Box::new(NullNavigatorBackend::with_base_path(
+            Path::new(swf_path).parent().unwrap(),
+            channel,
+        )),
+        Box::new(MemoryStorageBackend::default()),
+        Box::new(NullLocaleBackend::new()),
+        Box::new(NullVideoBackend::new()),
+        Box::new(TestLogBackend::new(Rc::new(RefCell::new(Vec::new())))),
+        Box::new(NullUiBackend::new()),
+    )?;
+
+    let load = player.lock().unwrap().update(|context| {
+        context.load_manager.load_root_movie(
+            context.player.clone().unwrap(),
+            "test.swf".to_string(),
+            RequestOptions::get(),
+            vec![],
+            Box::new(|_| {}),
+        )
+    });
+    Player::run_loader_to_completion(&player, load)?;
+
+    assert_eq!(
+        player.lock().unwrap().movie_width(),
+        movie.width().to_pixels() as u32
+    );
+    Ok(())
+}
+
+#[test]
+fn invalid_root_movie_is_reported() -> Result<(), Error> {
+    set_logger();
+    let base_path = Path::new("tests/swfs/avm1/action_to_integer");
+    let messages = Rc::new(RefCell::new(Vec::new()));
+
+    let (_executor, channel) = NullExecutor::new();
+    let player = Player::new(
+        Box::new(NullRenderer),
+        Box::new(NullAudioBackend::new()),
+        Box::new(NullNavigatorBackend::with_base_path(base_path, channel)),
+        Box::new(MemoryStorageBackend::default()),
+        Box::new(NullLocaleBackend::new()),
+        Box::new(NullVideoBackend::new()),
+        Box::new(TestLogBackend::new(Rc::new(RefCell::new(Vec::new())))),
+        Box::new(TestUiBackend::new(messages.clone())),
+    )?;
+
+    // The expected output of another test is certainly not a SWF.
+    let load = player.lock().unwrap().update(|context| {
+        context.load_manager.load_root_movie(
+            context.player.clone().unwrap(),
+            "output.txt".to_string(),
+            RequestOptions::get(),
+            vec![],
+            Box::new(|_| {}),
+        )
+    });
+    assert!(Player::run_loader_to_completion(&player, load).is_err());
+    assert_eq!(*messages.borrow(), vec![InvalidMovieReason::NotSwf]);
+
+    Ok(())
+}
+
+/// A navigator that answers fetches with canned results, in order.
+///
+/// Every fetched URL is recorded. Fetches beyond the canned results fail.
+/// Like a real navigator, responses that declare a `Content-Length` over the
+/// request's size limit fail without their body being read.
+struct MockNavigatorBackend {
+    results: Vec<Result<Response, LoadError>>,
+    fetched: Rc<RefCell<Vec<String>>>,
+    channel: Sender<OwnedFuture<(), LoadError>>,
+}
+
+impl NavigatorBackend for MockNavigatorBackend {
+    fn navigate_to_url(
+        &self,
+        _url: String,
+        _window: Option<String>,
+        _vars_method: Option<(NavigationMethod, IndexMap<String, String>)>,
+    ) {
+    }
+
+    fn fetch(&self, url: &str, options: RequestOptions) -> OwnedFuture<Response, LoadError> {
+        let mut fetched = self.fetched.borrow_mut();
+        fetched.push(url.to_string());
+        let result = match self.results.get(fetched.len() - 1) {
+            Some(Ok(response)) => {
+                let declared_size = response
+                    .header("Content-Length")
+                    .and_then(|length| length.parse().ok());
+                match declared_size {
+                    Some(size) => options.check_response_size(size),
+                    None => Ok(()),
+                }
+                .map(|()| Response {
+                    url: response.url.clone(),
+                    status: response.status,
+                    headers: response.headers.clone(),
+                    body: response.body.clone(),
+                })
+            }
+            Some(Err(LoadError::FetchError(message))) => {
+                Err(LoadError::FetchError(message.clone()))
+            }
+            Some(Err(LoadError::HttpNotOk { status, body })) => Err(LoadError::HttpNotOk {
+                status: *status,
+                body: body.clone(),
+            }),
+            Some(Err(e)) => Err(LoadError::FetchError(e.to_string())),
+            None => Err(LoadError::FetchError(format!("No response for {}", url))),
+        };
+
+        Box::pin(async move { result })
+    }
+
+    fn sleep(&self, _duration: Duration) -> OwnedFuture<(), LoadError> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn time_since_launch(&mut self) -> Duration {
+        Duration::from_millis(0)
+    }
+
+    fn spawn_future(&mut self, future: OwnedFuture<(), LoadError>) {
+        let _ = self.channel.send(future);
+    }
+
+    fn resolve_relative_url<'a>(&self, url: &'a str) -> Cow<'a, str> {
+        match Url::parse("http://example.com/").and_then(|base| base.join(url)) {
+            Ok(url) => String::from(url).into(),
+            Err(_) => url.into(),
+        }
+    }
+
+    fn pre_process_url(&self, url: Url) -> Url {
+        url
+    }
+}
+
+/// A successful response to a mock fetch.
+fn mock_response(url: &str, body: Vec<u8>) -> Response {
+    Response {
+        url: url.to_string(),
+        status: 200,
+        headers: vec![],
+        body,
+    }
+}
+
+/// Build a player whose fetches are answered with the given results.
+///
+/// Relative URLs resolve against `http://example.com/`. Returns the player,
+/// the URLs it fetched and the executor that runs its spawned futures.
+#[allow(clippy::type_complexity)]
+fn mock_player(
+    results: Vec<Result<Response, LoadError>>,
+) -> Result<(Arc<Mutex<Player>>, Rc<RefCell<Vec<String>>>, NullExecutor), Error> {
+    let fetched = Rc::new(RefCell::new(Vec::new()));
+    let (executor, channel) = NullExecutor::new();
+    let player = Player::new(
+        Box::new(NullRenderer),
+        Box::new(NullAudioBackend::new()),
+        Box::new(MockNavigatorBackend {
+            results,
+            fetched: fetched.clone(),
+            channel,
+        }),
+        Box::new(MemoryStorageBackend::default()),
+        Box::new(NullLocaleBackend::new()),
+        Box::new(NullVideoBackend::new()),
+        Box::new(TestLogBackend::new(Rc::new(RefCell::new(Vec::new())))),
+        Box::new(NullUiBackend::new()),
+    )?;
+
+    Ok((player, fetched, executor))
+}
+
+/// Start loading a root movie from the given URL.
+fn load_root_movie(player: &Arc<Mutex<Player>>, url: &str) -> OwnedFuture<(), LoadError> {
+    player.lock().unwrap().update(|context| {
+        context.load_manager.load_root_movie(
+            context.player.clone().unwrap(),
+            url.to_string(),
+            RequestOptions::get(),
+            vec![],
+            Box::new(|_| {}),
+        )
+    })
+}
+
+/// Start loading a movie from the given URL into the root clip.
+fn load_movie_into_root(player: &Arc<Mutex<Player>>, url: &str) -> OwnedFuture<(), LoadError> {
+    load_movie_with_options(player, url, RequestOptions::get())
+}
+
+/// Start loading a movie into the root clip with the given request options.
+fn load_movie_with_options(
+    player: &Arc<Mutex<Player>>,
+    url: &str,
+    options: RequestOptions,
+) -> OwnedFuture<(), LoadError> {
+    player.lock().unwrap().update(|context| {
+        let root = context.stage.root_clip();
+        context.load_manager.load_movie_into_clip(
+            context.player.clone().unwrap(),
+            root,
+            url.to_string(),
+            options,
+            MovieLoadOptions::default(),
+        )
+    })
+}
+
+#[test]
+fn cached_movies_are_not_fetched_again() -> Result<(), Error> {
+    set_logger();
+    let swf_path = "tests/swfs/avm1/action_to_integer/test.swf";
+    let url = "http://example.com/test.swf";
+    let (player, fetched, _executor) =
+        mock_player(vec![Ok(mock_response(url, std::fs::read(swf_path)?))])?;
+    player.lock().unwrap().set_movie_cache_capacity(4);
+    player
+        .lock()
+        .unwrap()
+        .set_root_movie(Arc::new(SwfMovie::from_path(swf_path, None)?));
+
+    for _ in 0..2 {
+        let load = load_movie_into_root(&player, url);
+        Player::run_loader_to_completion(&player, load)?;
+    }
+
+    assert_eq!(*fetched.borrow(), vec![url.to_string()]);
+    Ok(())
+}
+
+#[test]
+fn transient_fetch_failures_are_retried() -> Result<(), Error> {
+    set_logger();
+    let swf_path = "tests/swfs/avm1/action_to_integer/test.swf";
+    let movie = SwfMovie::from_path(swf_path, None)?;
+    let url = "http://example.com/test.swf";
+
+    let (player, fetched, _executor) = mock_player(vec![
+        Err(LoadError::FetchError("Connection reset".to_string())),
+        Err(LoadError::FetchError("Connection reset".to_string())),
+        Ok(mock_response(url, std::fs::read(swf_path)?)),
+    ])?;
+    player.lock().unwrap().update(|context| {
+        context.load_manager.set_retry_policy(Some(RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(10),
+        }))
+    });
+
+    let load = load_root_movie(&player, url);
+    Player::run_loader_to_completion(&player, load)?;
+
+    assert_eq!(fetched.borrow().len(), 3);
+    assert_eq!(
+        player.lock().unwrap().movie_width(),
+        movie.width().to_pixels() as u32
+    );
+    Ok(())
+}
+
+#[test]
+fn http_errors_are_not_retried() -> Result<(), Error> {
+    set_logger();
+    let url = "http://example.com/test.swf";
+    let (player, fetched, _executor) = mock_player(vec![
+        Err(LoadError::HttpNotOk {
+            status: 404,
+            body: vec![],
+        }),
+        Ok(mock_response(
+            url,
+            std::fs::read("tests/swfs/avm1/action_to_integer/test.swf")?,
+        )),
+    ])?;
+    player.lock().unwrap().update(|context| {
+        context.load_manager.set_retry_policy(Some(RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(10),
+        }))
+    });
+
+    let load = load_root_movie(&player, url);
+    assert!(Player::run_loader_to_completion(&player, load).is_err());
+
+    assert_eq!(*fetched.borrow(), vec![url.to_string()]);
+    assert_eq!(root_movie_url(&player), None);
+    Ok(())
+}
+
+#[test]
+fn child_metadata_is_reported_for_movies_only() -> Result<(), Error> {
+    set_logger();
+    let swf_path = "tests/swfs/avm1/action_to_integer/test.swf";
+    let movie = SwfMovie::from_path(swf_path, None)?;
+    // A 1x1 transparent GIF.
+    let gif = b"GIF89a\x01\0\x01\0\x80\0\0\xff\xff\xff\0\0\0\
+        !\xf9\x04\x01\0\0\0\0,\0\0\0\0\x01\0\x01\0\0\x02\x02D\x01\0;";
+    let (player, _fetched, _executor) = mock_player(vec![
+        Ok(mock_response("http://example.com/image.gif", gif.to_vec())),
+        Ok(mock_response(
+            "http://example.com/test.swf",
+            std::fs::read(swf_path)?,
+        )),
+    ])?;
+    player
+        .lock()
+        .unwrap()
+        .set_root_movie(Arc::new(SwfMovie::from_path(swf_path, None)?));
+
+    let frame_rates = Rc::new(RefCell::new(Vec::new()));
+    for url in &[
+        "http://example.com/image.gif",
+        "http://example.com/test.swf",
+    ] {
+        let frame_rates = frame_rates.clone();
+        let load = player.lock().unwrap().update(|context| {
+            let root = context.stage.root_clip();
+            context.load_manager.load_movie_into_clip(
+                context.player.clone().unwrap(),
+                root,
+                url.to_string(),
+                RequestOptions::get(),
+                MovieLoadOptions {
+                    on_metadata: Some(Box::new(move |header: &HeaderExt| {
+                        frame_rates.borrow_mut().push(header.frame_rate().to_f64())
+                    })),
+                    ..Default::default()
+                },
+            )
+        });
+        Player::run_loader_to_completion(&player, load)?;
+    }
+
+    assert_eq!(
+        *frame_rates.borrow(),
+        vec![movie.header().frame_rate().to_f64()]
+    );
+    Ok(())
+}
+
+/// The URL of the root movie.
+///
+/// A movie loaded into the root clip replaces the root movie, so this tells
+/// whether such a load went ahead.
+fn root_movie_url(player: &Arc<Mutex<Player>>) -> Option<String> {
+    player
+        .lock()
+        .unwrap()
+        .update(|context| context.swf.url().map(|url| url.to_string()))
+}
+
+/// A policy file that lets movies from `domain` load data.
+fn policy_file(url: &str, domain: &str) -> Response {
+    let policy = format!(
+        r#"<cross-domain-policy><allow-access-from domain="{}"/></cross-domain-policy>"#,
+        domain
+    );
+    mock_response(url, policy.into_bytes())
+}
+
+#[test]
+fn cross_origin_loads_need_a_policy_file() -> Result<(), Error> {
+    set_logger();
+    let swf_path = "tests/swfs/avm1/action_to_integer/test.swf";
+    let swf = std::fs::read(swf_path)?;
+    let root_url = "http://example.com/main.swf";
+    let url = "http://other.example.net/test.swf";
+    let policy_url = "http://other.example.net/crossdomain.xml";
+    let redirect_url = "http://third.example.org/test.swf";
+    let redirect_policy_url = "http://third.example.org/crossdomain.xml";
+
+    let cases = vec![
+        // Allowed by name.
+        (
+            vec![
+                Ok(policy_file(policy_url, "example.com")),
+                Ok(mock_response(url, swf.clone())),
+            ],
+            vec![policy_url, url],
+            url,
+        ),
+        // Allowed by a subdomain wildcard.
+        (
+            vec![
+                Ok(policy_file(policy_url, "*.example.com")),
+                Ok(mock_response(url, swf.clone())),
+            ],
+            vec![policy_url, url],
+            url,
+        ),
+        // Denied, so the movie is never fetched.
+        (
+            vec![Ok(policy_file(policy_url, "example.org"))],
+            vec![policy_url],
+            root_url,
+        ),
+        // A missing policy file denies the load.
+        (
+            vec![Err(LoadError::HttpNotOk {
+                status: 404,
+                body: vec![],
+            })],
+            vec![policy_url],
+            root_url,
+        ),
+        // A redirect to another origin needs that origin's permission too.
+        (
+            vec![
+                Ok(policy_file(policy_url, "*")),
+                Ok(mock_response(redirect_url, swf.clone())),
+                Ok(policy_file(redirect_policy_url, "example.org")),
+            ],
+            vec![policy_url, url, redirect_policy_url],
+            root_url,
+        ),
+        (
+            vec![
+                Ok(policy_file(policy_url, "*")),
+                Ok(mock_response(redirect_url, swf.clone())),
+                Ok(policy_file(redirect_policy_url, "example.com")),
+            ],
+            vec![policy_url, url, redirect_policy_url],
+            redirect_url,
+        ),
+    ];
+
+    for (results, expected_fetches, loaded_url) in cases {
+        let (player, fetched, _executor) = mock_player(results)?;
+        player.lock().unwrap().set_check_policy_files(true);
+        player
+            .lock()
+            .unwrap()
+            .set_root_movie(Arc::new(SwfMovie::from_data(
+                &swf,
+                Some(root_url.to_string()),
+                None,
+            )?));
+
+        let load = load_movie_into_root(&player, url);
+        Player::run_loader_to_completion(&player, load)?;
+        std::assert_eq!(root_movie_url(&player).as_deref(), Some(loaded_url));
+        std::assert_eq!(*fetched.borrow(), expected_fetches);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn cached_movies_pass_the_url_filter_and_policy_file() -> Result<(), Error> {
+    set_logger();
+    let swf_path = "tests/swfs/avm1/action_to_integer/test.swf";
+    let swf = std::fs::read(swf_path)?;
+    let url = "http://example.com/test.swf";
+    let mirror_url = "http://mirror.example.com/test.swf";
+    let (player, fetched, _executor) = mock_player(vec![
+        Ok(mock_response(url, swf.clone())),
+        Ok(mock_response(mirror_url, swf.clone())),
+        Ok(policy_file(
+            "http://example.com/crossdomain.xml",
+            "example.org",
+        )),
+    ])?;
+    player.lock().unwrap().set_movie_cache_capacity(4);
+    player
+        .lock()
+        .unwrap()
+        .set_root_movie(Arc::new(SwfMovie::from_data(
+            &swf,
+            Some("http://example.com/main.swf".to_string()),
+            None,
+        )?));
+
+    let load = load_movie_into_root(&player, url);
+    Player::run_loader_to_completion(&player, load)?;
+    std::assert_eq!(root_movie_url(&player).as_deref(), Some(url));
+
+    // A rewritten URL is fetched, rather than served from the cache.
+    player
+        .lock()
+        .unwrap()
+        .set_url_filter(Some(Box::new(move |filtered| {
+            if filtered == url {
+                UrlDecision::RewriteTo(mirror_url.to_string())
+            } else {
+                UrlDecision::Allow
+            }
+        })));
+    let load = load_movie_into_root(&player, url);
+    Player::run_loader_to_completion(&player, load)?;
+    std::assert_eq!(root_movie_url(&player).as_deref(), Some(mirror_url));
+
+    // A blocked URL isn't loaded, even though it is cached.
+    player
+        .lock()
+        .unwrap()
+        .set_url_filter(Some(Box::new(move |filtered| {
+            if filtered == url {
+                UrlDecision::Deny
+            } else {
+                UrlDecision::Allow
+            }
+        })));
+    let load = load_movie_into_root(&player, url);
+    Player::run_loader_to_completion(&player, load)?;
+    std::assert_eq!(root_movie_url(&player).as_deref(), Some(mirror_url));
+
+    // Neither is a cached movie from an origin that denies the root movie.
+    player.lock().unwrap().set_url_filter(None);
+    player.lock().unwrap().set_check_policy_files(true);
+    player
+        .lock()
+        .unwrap()
+        .set_root_movie(Arc::new(SwfMovie::from_data(
+            &swf,
+            Some("http://example.net/main.swf".to_string()),
+            None,
+        )?));
+    let load = load_movie_into_root(&player, url);
+    Player::run_loader_to_completion(&player, load)?;
+    std::assert_eq!(
+        root_movie_url(&player).as_deref(),
+        Some("http://example.net/main.swf")
+    );
+
+    std::assert_eq!(
+        *fetched.borrow(),
+        vec![url, mirror_url, "http://example.com/crossdomain.xml"]
+    );
+    Ok(())
+}
+
+#[test]
+fn oversized_responses_fail_the_load() -> Result<(), Error> {
+    set_logger();
+    let swf_path = "tests/swfs/avm1/action_to_integer/test.swf";
+    let swf = std::fs::read(swf_path)?;
+    let url = "http://example.com/test.swf";
+    let root_url = "http://example.com/main.swf";
+
+    let mut declared = mock_response(url, vec![]);
+    declared
+        .headers
+        .push(("Content-Length".to_string(), swf.len().to_string()));
+    let (player, fetched, _executor) = mock_player(vec![
+        Ok(mock_response(url, swf.clone())),
+        Ok(declared),
+        Ok(mock_response(url, swf.clone())),
+    ])?;
+    player
+        .lock()
+        .unwrap()
+        .set_max_response_size(Some(swf.len() - 1));
+    player
+        .lock()
+        .unwrap()
+        .set_root_movie(Arc::new(SwfMovie::from_data(
+            &swf,
+            Some(root_url.to_string()),
+            None,
+        )?));
+
+    // The first body is only found to be too large once it has arrived, and
+    // the second declares its size up front.
+    for _ in 0..2 {
+        let load = load_movie_into_root(&player, url);
+        Player::run_loader_to_completion(&player, load)?;
+        std::assert_eq!(root_movie_url(&player).as_deref(), Some(root_url));
+    }
+
+    // A request's own limit takes precedence.
+    let mut options = RequestOptions::get();
+    options.set_max_response_size(Some(swf.len()));
+    let load = load_movie_with_options(&player, url, options);
+    Player::run_loader_to_completion(&player, load)?;
+    std::assert_eq!(root_movie_url(&player).as_deref(), Some(url));
+
+    std::assert_eq!(fetched.borrow().len(), 3);
+    Ok(())
+}
+
+#[test]
+fn not_modified_responses_fail_the_load() -> Result<(), Error> {
+    set_logger();
+    let swf_path = "tests/swfs/avm1/action_to_integer/test.swf";
+    let swf = std::fs::read(swf_path)?;
+    let url = "http://example.com/test.swf";
+    let root_url = "http://example.com/main.swf";
+
+    // There is no HTTP cache to supply the body a 304 refers to, so the load
+    // fails even if the server sends a movie anyway.
+    let mut not_modified = mock_response(url, swf.clone());
+    not_modified.status = 304;
+    let (player, _fetched, _executor) = mock_player(vec![Ok(not_modified)])?;
+    player
+        .lock()
+        .unwrap()
+        .set_root_movie(Arc::new(SwfMovie::from_data(
+            &swf,
+            Some(root_url.to_string()),
+            None,
+        )?));
+
+    let load = load_movie_into_root(&player, url);
+    Player::run_loader_to_completion(&player, load)?;
+    std::assert_eq!(root_movie_url(&player).as_deref(), Some(root_url));
+    Ok(())
+}
+
+#[test]
+fn required_verification_skips_the_movie_cache() -> Result<(), Error> {
+    set_logger();
+    let swf_path = "tests/swfs/avm1/action_to_integer/test.swf";
+    let swf = std::fs::read(swf_path)?;
+    let url = "http://example.com/test.swf";
+    let root_url = "http://example.com/main.swf";
+    let (player, fetched, _executor) = mock_player(vec![Ok(mock_response(url, swf.clone()))])?;
+    player.lock().unwrap().set_movie_cache_capacity(4);
+
+    let load = load_movie_into_root(&player, url);
+    Player::run_loader_to_completion(&player, load)?;
+    std::assert_eq!(root_movie_url(&player).as_deref(), Some(url));
+
+    // The cached copy was never verified, so it can't stand in for a load
+    // that must be.
+    player
+        .lock()
+        .unwrap()
+        .set_root_movie(Arc::new(SwfMovie::from_data(
+            &swf,
+            Some(root_url.to_string()),
+            None,
+        )?));
+    player.lock().unwrap().update(|context| {
+        context.load_manager.set_require_verified_movies(true);
+    });
+    let load = load_movie_into_root(&player, url);
+    Player::run_loader_to_completion(&player, load)?;
+    std::assert_eq!(root_movie_url(&player).as_deref(), Some(root_url));
+
+    std::assert_eq!(*fetched.borrow(), vec![url.to_string()]);
+    Ok(())
+}
+
 /// Wrapper around string slice that makes debug output `{:?}` to print string same way as `{}`.
 /// Used in different `assert*!` macros in combination with `pretty_assertions` crate to make
 /// test failures to show nice diffs.
@@ -1135,6 +1805,57 @@ impl LogBackend for TestLogBackend {
     }
 }
 
+/// A UI backend that records which invalid root movie messages were shown.
+struct TestUiBackend {
+    invalid_movie_messages: Rc<RefCell<Vec<InvalidMovieReason>>>,
+}
+
+impl TestUiBackend {
+    pub fn new(invalid_movie_messages: Rc<RefCell<Vec<InvalidMovieReason>>>) -> Self {
+        Self {
+            invalid_movie_messages,
+        }
+    }
+}
+
+impl UiBackend for TestUiBackend {
+    fn is_key_down(&self, _key: KeyCode) -> bool {
+        false
+    }
+
+    fn last_key_code(&self) -> KeyCode {
+        KeyCode::Unknown
+    }
+
+    fn last_key_char(&self) -> Option<char> {
+        None
+    }
+
+    fn mouse_visible(&self) -> bool {
+        true
+    }
+
+    fn set_mouse_visible(&mut self, _visible: bool) {}
+
+    fn set_mouse_cursor(&mut self, _cursor: MouseCursor) {}
+
+    fn set_clipboard_content(&mut self, _content: String) {}
+
+    fn is_fullscreen(&self) -> bool {
+        false
+    }
+
+    fn display_unsupported_message(&self) {}
+
+    fn display_root_movie_download_failed_message(&self) {}
+
+    fn display_root_movie_invalid_message(&self, reason: InvalidMovieReason) {
+        self.invalid_movie_messages.borrow_mut().push(reason);
+    }
+
+    fn message(&self, _message: &str) {}
+}
+
 #[derive(Default)]
 pub struct ExternalInterfaceTestProvider {}
 
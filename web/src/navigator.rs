@@ -1,7 +1,8 @@
 //! Navigator backend for web
-use js_sys::{Array, ArrayBuffer, Uint8Array};
+use js_sys::{Array, ArrayBuffer, Promise, Uint8Array};
 use ruffle_core::backend::navigator::{
-    url_from_relative_url, NavigationMethod, NavigatorBackend, OwnedFuture, RequestOptions,
+    url_from_relative_url, NavigationMethod, NavigatorBackend, OwnedFuture, RedirectPolicy,
+    RequestOptions, Response as FetchResponse,
 };
 use ruffle_core::indexmap::IndexMap;
 use ruffle_core::loader::Error;
@@ -11,7 +12,8 @@ use url::Url;
 use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::{spawn_local, JsFuture};
 use web_sys::{
-    window, Blob, BlobPropertyBag, Document, Performance, Request, RequestInit, Response,
+    window, Blob, BlobPropertyBag, Document, Performance, Request, RequestInit, RequestRedirect,
+    Response,
 };
 
 pub struct WebNavigatorBackend {
@@ -170,7 +172,7 @@ impl NavigatorBackend for WebNavigatorBackend {
         Duration::from_millis(dt as u64)
     }
 
-    fn fetch(&self, url: &str, options: RequestOptions) -> OwnedFuture<Vec<u8>, Error> {
+    fn fetch(&self, url: &str, options: RequestOptions) -> OwnedFuture<FetchResponse, Error> {
         let url = if let Ok(parsed_url) = Url::parse(url) {
             self.pre_process_url(parsed_url).to_string()
         } else {
@@ -185,6 +187,15 @@ impl NavigatorBackend for WebNavigatorBackend {
                 NavigationMethod::Post => "POST",
             });
 
+            // The browser can't check redirects one at a time, so same-origin
+            // redirects are checked against the final URL below.
+            init.redirect(match options.redirect_policy() {
+                RedirectPolicy::FollowAll | RedirectPolicy::FollowSameOrigin => {
+                    RequestRedirect::Follow
+                }
+                RedirectPolicy::None => RequestRedirect::Error,
+            });
+
             if let Some((data, mime)) = options.body() {
                 let arraydata = ArrayBuffer::new(data.len() as u32);
                 let u8data = Uint8Array::new(&arraydata);
@@ -211,6 +222,12 @@ impl NavigatorBackend for WebNavigatorBackend {
             let request = Request::new_with_str_and_init(&url, &init)
                 .map_err(|_| Error::FetchError(format!("Unable to create request for {}", url)))?;
 
+            // Browsers refuse to let pages set some headers, such as
+            // `Referer` and `User-Agent`; those are silently dropped.
+            for (name, value) in options.headers() {
+                let _ = request.headers().append(name, value);
+            }
+
             let window = web_sys::window().unwrap();
             let fetchval = JsFuture::from(window.fetch_with_request(&request)).await;
             if fetchval.is_err() {
@@ -222,11 +239,25 @@ impl NavigatorBackend for WebNavigatorBackend {
 
             let resp: Response = fetchval.unwrap().dyn_into().unwrap();
 
-            if !resp.ok() {
-                return Err(Error::FetchError(format!(
-                    "HTTP status is not ok, got {}",
-                    resp.status_text()
-                )));
+            if resp.redirected() {
+                if let (Ok(from), Ok(to)) = (Url::parse(&url), Url::parse(&resp.url())) {
+                    if !options.redirect_policy().allows(&from, &to) {
+                        return Err(Error::FetchError(format!(
+                            "Redirect from {} to {} is not allowed",
+                            from, to
+                        )));
+                    }
+                }
+            }
+
+            let declared_size = resp
+                .headers()
+                .get("Content-Length")
+                .ok()
+                .flatten()
+                .and_then(|length| length.parse().ok());
+            if let Some(declared_size) = declared_size {
+                options.check_response_size(declared_size)?;
             }
 
             let data: ArrayBuffer = JsFuture::from(resp.array_buffer().unwrap())
@@ -238,10 +269,57 @@ impl NavigatorBackend for WebNavigatorBackend {
                 .unwrap();
 
             let jsarray = Uint8Array::new(&data);
-            let mut rust_array = vec![0; jsarray.length() as usize];
-            jsarray.copy_to(&mut rust_array);
+            // Don't copy an oversized body into the module's memory.
+            options.check_response_size(jsarray.length() as usize)?;
+            let mut body = vec![0; jsarray.length() as usize];
+            jsarray.copy_to(&mut body);
+
+            if !resp.ok() {
+                return Err(Error::HttpNotOk {
+                    status: resp.status(),
+                    body,
+                });
+            }
+
+            let mut headers = vec![];
+            if let Ok(Some(entries)) = js_sys::try_iter(&resp.headers()) {
+                for entry in entries.flatten() {
+                    let entry: Array = entry.unchecked_into();
+                    if let (Some(name), Some(value)) =
+                        (entry.get(0).as_string(), entry.get(1).as_string())
+                    {
+                        // The browser has already decompressed the body.
+                        if !name.eq_ignore_ascii_case("Content-Encoding") {
+                            headers.push((name, value));
+                        }
+                    }
+                }
+            }
+
+            Ok(FetchResponse {
+                url: resp.url(),
+                status: resp.status(),
+                headers,
+                body,
+            })
+        })
+    }
+
+    fn sleep(&self, duration: Duration) -> OwnedFuture<(), Error> {
+        let promise = Promise::new(&mut |resolve, _reject| {
+            let window = web_sys::window().expect("window()");
+            let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(
+                &resolve,
+                duration.as_millis() as i32,
+            );
+        });
+
+        Box::pin(async move {
+            JsFuture::from(promise)
+                .await
+                .map_err(|_| Error::FetchError("Could not wait for timeout".to_string()))?;
 
-            Ok(rust_array)
+            Ok(())
         })
     }
 
@@ -1,5 +1,5 @@
 use super::JavascriptPlayer;
-use ruffle_core::backend::ui::{MouseCursor, UiBackend};
+use ruffle_core::backend::ui::{InvalidMovieReason, MouseCursor, UiBackend};
 use ruffle_core::events::KeyCode;
 use ruffle_web_common::JsResult;
 use std::collections::HashSet;
@@ -210,6 +210,20 @@ impl UiBackend for WebUiBackend {
         self.js_player.display_root_movie_download_failed_message()
     }
 
+    fn display_root_movie_invalid_message(&self, reason: InvalidMovieReason) {
+        let message = match reason {
+            InvalidMovieReason::NotSwf => {
+                "This file is not a Flash movie, or is damaged.".to_string()
+            }
+            InvalidMovieReason::UnsupportedVersion(version) => format!(
+                "This Flash movie requires version {} of Flash Player, which doesn't exist yet, \
+                and could not be read.",
+                version
+            ),
+        };
+        self.js_player.display_message(&message);
+    }
+
     fn message(&self, message: &str) {
         self.js_player.display_message(message);
     }